@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{any::Any, borrow::Cow, future::Future, time::Duration};
 
 use actix_rt::ArbiterHandle;
 use futures_core::stream::Stream;
@@ -6,10 +6,12 @@ use log::error;
 
 use crate::{
     address::{channel, Addr},
-    context::Context,
+    clock::{Instant, MissedTickBehavior},
+    context::{Context, StartReady},
+    context_impl::ContextFut,
     context_items::{ActorDelayedMessageItem, ActorMessageItem, ActorMessageStreamItem},
-    fut::{ActorFuture, ActorStreamExt},
-    handler::{Handler, Message},
+    fut::{wrap_future, ActorFuture, ActorFutureExt, ActorStreamExt},
+    handler::{Handler, Message, ResponseActFuture},
     mailbox::DEFAULT_CAPACITY,
     stream::StreamHandler,
     utils::{IntervalFunc, TimerFunc},
@@ -93,6 +95,40 @@ pub trait Actor: Sized + Unpin + 'static {
         Running::Stop
     }
 
+    /// Returns a future to run before the actor is stopped, for asynchronous cleanup.
+    ///
+    /// Called once, after [`stopping`](Actor::stopping) returned `Running::Stop`. The context
+    /// no longer processes messages or spawned futures at this point, but keeps polling the
+    /// returned future to completion before calling [`stopped`](Actor::stopped) and dropping the
+    /// actor. Use it to flush buffers or close connections that need to be awaited. There is
+    /// no time limit; wrap the future with [`timeout`](crate::fut::ActorFutureExt::timeout) to
+    /// bound it. The future is not run if the context is terminated or dropped, e.g. because
+    /// its arbiter stopped, and it is only awaited by [`Context`]-based actors.
+    ///
+    /// ```
+    /// # use actix::prelude::*;
+    /// # use std::time::Duration;
+    /// struct Writer {
+    ///     pending: Vec<String>,
+    /// }
+    ///
+    /// impl Actor for Writer {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn finalize(&mut self, _: &mut Self::Context) -> Option<ResponseActFuture<Self, ()>> {
+    ///         let pending = std::mem::take(&mut self.pending);
+    ///         Some(Box::pin(fut::wrap_future(async move {
+    ///             // write `pending` out
+    ///             actix_rt::time::sleep(Duration::from_millis(10)).await;
+    ///             drop(pending);
+    ///         })))
+    ///     }
+    /// }
+    /// ```
+    fn finalize(&mut self, ctx: &mut Self::Context) -> Option<ResponseActFuture<Self, ()>> {
+        None
+    }
+
     /// Called after an actor is stopped.
     ///
     /// This method can be used to perform any needed cleanup work or
@@ -100,6 +136,30 @@ pub trait Actor: Sized + Unpin + 'static {
     /// method got called, the actor will be dropped.
     fn stopped(&mut self, ctx: &mut Self::Context) {}
 
+    /// Returns whether panics in message handlers are caught instead of unwinding the actor.
+    ///
+    /// When this returns `true`, a panic in [`Handler::handle`] for a message taken from the
+    /// mailbox is caught and passed to [`on_panic`](Actor::on_panic); the requester, if any,
+    /// receives [`MailboxError::Closed`](crate::MailboxError::Closed). Panics in futures
+    /// returned by handlers, in spawned futures and in lifecycle methods are not caught.
+    ///
+    /// The handler is treated as unwind safe. A panic can leave the actor half way through an
+    /// update, so only opt in if every handler keeps the actor's state consistent at each point
+    /// where it may panic, or if [`on_panic`](Actor::on_panic) resets the affected state. This
+    /// also requires the `panic = "unwind"` strategy.
+    fn catch_unwind(&self) -> bool {
+        false
+    }
+
+    /// Called with the panic payload when a message handler panicked and
+    /// [`catch_unwind`](Actor::catch_unwind) returned `true`.
+    ///
+    /// Returning `Running::Continue` keeps the actor handling messages; `Running::Stop`, the
+    /// default, stops it as if [`ActorContext::stop`] was called.
+    fn on_panic(&mut self, payload: Box<dyn Any + Send>, ctx: &mut Self::Context) -> Running {
+        Running::Stop
+    }
+
     /// Start a new asynchronous actor, returning its address.
     ///
     /// # Examples
@@ -126,6 +186,84 @@ pub trait Actor: Sized + Unpin + 'static {
         Context::new().run(self)
     }
 
+    /// Start a new asynchronous actor, returning a future that resolves to its address once the
+    /// actor is ready.
+    ///
+    /// The actor counts as ready when [`started`](Actor::started) has run and the futures it
+    /// registered with [`wait`](AsyncContext::wait) have completed, i.e. when it would handle
+    /// its first mailbox message. This lets callers await asynchronous setup before relying on
+    /// the actor. If the actor stops before getting ready, the future still resolves and the
+    /// returned address is disconnected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use actix::prelude::*;
+    ///
+    /// struct Cache {
+    ///     loaded: bool,
+    /// }
+    ///
+    /// impl Actor for Cache {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn started(&mut self, ctx: &mut Self::Context) {
+    ///         async {}
+    ///             .into_actor(self)
+    ///             .map(|_, act, _| act.loaded = true)
+    ///             .wait(ctx);
+    ///     }
+    /// }
+    ///
+    /// #[actix::main]
+    /// async fn main() {
+    ///     let _addr = Cache { loaded: false }.start_ready().await;
+    ///     # System::current().stop();
+    /// }
+    /// ```
+    fn start_ready(self) -> StartReady<Self>
+    where
+        Self: Actor<Context = Context<Self>>,
+    {
+        Context::new().run_ready(self)
+    }
+
+    /// Start a new asynchronous actor with a name, returning its address.
+    ///
+    /// The name is available through [`Context::name`] and tells apart log output of actors of
+    /// the same type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use actix::prelude::*;
+    ///
+    /// struct Session;
+    ///
+    /// impl Actor for Session {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn started(&mut self, ctx: &mut Self::Context) {
+    ///         assert_eq!(ctx.name(), "session-1");
+    ///     }
+    /// }
+    ///
+    /// #[actix::main]
+    /// async fn main() {
+    ///     let addr = Session.start_named("session-1");
+    ///     # System::current().stop();
+    /// }
+    /// ```
+    fn start_named<N>(self, name: N) -> Addr<Self>
+    where
+        Self: Actor<Context = Context<Self>>,
+        N: Into<Cow<'static, str>>,
+    {
+        let mut ctx = Context::new();
+        ctx.set_name(name);
+        ctx.run(self)
+    }
+
     /// Construct and start a new asynchronous actor, returning its
     /// address.
     ///
@@ -190,6 +328,42 @@ pub trait Actor: Sized + Unpin + 'static {
         let act = f(&mut ctx);
         ctx.run(act)
     }
+
+    /// Create a new asynchronous actor without spawning it, returning its address and the future
+    /// running it.
+    ///
+    /// The actor only makes progress while the caller polls the future, which completes once
+    /// the actor has stopped. This allows running actors on a custom executor, or stepping
+    /// through their message handling in tests. Timers and other Tokio resources used by the
+    /// actor still need a Tokio runtime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use actix::prelude::*;
+    ///
+    /// struct MyActor;
+    ///
+    /// impl Actor for MyActor {
+    ///     type Context = Context<Self>;
+    /// }
+    ///
+    /// # #[actix::main]
+    /// # async fn main() {
+    /// let (addr, fut) = MyActor::into_future(|_| MyActor);
+    /// actix::spawn(fut);
+    /// # }
+    /// ```
+    fn into_future<F>(f: F) -> (Addr<Self>, ContextFut<Self, Context<Self>>)
+    where
+        Self: Actor<Context = Context<Self>>,
+        F: FnOnce(&mut Context<Self>) -> Self,
+    {
+        let mut ctx = Context::new();
+        let act = f(&mut ctx);
+        let fut = ctx.into_future(act);
+        (fut.address(), fut)
+    }
 }
 
 #[allow(unused_variables)]
@@ -283,6 +457,42 @@ where
     where
         F: ActorFuture<A, Output = ()> + 'static;
 
+    /// Spawns a plain future into the context.
+    ///
+    /// This is a shorthand for [`spawn`](AsyncContext::spawn) with the future wrapped by
+    /// [`wrap_future`](crate::fut::wrap_future), for work that does not need access to the actor
+    /// or its context. The output of the future is discarded. Like other spawned futures, it can
+    /// be cancelled through the returned handle and is cancelled when the actor stops.
+    ///
+    /// ```
+    /// # use actix::prelude::*;
+    /// # use std::time::Duration;
+    /// struct MyActor;
+    ///
+    /// impl Actor for MyActor {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn started(&mut self, ctx: &mut Self::Context) {
+    ///         ctx.spawn_future(async {
+    ///             actix_rt::time::sleep(Duration::from_millis(10)).await;
+    ///             System::current().stop();
+    ///         });
+    ///     }
+    /// }
+    ///
+    /// # fn main() {
+    /// let sys = System::new();
+    /// sys.block_on(async { MyActor.start() });
+    /// sys.run().unwrap();
+    /// # }
+    /// ```
+    fn spawn_future<F>(&mut self, fut: F) -> SpawnHandle
+    where
+        F: Future + 'static,
+    {
+        self.spawn(wrap_future(fut).map(|_, _, _| ()))
+    }
+
     /// Spawns a future into the context, waiting for it to resolve.
     ///
     /// This stops processing any incoming events until the future
@@ -403,6 +613,11 @@ where
     /// Sends the message `msg` to self. This bypasses the mailbox capacity, and
     /// will always queue the message. If the actor is in the `stopped` state, an
     /// error will be raised.
+    ///
+    /// Notifications do not go through the mailbox and never overtake messages waiting there:
+    /// a notification is handled once the mailbox is empty, or once the actor has handled as
+    /// many mailbox messages in a row as its [budget](crate::Context::set_mailbox_budget)
+    /// allows. Notifications are handled in the order they were sent.
     fn notify<M>(&mut self, msg: M)
     where
         A: Handler<M>,
@@ -435,6 +650,25 @@ where
         }
     }
 
+    /// Sends the message `msg` to self at the given `deadline`.
+    ///
+    /// Like [`notify_later`](AsyncContext::notify_later), but takes an absolute point in time,
+    /// so schedules computed ahead of time stay accurate regardless of when they are
+    /// registered. A deadline that has already passed delivers the message as soon as the
+    /// context is polled next.
+    fn notify_at<M>(&mut self, msg: M, deadline: Instant) -> SpawnHandle
+    where
+        A: Handler<M>,
+        M: Message + 'static,
+    {
+        if self.state() == ActorState::Stopped {
+            error!("Context::notify_at called for stopped actor.");
+            SpawnHandle::default()
+        } else {
+            self.spawn(ActorDelayedMessageItem::at(msg, deadline))
+        }
+    }
+
     /// Executes a closure after a specified period of time.
     ///
     /// The closure gets passed the same actor and its
@@ -456,6 +690,34 @@ where
         self.spawn(IntervalFunc::new(dur, f).finish())
     }
 
+    /// Like [`run_interval`](AsyncContext::run_interval), with the given handling of missed
+    /// ticks.
+    ///
+    /// A tick is missed when the actor is busy, e.g. with a slow handler, past the time the
+    /// closure should have run. `run_interval` runs missed ticks back to back
+    /// ([`MissedTickBehavior::Burst`]); [`MissedTickBehavior::Skip`] drops them and keeps to the
+    /// original schedule, while [`MissedTickBehavior::Delay`] restarts the schedule from the
+    /// late tick.
+    ///
+    /// [`MissedTickBehavior::Burst`]: crate::clock::MissedTickBehavior::Burst
+    /// [`MissedTickBehavior::Skip`]: crate::clock::MissedTickBehavior::Skip
+    /// [`MissedTickBehavior::Delay`]: crate::clock::MissedTickBehavior::Delay
+    fn run_interval_with<F>(
+        &mut self,
+        dur: Duration,
+        behavior: MissedTickBehavior,
+        f: F,
+    ) -> SpawnHandle
+    where
+        F: FnMut(&mut A, &mut A::Context) + 'static,
+    {
+        self.spawn(
+            IntervalFunc::new(dur, f)
+                .missed_tick_behavior(behavior)
+                .finish(),
+        )
+    }
+
     /// Spawns a periodic `task` function to begin executing at the given `start` time, and with the
     /// given `interval` duration.
     fn run_interval_at<F>(
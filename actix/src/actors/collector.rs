@@ -0,0 +1,180 @@
+//! Fan-in buffer actor.
+//!
+//! [`Collector`] receives messages of one type from any number of producers and keeps them
+//! until a consumer takes them, either on demand with [`Drain`] or periodically in [`Batch`]es
+//! after [`Subscribe`]. Messages are kept in the order the collector received them.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use actix::{actors::collector::{Collector, Drain}, prelude::*};
+//!
+//! #[derive(Message)]
+//! #[rtype(result = "()")]
+//! struct Sample(u64);
+//!
+//! # #[actix::main]
+//! # async fn main() {
+//! let collector = Collector::<Sample>::new().start();
+//!
+//! collector.do_send(Sample(3));
+//! collector.do_send(Sample(5));
+//!
+//! let samples = collector.send(Drain::new()).await.unwrap();
+//! assert_eq!(samples.len(), 2);
+//! # }
+//! ```
+
+use std::{fmt, marker::PhantomData, mem, time::Duration};
+
+use crate::{
+    handler::{Handler, Message, MessageResult},
+    prelude::*,
+};
+
+/// Actor buffering the messages of type `M` it receives.
+pub struct Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    buffer: Vec<M>,
+    subscription: Option<SpawnHandle>,
+}
+
+impl<M> Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    /// Creates a collector with an empty buffer.
+    pub fn new() -> Self {
+        Collector {
+            buffer: Vec::new(),
+            subscription: None,
+        }
+    }
+}
+
+// buffered messages are never pinned
+impl<M> Unpin for Collector<M> where M: Message<Result = ()> + Send + 'static {}
+
+impl<M> Default for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    fn default() -> Self {
+        Collector::new()
+    }
+}
+
+impl<M> fmt::Debug for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Collector")
+            .field("buffered", &self.buffer.len())
+            .field("subscribed", &self.subscription.is_some())
+            .finish()
+    }
+}
+
+impl<M> Actor for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    type Context = Context<Self>;
+}
+
+impl<M> Handler<M> for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: M, _: &mut Self::Context) {
+        self.buffer.push(msg);
+    }
+}
+
+/// Takes all buffered messages out of a [`Collector`].
+pub struct Drain<M>(PhantomData<M>);
+
+impl<M> Drain<M> {
+    /// Creates a drain request.
+    pub fn new() -> Self {
+        Drain(PhantomData)
+    }
+}
+
+impl<M> Default for Drain<M> {
+    fn default() -> Self {
+        Drain::new()
+    }
+}
+
+impl<M: 'static> Message for Drain<M> {
+    type Result = Vec<M>;
+}
+
+impl<M> Handler<Drain<M>> for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    type Result = MessageResult<Drain<M>>;
+
+    fn handle(&mut self, _: Drain<M>, _: &mut Self::Context) -> Self::Result {
+        MessageResult(mem::take(&mut self.buffer))
+    }
+}
+
+/// Messages taken out of a [`Collector`] and forwarded to its subscriber.
+pub struct Batch<M>(pub Vec<M>);
+
+impl<M> Message for Batch<M> {
+    type Result = ();
+}
+
+/// Forwards the buffered messages of a [`Collector`] to a recipient every interval.
+///
+/// Intervals with no buffered messages are skipped. A collector has at most one subscriber:
+/// subscribing replaces the previous subscription. The subscription ends when the recipient's
+/// actor stops, after which messages are buffered again until drained or a new subscription.
+pub struct Subscribe<M>(pub Recipient<Batch<M>>, pub Duration)
+where
+    M: Send + 'static;
+
+impl<M> Message for Subscribe<M>
+where
+    M: Send + 'static,
+{
+    type Result = ();
+}
+
+impl<M> Handler<Subscribe<M>> for Collector<M>
+where
+    M: Message<Result = ()> + Send + 'static,
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: Subscribe<M>, ctx: &mut Self::Context) {
+        let Subscribe(recipient, interval) = msg;
+
+        if let Some(handle) = self.subscription.take() {
+            ctx.cancel_future(handle);
+        }
+
+        let handle = ctx.run_interval(interval, move |act, ctx| {
+            if !recipient.connected() {
+                if let Some(handle) = act.subscription.take() {
+                    ctx.cancel_future(handle);
+                }
+                return;
+            }
+
+            if !act.buffer.is_empty() {
+                recipient.do_send(Batch(mem::take(&mut act.buffer)));
+            }
+        });
+        self.subscription = Some(handle);
+    }
+}
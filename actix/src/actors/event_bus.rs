@@ -0,0 +1,273 @@
+//! Publish/subscribe hub actor.
+//!
+//! [`EventBus`] keeps a set of subscribers for one message type and forwards every published
+//! message to all of them. Subscribers whose actor has stopped are dropped the next time a
+//! message is published, so they do not need to unsubscribe before stopping. The bus also
+//! sweeps them out periodically, so they do not pile up while nothing is published, see
+//! [`EventBus::sweep_interval`].
+//!
+//! Messages published with [`PublishLater`] go to the subscribers present when the delay
+//! elapses, not to those present when the message was scheduled.
+//!
+//! A bus created with [`EventBus::with_replay`] keeps the last messages it published and sends
+//! them to each new subscriber, so late joiners catch up on recent history.
+//!
+//! ```
+//! use actix::{actors::event_bus::{EventBus, Publish, Subscribe}, prelude::*};
+//!
+//! #[derive(Clone, Message)]
+//! #[rtype(result = "()")]
+//! struct Chat(String);
+//!
+//! struct Member;
+//!
+//! impl Actor for Member {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<Chat> for Member {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, msg: Chat, _: &mut Self::Context) {
+//!         println!("received: {}", msg.0);
+//!     }
+//! }
+//!
+//! # #[actix::main]
+//! # async fn main() {
+//! let bus = EventBus::<Chat>::default().start();
+//! bus.do_send(Subscribe(Member.start().recipient()));
+//! bus.do_send(Publish(Chat("hello".to_owned())));
+//! # }
+//! ```
+
+use std::{any::type_name, collections::VecDeque, time::Duration};
+
+use log::debug;
+
+use crate::{
+    address::RecipientSet,
+    handler::{Handler, Message},
+    prelude::*,
+};
+
+/// Actor that broadcasts published messages of type `M` to its subscribers.
+pub struct EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    subscribers: RecipientSet<M>,
+    sweep_interval: Duration,
+    // the last `replay` published messages, oldest first; boxed to keep the actor `Unpin`
+    history: VecDeque<Box<M>>,
+    replay: usize,
+}
+
+/// Default interval at which an [`EventBus`] removes subscribers whose actor has stopped.
+pub const DEFAULT_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+impl<M> EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    /// Creates an event bus without subscribers.
+    pub fn new() -> Self {
+        EventBus::with_replay(0)
+    }
+
+    /// Creates an event bus without subscribers that replays the last `len` published messages
+    /// to each new subscriber.
+    ///
+    /// Replayed messages are sent, oldest first, when the subscription is handled, so they reach
+    /// the subscriber before any message published afterwards. A `len` of 0 disables replay.
+    pub fn with_replay(len: usize) -> Self {
+        let mut subscribers = RecipientSet::new();
+        subscribers.set_prune_on_send(true);
+        EventBus {
+            subscribers,
+            sweep_interval: DEFAULT_SWEEP_INTERVAL,
+            history: VecDeque::with_capacity(len),
+            replay: len,
+        }
+    }
+
+    /// Sets how often the bus removes subscribers whose actor has stopped.
+    ///
+    /// Publishing drops such subscribers as well, so the sweep only matters for buses with many
+    /// short-lived subscribers and few messages. Defaults to [`DEFAULT_SWEEP_INTERVAL`].
+    pub fn sweep_interval(mut self, interval: Duration) -> Self {
+        self.sweep_interval = interval;
+        self
+    }
+
+    fn publish(&mut self, msg: M) {
+        if self.replay > 0 {
+            if self.history.len() == self.replay {
+                self.history.pop_front();
+            }
+            self.history.push_back(Box::new(msg.clone()));
+        }
+        self.subscribers.broadcast(msg);
+    }
+}
+
+impl<M> Default for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    fn default() -> Self {
+        EventBus::new()
+    }
+}
+
+impl<M> Actor for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.run_interval(self.sweep_interval, |act, _| {
+            let removed = act.subscribers.prune();
+            if removed > 0 {
+                debug!(
+                    "EventBus<{}> removed {} stopped subscribers, {} left",
+                    type_name::<M>(),
+                    removed,
+                    act.subscribers.len()
+                );
+            }
+        });
+    }
+}
+
+/// Adds a subscriber to an [`EventBus`]. Subscribing the same recipient twice has no effect.
+///
+/// On a bus with [replay](EventBus::with_replay), the new subscriber first receives the recently
+/// published messages.
+pub struct Subscribe<M>(pub Recipient<M>)
+where
+    M: Message + Send,
+    M::Result: Send;
+
+impl<M> Message for Subscribe<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    type Result = ();
+}
+
+/// Removes a subscriber from an [`EventBus`].
+pub struct Unsubscribe<M>(pub Recipient<M>)
+where
+    M: Message + Send,
+    M::Result: Send;
+
+impl<M> Message for Unsubscribe<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    type Result = ();
+}
+
+/// Sends a message to every subscriber of an [`EventBus`].
+///
+/// Resolves to the number of subscribers the message was sent to.
+pub struct Publish<M>(pub M);
+
+impl<M: Message> Message for Publish<M> {
+    type Result = usize;
+}
+
+/// Returns the number of subscribers of an [`EventBus`].
+///
+/// Subscribers whose actor has stopped are counted until the next publish or sweep removes them.
+pub struct SubscriberCount;
+
+impl Message for SubscriberCount {
+    type Result = usize;
+}
+
+/// Sends a message to every subscriber of an [`EventBus`] after a delay.
+///
+/// The message goes to the subscribers at the time of delivery: subscribers that unsubscribe or
+/// stop before the delay elapses do not receive it, while those that subscribe in the meantime
+/// do. Stopping the bus cancels pending deliveries.
+pub struct PublishLater<M>(pub M, pub Duration);
+
+impl<M: Message> Message for PublishLater<M> {
+    type Result = ();
+}
+
+impl<M> Handler<Subscribe<M>> for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: Subscribe<M>, _: &mut Self::Context) {
+        let Subscribe(recipient) = msg;
+        if self.subscribers.insert(recipient.clone()) {
+            for past in &self.history {
+                recipient.do_send(M::clone(past));
+            }
+        }
+    }
+}
+
+impl<M> Handler<Unsubscribe<M>> for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: Unsubscribe<M>, _: &mut Self::Context) {
+        self.subscribers.remove(&msg.0);
+    }
+}
+
+impl<M> Handler<Publish<M>> for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = usize;
+
+    fn handle(&mut self, msg: Publish<M>, _: &mut Self::Context) -> usize {
+        self.publish(msg.0);
+        self.subscribers.len()
+    }
+}
+
+impl<M> Handler<SubscriberCount> for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = usize;
+
+    fn handle(&mut self, _: SubscriberCount, _: &mut Self::Context) -> usize {
+        self.subscribers.len()
+    }
+}
+
+impl<M> Handler<PublishLater<M>> for EventBus<M>
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = ();
+
+    fn handle(&mut self, msg: PublishLater<M>, ctx: &mut Self::Context) {
+        let PublishLater(msg, delay) = msg;
+        ctx.run_later(delay, move |act, _| act.publish(msg));
+    }
+}
@@ -1,3 +1,6 @@
 //! Helper actors
 
+pub mod collector;
+pub mod event_bus;
 pub mod mocker;
+pub mod timer;
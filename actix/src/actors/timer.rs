@@ -0,0 +1,201 @@
+//! Scheduling service actor.
+//!
+//! [`TimerActor`] delivers messages to a recipient after a delay, either once with
+//! [`ScheduleOnce`] or periodically with [`ScheduleRepeating`]. Scheduling returns a
+//! [`TimerHandle`] that can be passed to [`Cancel`] to drop the timer before it fires.
+//!
+//! The actor is a [`SystemService`], so one instance can be shared by the whole application
+//! through [`TimerActor::from_registry`](SystemService::from_registry).
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use actix::{actors::timer::{Cancel, ScheduleOnce, TimerActor}, prelude::*};
+//!
+//! #[derive(Message)]
+//! #[rtype(result = "()")]
+//! struct Reminder;
+//!
+//! struct Calendar;
+//!
+//! impl Actor for Calendar {
+//!     type Context = Context<Self>;
+//! }
+//!
+//! impl Handler<Reminder> for Calendar {
+//!     type Result = ();
+//!
+//!     fn handle(&mut self, _: Reminder, _: &mut Self::Context) {
+//!         println!("meeting in 5 minutes");
+//!     }
+//! }
+//!
+//! # #[actix::main]
+//! # async fn main() {
+//! let timer = TimerActor::from_registry();
+//! let handle = timer
+//!     .send(ScheduleOnce {
+//!         recipient: Calendar.start().recipient(),
+//!         msg: Reminder,
+//!         delay: Duration::from_secs(60),
+//!     })
+//!     .await
+//!     .unwrap();
+//!
+//! assert!(timer.send(Cancel(handle)).await.unwrap());
+//! # }
+//! ```
+
+use std::{collections::HashSet, time::Duration};
+
+use crate::{
+    handler::{Handler, Message},
+    prelude::*,
+};
+
+/// Actor delivering scheduled messages to their recipients.
+///
+/// Stopping the actor drops all pending timers.
+#[derive(Debug, Default)]
+pub struct TimerActor {
+    pending: HashSet<SpawnHandle>,
+}
+
+impl TimerActor {
+    /// Creates a timer without pending timers.
+    pub fn new() -> Self {
+        TimerActor::default()
+    }
+}
+
+impl Actor for TimerActor {
+    type Context = Context<Self>;
+}
+
+impl Supervised for TimerActor {
+    fn restarting(&mut self, _: &mut Self::Context) {
+        // the restarted context has cancelled all timers
+        self.pending.clear();
+    }
+}
+
+impl SystemService for TimerActor {}
+
+/// Identifies a timer scheduled with a [`TimerActor`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimerHandle(SpawnHandle);
+
+/// Sends `msg` to `recipient` once `delay` has elapsed.
+///
+/// Resolves to the handle of the timer.
+pub struct ScheduleOnce<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    pub recipient: Recipient<M>,
+    pub msg: M,
+    pub delay: Duration,
+}
+
+impl<M> Message for ScheduleOnce<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    type Result = TimerHandle;
+}
+
+/// Sends a copy of `msg` to `recipient` every `interval`, starting one `interval` from now.
+///
+/// The timer keeps running until it is cancelled or the recipient's actor stops. Resolves to the
+/// handle of the timer.
+pub struct ScheduleRepeating<M>
+where
+    M: Message + Send + Clone,
+    M::Result: Send,
+{
+    pub recipient: Recipient<M>,
+    pub msg: M,
+    pub interval: Duration,
+}
+
+impl<M> Message for ScheduleRepeating<M>
+where
+    M: Message + Send + Clone,
+    M::Result: Send,
+{
+    type Result = TimerHandle;
+}
+
+/// Cancels a timer of a [`TimerActor`].
+///
+/// Resolves to `true` if the timer was still pending, i.e. it was a repeating timer or a
+/// one-shot timer that had not fired yet.
+pub struct Cancel(pub TimerHandle);
+
+impl Message for Cancel {
+    type Result = bool;
+}
+
+impl<M> Handler<ScheduleOnce<M>> for TimerActor
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    type Result = MessageResult<ScheduleOnce<M>>;
+
+    fn handle(&mut self, msg: ScheduleOnce<M>, ctx: &mut Self::Context) -> Self::Result {
+        let ScheduleOnce {
+            recipient,
+            msg,
+            delay,
+        } = msg;
+
+        let handle = ctx.run_later(delay, move |act, ctx| {
+            act.pending.remove(&ctx.handle());
+            recipient.do_send(msg);
+        });
+        self.pending.insert(handle);
+
+        MessageResult(TimerHandle(handle))
+    }
+}
+
+impl<M> Handler<ScheduleRepeating<M>> for TimerActor
+where
+    M: Message + Send + Clone + 'static,
+    M::Result: Send,
+{
+    type Result = MessageResult<ScheduleRepeating<M>>;
+
+    fn handle(&mut self, msg: ScheduleRepeating<M>, ctx: &mut Self::Context) -> Self::Result {
+        let ScheduleRepeating {
+            recipient,
+            msg,
+            interval,
+        } = msg;
+
+        let handle = ctx.run_interval(interval, move |act, ctx| {
+            if recipient.connected() {
+                recipient.do_send(msg.clone());
+            } else {
+                let handle = ctx.handle();
+                act.pending.remove(&handle);
+                ctx.cancel_future(handle);
+            }
+        });
+        self.pending.insert(handle);
+
+        MessageResult(TimerHandle(handle))
+    }
+}
+
+impl Handler<Cancel> for TimerActor {
+    type Result = bool;
+
+    fn handle(&mut self, msg: Cancel, ctx: &mut Self::Context) -> bool {
+        let Cancel(TimerHandle(handle)) = msg;
+        self.pending.remove(&handle) && ctx.cancel_future(handle)
+    }
+}
@@ -15,17 +15,19 @@ use std::{
     thread,
 };
 
-use futures_core::{stream::Stream, task::__internal::AtomicWaker};
+use futures_core::{ready, stream::Stream, task::__internal::AtomicWaker};
 use parking_lot::Mutex;
 use tokio::sync::oneshot::{channel as oneshot_channel, Receiver as OneshotReceiver};
 
 use super::{
     envelope::{Envelope, ToEnvelope},
     queue::Queue,
-    SendError,
+    CancelToken, Recipient, SendError,
 };
 use crate::{
-    actor::Actor,
+    actor::{Actor, AsyncContext},
+    clock::Instant,
+    context_impl::AsyncContextParts,
     handler::{Handler, Message},
 };
 
@@ -40,6 +42,12 @@ where
 
     fn send(&self, msg: M) -> Result<OneshotReceiver<M::Result>, SendError<M>>;
 
+    fn send_with_deadline(
+        &self,
+        msg: M,
+        deadline: Instant,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<M>>;
+
     fn boxed(&self) -> Box<dyn Sender<M> + Sync>;
 
     fn hash(&self) -> usize;
@@ -48,6 +56,30 @@ where
 
     /// Returns a downgraded sender, where the sender is downgraded into its weak counterpart.
     fn downgrade(&self) -> Box<dyn WeakSender<M> + Sync + 'static>;
+
+    /// Queues the message built by `msg` the way selected by `delivery`.
+    ///
+    /// `msg` is only called once the channel has accepted the message, so the caller keeps
+    /// whatever it builds the message from when the message is refused.
+    fn send_deferred(
+        &self,
+        delivery: Delivery,
+        msg: &mut dyn FnMut() -> M,
+    ) -> Result<Option<OneshotReceiver<M::Result>>, SendError<()>>;
+
+    /// Handles a message that could not be sent because the mailbox is closed.
+    fn dead_letter(&self, msg: M);
+}
+
+/// How [`Sender::send_deferred`] queues a message.
+#[derive(Debug, Clone, Copy)]
+pub enum Delivery {
+    /// Like [`Sender::do_send`].
+    DoSend,
+    /// Like [`Sender::try_send`].
+    TrySend,
+    /// Like [`Sender::send_with_deadline`], or [`Sender::send`] without a deadline.
+    Send(Option<Instant>),
 }
 
 impl<S, M> Sender<M> for Box<S>
@@ -68,6 +100,14 @@ where
         (**self).send(msg)
     }
 
+    fn send_with_deadline(
+        &self,
+        msg: M,
+        deadline: Instant,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<M>> {
+        (**self).send_with_deadline(msg, deadline)
+    }
+
     fn boxed(&self) -> Box<dyn Sender<M> + Sync> {
         (**self).boxed()
     }
@@ -83,6 +123,18 @@ where
     fn downgrade(&self) -> Box<dyn WeakSender<M> + Sync> {
         (**self).downgrade()
     }
+
+    fn send_deferred(
+        &self,
+        delivery: Delivery,
+        msg: &mut dyn FnMut() -> M,
+    ) -> Result<Option<OneshotReceiver<M::Result>>, SendError<()>> {
+        (**self).send_deferred(delivery, msg)
+    }
+
+    fn dead_letter(&self, msg: M) {
+        (**self).dead_letter(msg)
+    }
 }
 
 pub trait WeakSender<M>: Send
@@ -300,10 +352,71 @@ impl<A: Actor> AddressSender<A> {
         state.is_open
     }
 
+    /// Get number of queued messages
+    pub fn queued(&self) -> usize {
+        decode_state(self.inner.state.load(SeqCst)).num_messages
+    }
+
     /// Attempts to send a message on this `Sender<A>` with blocking.
     ///
     /// This function must be called from inside of a task.
     pub fn send<M>(&self, msg: M) -> Result<OneshotReceiver<M::Result>, SendError<M>>
+    where
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M::Result: Send,
+        M: Message + Send,
+    {
+        self.send_envelope(msg, |msg| msg, |env| env)
+    }
+
+    /// Attempts to send a message on this `Sender<A>` with blocking. The message is
+    /// dead-lettered unhandled if it is still queued when `deadline` passes.
+    ///
+    /// This function must be called from inside of a task.
+    pub fn send_with_deadline<M>(
+        &self,
+        msg: M,
+        deadline: Instant,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<M>>
+    where
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M::Result: Send,
+        M: Message + Send + 'static,
+    {
+        self.send_envelope(
+            msg,
+            |msg| msg,
+            |env| Envelope::with_deadline::<M>(env, deadline),
+        )
+    }
+
+    /// Attempts to send a message on this `Sender<A>` with blocking. The message is dropped
+    /// unhandled if `token` is cancelled while it is still queued.
+    ///
+    /// This function must be called from inside of a task.
+    pub fn send_cancellable<M>(
+        &self,
+        msg: M,
+        token: CancelToken,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<M>>
+    where
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M> + AsyncContextParts<A>,
+        M::Result: Send,
+        M: Message + Send,
+    {
+        self.send_envelope(msg, |msg| msg, |env| Envelope::with_cancel(env, token))
+    }
+
+    // `into` builds the message once the channel has accepted it
+    fn send_envelope<P, M>(
+        &self,
+        msg: P,
+        into: impl FnOnce(P) -> M,
+        wrap: impl FnOnce(Envelope<A>) -> Envelope<A>,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<P>>
     where
         A: Handler<M>,
         A::Context: ToEnvelope<A, M>,
@@ -337,8 +450,8 @@ impl<A: Actor> AddressSender<A> {
             self.park();
         }
         let (tx, rx) = oneshot_channel();
-        let env = <A::Context as ToEnvelope<A, M>>::pack(msg, Some(tx));
-        self.queue_push_and_signal(env);
+        let env = <A::Context as ToEnvelope<A, M>>::pack(into(msg), Some(tx));
+        self.queue_push_and_signal(wrap(env));
         Ok(rx)
     }
 
@@ -349,6 +462,21 @@ impl<A: Actor> AddressSender<A> {
         <A as Actor>::Context: ToEnvelope<A, M>,
         M::Result: Send,
         M: Message + Send + 'static,
+    {
+        self.try_send_into(msg, |msg| msg, park)
+    }
+
+    fn try_send_into<P, M>(
+        &self,
+        msg: P,
+        into: impl FnOnce(P) -> M,
+        park: bool,
+    ) -> Result<(), SendError<P>>
+    where
+        A: Handler<M>,
+        <A as Actor>::Context: ToEnvelope<A, M>,
+        M::Result: Send,
+        M: Message + Send,
     {
         // If the sender is currently blocked, reject the message
         if !self.poll_unparked(false, None).is_ready() {
@@ -367,7 +495,7 @@ impl<A: Actor> AddressSender<A> {
         if park_self && park {
             self.park();
         }
-        let env = <A::Context as ToEnvelope<A, M>>::pack(msg, None);
+        let env = <A::Context as ToEnvelope<A, M>>::pack(into(msg), None);
         self.queue_push_and_signal(env);
         Ok(())
     }
@@ -376,6 +504,16 @@ impl<A: Actor> AddressSender<A> {
     ///
     /// This function does not park current task.
     pub fn do_send<M>(&self, msg: M) -> Result<(), SendError<M>>
+    where
+        A: Handler<M>,
+        <A as Actor>::Context: ToEnvelope<A, M>,
+        M::Result: Send,
+        M: Message + Send,
+    {
+        self.do_send_into(msg, |msg| msg)
+    }
+
+    fn do_send_into<P, M>(&self, msg: P, into: impl FnOnce(P) -> M) -> Result<(), SendError<P>>
     where
         A: Handler<M>,
         <A as Actor>::Context: ToEnvelope<A, M>,
@@ -388,12 +526,89 @@ impl<A: Actor> AddressSender<A> {
             // If inc_num_messages returned Some(park_self), then the mailbox is still active.
             // We ignore the boolean (indicating to park and wait) in the Some, and queue the
             // message regardless.
-            let env = <A::Context as ToEnvelope<A, M>>::pack(msg, None);
+            let env = <A::Context as ToEnvelope<A, M>>::pack(into(msg), None);
             self.queue_push_and_signal(env);
             Ok(())
         }
     }
 
+    /// Send a message on this `Sender<A>` without blocking, unless the channel is at capacity.
+    ///
+    /// Unlike [`try_send`](Self::try_send), this never parks the current task and rejects the
+    /// message as soon as `capacity` messages are queued.
+    pub fn do_send_bounded<M>(&self, msg: M) -> Result<(), SendError<M>>
+    where
+        A: Handler<M>,
+        <A as Actor>::Context: ToEnvelope<A, M>,
+        M::Result: Send,
+        M: Message + Send,
+    {
+        let mut curr = self.inner.state.load(SeqCst);
+        loop {
+            let mut state = decode_state(curr);
+            if !state.is_open {
+                return Err(SendError::Closed(msg));
+            }
+
+            let buffer = self.inner.buffer.load(Relaxed);
+            if buffer != 0 && state.num_messages >= buffer {
+                return Err(SendError::Full(msg));
+            }
+            state.num_messages += 1;
+
+            let next = encode_state(&state);
+            match self
+                .inner
+                .state
+                .compare_exchange(curr, next, SeqCst, SeqCst)
+            {
+                Ok(_) => break,
+                Err(actual) => curr = actual,
+            }
+        }
+
+        let env = <A::Context as ToEnvelope<A, M>>::pack(msg, None);
+        self.queue_push_and_signal(env);
+        Ok(())
+    }
+
+    /// Send a message on this `Sender<A>` without blocking, delivering its result to `reply`.
+    ///
+    /// This function does not park current task.
+    pub fn do_send_with_reply<M>(
+        &self,
+        msg: M,
+        reply: Recipient<M::Result>,
+    ) -> Result<(), SendError<M>>
+    where
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M> + AsyncContext<A>,
+        M: Message + Send,
+        M::Result: Message + Send + 'static,
+        <M::Result as Message>::Result: Send,
+    {
+        if self.inc_num_messages().is_none() {
+            return Err(SendError::Closed(msg));
+        }
+
+        let (tx, rx) = oneshot_channel();
+        let env = <A::Context as ToEnvelope<A, M>>::pack(msg, Some(tx));
+        self.queue_push_and_signal(Envelope::with_reply(env, rx, reply));
+        Ok(())
+    }
+
+    /// Queues an envelope that was already taken out of the mailbox again.
+    ///
+    /// Returns the envelope if the mailbox is closed.
+    pub(crate) fn requeue(&self, env: Envelope<A>) -> Result<(), Envelope<A>> {
+        if self.inc_num_messages().is_none() {
+            return Err(env);
+        }
+
+        self.queue_push_and_signal(env);
+        Ok(())
+    }
+
     /// Downgrade to `WeakAddressSender` which can later be upgraded
     pub fn downgrade(&self) -> WeakAddressSender<A> {
         WeakAddressSender {
@@ -452,6 +667,47 @@ impl<A: Actor> AddressSender<A> {
         self.maybe_parked.store(state.is_open, Relaxed);
     }
 
+    /// Polls whether a message can be queued without parking this sender.
+    ///
+    /// Resolves to `false` if the channel is closed. Otherwise waits until the receiver takes a
+    /// message, if this sender is parked or the channel is full.
+    pub fn poll_ready(&self, cx: &mut task::Context<'_>) -> Poll<bool> {
+        if !self.connected() {
+            return Poll::Ready(false);
+        }
+
+        // parked by a previous message, or by a previous call: unparking frees a slot
+        if self.maybe_parked.load(Relaxed) {
+            ready!(self.poll_unparked(true, Some(cx)));
+            return Poll::Ready(self.connected());
+        }
+
+        let buffer = self.inner.buffer.load(Relaxed);
+        if buffer == 0 || self.queued() < buffer {
+            return Poll::Ready(true);
+        }
+
+        // wait in line with the parked senders for the receiver to take a message
+        {
+            let mut task = self.sender_task.lock();
+            task.task = Some(cx.waker().clone());
+            task.is_parked = true;
+        }
+        self.inner.parked_queue.push(Arc::clone(&self.sender_task));
+        self.maybe_parked.store(true, Relaxed);
+
+        // the receiver may have taken a message or closed before seeing the parked task, in
+        // which case the receiver skips the stale task once it pops it
+        let state = decode_state(self.inner.state.load(SeqCst));
+        if !state.is_open || state.num_messages < buffer {
+            self.sender_task.lock().is_parked = false;
+            self.maybe_parked.store(false, Relaxed);
+            return Poll::Ready(state.is_open);
+        }
+
+        Poll::Pending
+    }
+
     fn poll_unparked(&self, do_park: bool, cx: Option<&mut task::Context<'_>>) -> Poll<()> {
         // First check the `maybe_parked` variable. This avoids acquiring the
         // lock in most cases
@@ -499,6 +755,13 @@ where
     fn send(&self, msg: M) -> Result<OneshotReceiver<M::Result>, SendError<M>> {
         self.send(msg)
     }
+    fn send_with_deadline(
+        &self,
+        msg: M,
+        deadline: Instant,
+    ) -> Result<OneshotReceiver<M::Result>, SendError<M>> {
+        self.send_with_deadline(msg, deadline)
+    }
     fn boxed(&self) -> Box<dyn Sender<M> + Sync> {
         Box::new(self.clone())
     }
@@ -517,6 +780,30 @@ where
             inner: Arc::downgrade(&self.inner),
         })
     }
+
+    fn send_deferred(
+        &self,
+        delivery: Delivery,
+        msg: &mut dyn FnMut() -> M,
+    ) -> Result<Option<OneshotReceiver<M::Result>>, SendError<()>> {
+        let res = match delivery {
+            Delivery::DoSend => self.do_send_into(msg, |msg| msg()).map(|_| None),
+            Delivery::TrySend => self.try_send_into(msg, |msg| msg(), true).map(|_| None),
+            Delivery::Send(None) => self.send_envelope(msg, |msg| msg(), |env| env).map(Some),
+            Delivery::Send(Some(deadline)) => self
+                .send_envelope(
+                    msg,
+                    |msg| msg(),
+                    |env| Envelope::with_deadline::<M>(env, deadline),
+                )
+                .map(Some),
+        };
+        res.map_err(|err| err.map(|_| ()))
+    }
+
+    fn dead_letter(&self, msg: M) {
+        <A::Context as ToEnvelope<A, M>>::dead_letter(msg)
+    }
 }
 
 impl<A: Actor> Clone for AddressSender<A> {
@@ -629,6 +916,11 @@ impl<A: Actor> AddressSenderProducer<A> {
         self.inner.buffer.load(Relaxed)
     }
 
+    /// Get number of queued messages
+    pub fn queued(&self) -> usize {
+        decode_state(self.inner.state.load(SeqCst)).num_messages
+    }
+
     /// Set channel capacity
     ///
     /// This method wakes up all waiting senders if new capacity is greater
@@ -645,6 +937,31 @@ impl<A: Actor> AddressSenderProducer<A> {
         }
     }
 
+    /// Removes queued messages of type `M` from the channel, keeping other messages queued in
+    /// their original order, also relative to messages sent meanwhile.
+    ///
+    /// Must only be called from the receiver's task while it is not polling the channel.
+    pub(crate) fn take_queued<M>(&self) -> Vec<M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        // the emptied envelopes are dropped, failing the pending requests
+        let taken = unsafe {
+            self.inner
+                .message_queue
+                .take_where(|env| env.take_message::<M>())
+        };
+
+        for _ in &taken {
+            // same bookkeeping as the receiver does for a handled message
+            self.inner.state.fetch_sub(1, SeqCst);
+            self.inner.unpark_one();
+        }
+
+        taken
+    }
+
     /// Get sender side of the channel
     pub fn sender(&self) -> AddressSender<A> {
         // this code same as Sender::clone
@@ -743,6 +1060,41 @@ impl<A: Actor> AddressReceiver<A> {
         }
     }
 
+    /// Closes the channel and passes all pending messages to `f`.
+    pub(crate) fn close_with(&mut self, mut f: impl FnMut(Envelope<A>)) {
+        // close
+        self.inner.set_closed();
+
+        // Wake up any threads waiting as they'll see that we've closed the
+        // channel and will continue on their merry way.
+        while let Some(task) = unsafe { self.inner.parked_queue.pop_spin() } {
+            task.lock().notify();
+        }
+
+        // Drain the channel of all pending messages
+        loop {
+            match self.next_message() {
+                Poll::Ready(Some(env)) => f(env),
+                Poll::Ready(None) => break,
+                Poll::Pending => {
+                    let state = decode_state(self.inner.state.load(SeqCst));
+
+                    // If the channel is closed, then there is no need to park.
+                    if state.is_closed() {
+                        break;
+                    }
+
+                    // TODO: Spinning isn't ideal, it might be worth
+                    // investigating using a condvar or some other strategy
+                    // here. That said, if this case is hit, then another thread
+                    // is about to push the value into the queue and this isn't
+                    // the only spinlock in the impl right now.
+                    thread::yield_now();
+                }
+            }
+        }
+    }
+
     fn next_message(&mut self) -> Poll<Option<Envelope<A>>> {
         // Pop off a message
         match unsafe { self.inner.message_queue.pop_spin() } {
@@ -778,9 +1130,7 @@ impl<A: Actor> AddressReceiver<A> {
 
     // Unpark a single task handle if there is one pending in the parked queue
     fn unpark_one(&mut self) {
-        if let Some(task) = unsafe { self.inner.parked_queue.pop_spin() } {
-            task.lock().notify();
-        }
+        self.inner.unpark_one();
     }
 
     fn dec_num_messages(&self) {
@@ -812,40 +1162,9 @@ impl<A: Actor> Stream for AddressReceiver<A> {
 
 impl<A: Actor> Drop for AddressReceiver<A> {
     fn drop(&mut self) {
-        // close
-        self.inner.set_closed();
-
-        // Wake up any threads waiting as they'll see that we've closed the
-        // channel and will continue on their merry way.
-        while let Some(task) = unsafe { self.inner.parked_queue.pop_spin() } {
-            task.lock().notify();
-        }
-
-        // Drain the channel of all pending messages
-        loop {
-            match self.next_message() {
-                Poll::Ready(Some(_)) => {}
-                Poll::Ready(None) => break,
-                Poll::Pending => {
-                    let state = decode_state(self.inner.state.load(SeqCst));
-
-                    // If the channel is closed, then there is no need to park.
-                    if state.is_closed() {
-                        break;
-                    }
-
-                    // TODO: Spinning isn't ideal, it might be worth
-                    // investigating using a condvar or some other strategy
-                    // here. That said, if this case is hit, then another thread
-                    // is about to push the value into the queue and this isn't
-                    // the only spinlock in the impl right now.
-                    thread::yield_now();
-                }
-            }
-        }
+        self.close_with(drop);
     }
 }
-
 //
 //
 // ===== impl Inner =====
@@ -867,6 +1186,18 @@ impl<A: Actor> Inner<A> {
 
         self.state.fetch_and(!OPEN_MASK, SeqCst);
     }
+
+    // Unpark the first task in the parked queue that is still parked. Tasks waiting for room
+    // with `AddressSender::poll_ready` can leave behind ones that no longer are.
+    fn unpark_one(&self) {
+        while let Some(task) = unsafe { self.parked_queue.pop_spin() } {
+            let mut task = task.lock();
+            if task.is_parked {
+                task.notify();
+                break;
+            }
+        }
+    }
 }
 
 unsafe impl<A: Actor> Send for Inner<A> {}
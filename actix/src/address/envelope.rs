@@ -1,9 +1,20 @@
-use tokio::sync::oneshot::Sender;
+use std::{
+    any::{type_name, Any},
+    marker::PhantomData,
+};
+
+use log::debug;
+use tokio::sync::oneshot::{error::TryRecvError, Receiver, Sender};
 
+use super::{CancelToken, Recipient, SendError};
 use crate::{
     actor::{Actor, AsyncContext},
+    clock::Instant,
     context::Context,
+    context_impl::{current_correlation_id, AsyncContextParts},
+    dead_letter,
     handler::{Handler, Message, MessageResponse},
+    sync::SyncContextEnvelope,
 };
 
 /// Converter trait, packs message into a suitable envelope.
@@ -14,11 +25,36 @@ where
 {
     /// Pack message into suitable envelope
     fn pack(msg: M, tx: Option<Sender<M::Result>>) -> Envelope<A>;
+
+    /// Handles a message sent with `do_send` to a closed mailbox.
+    ///
+    /// The message is dropped by default.
+    fn dead_letter(msg: M) {
+        drop(msg);
+    }
 }
 
 pub trait EnvelopeProxy<A: Actor> {
     /// handle message within new actor and context
     fn handle(&mut self, act: &mut A, ctx: &mut A::Context);
+
+    /// Returns the proxy as `Any`, if it supports recovering its message.
+    #[doc(hidden)]
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+
+    /// Returns the message, if it has not been handled yet and the proxy exposes it.
+    #[doc(hidden)]
+    fn message(&self) -> Option<&dyn Any> {
+        None
+    }
+
+    /// Returns the type name of the message, for diagnostics.
+    #[doc(hidden)]
+    fn message_type(&self) -> &'static str {
+        "<unknown>"
+    }
 }
 
 impl<A, M> ToEnvelope<A, M> for Context<A>
@@ -28,7 +64,15 @@ where
     M::Result: Send,
 {
     fn pack(msg: M, tx: Option<Sender<M::Result>>) -> Envelope<A> {
-        Envelope::new(msg, tx)
+        let env = Envelope::new(msg, tx);
+        match current_correlation_id() {
+            Some(id) => Envelope(Box::new(CorrelatedEnvelopeProxy { env, id })),
+            None => env,
+        }
+    }
+
+    fn dead_letter(msg: M) {
+        dead_letter::deliver(msg);
     }
 }
 
@@ -48,12 +92,75 @@ impl<A: Actor> Envelope<A> {
     pub fn with_proxy(proxy: Box<dyn EnvelopeProxy<A> + Send>) -> Self {
         Envelope(proxy)
     }
+
+    /// Wraps the envelope so that its message is dead-lettered unhandled once `deadline` passes.
+    pub(crate) fn with_deadline<M>(env: Envelope<A>, deadline: Instant) -> Self
+    where
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        Envelope(Box::new(DeadlineEnvelopeProxy::<A, M> {
+            env,
+            deadline,
+            _msg: PhantomData,
+        }))
+    }
+
+    /// Wraps the envelope so that its message is dropped unhandled once `token` is cancelled.
+    pub(crate) fn with_cancel(env: Envelope<A>, token: CancelToken) -> Self
+    where
+        A::Context: AsyncContextParts<A>,
+    {
+        Envelope(Box::new(CancelEnvelopeProxy { env, token }))
+    }
+
+    /// Wraps the envelope so that the response received on `rx` is sent to `reply`.
+    pub(crate) fn with_reply<R>(env: Envelope<A>, rx: Receiver<R>, reply: Recipient<R>) -> Self
+    where
+        A::Context: AsyncContext<A>,
+        R: Message + Send + 'static,
+        R::Result: Send,
+    {
+        Envelope(Box::new(ReplyEnvelopeProxy {
+            env,
+            reply: Some((rx, reply)),
+        }))
+    }
+
+    /// Takes the message out of the envelope if it is an unhandled message of type `M`.
+    pub(crate) fn take_message<M>(&mut self) -> Option<M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        let proxy = self.0.as_any_mut()?;
+        if let Some(proxy) = proxy.downcast_mut::<SyncEnvelopeProxy<M>>() {
+            return proxy.msg.take();
+        }
+        proxy
+            .downcast_mut::<SyncContextEnvelope<M>>()?
+            .take_message()
+    }
 }
 
 impl<A: Actor> EnvelopeProxy<A> for Envelope<A> {
     fn handle(&mut self, act: &mut A, ctx: &mut <A as Actor>::Context) {
         self.0.handle(act, ctx)
     }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.0.as_any_mut()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.0.message()
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.0.message_type()
+    }
 }
 
 pub struct SyncEnvelopeProxy<M>
@@ -83,4 +190,185 @@ where
             fut.handle(ctx, tx)
         }
     }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.msg.as_ref().map(|msg| msg as &dyn Any)
+    }
+
+    fn message_type(&self) -> &'static str {
+        type_name::<M>()
+    }
+}
+
+struct DeadlineEnvelopeProxy<A: Actor, M> {
+    env: Envelope<A>,
+    deadline: Instant,
+    _msg: PhantomData<fn() -> M>,
+}
+
+impl<A, M> EnvelopeProxy<A> for DeadlineEnvelopeProxy<A, M>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        if Instant::now() >= self.deadline {
+            // dropping the inner envelope notifies the requester
+            debug!("Dropping expired message of type {}", type_name::<M>());
+            if let Some(msg) = self.env.take_message::<M>() {
+                <A::Context as ToEnvelope<A, M>>::dead_letter(msg);
+            }
+            return;
+        }
+
+        self.env.handle(act, ctx)
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.env.as_any_mut()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.env.message()
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.env.message_type()
+    }
+}
+
+struct CancelEnvelopeProxy<A: Actor> {
+    env: Envelope<A>,
+    token: CancelToken,
+}
+
+impl<A> EnvelopeProxy<A> for CancelEnvelopeProxy<A>
+where
+    A: Actor,
+    A::Context: AsyncContextParts<A>,
+{
+    fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        if self.token.is_cancelled() {
+            return;
+        }
+
+        // make the token available to the handler through `Context::cancel_token`
+        let prev = ctx.parts().set_cancel_token(Some(self.token.clone()));
+        self.env.handle(act, ctx);
+        ctx.parts().set_cancel_token(prev);
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.env.as_any_mut()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.env.message()
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.env.message_type()
+    }
+}
+
+struct CorrelatedEnvelopeProxy<A: Actor> {
+    env: Envelope<A>,
+    id: u64,
+}
+
+impl<A> EnvelopeProxy<A> for CorrelatedEnvelopeProxy<A>
+where
+    A: Actor,
+    A::Context: AsyncContextParts<A>,
+{
+    fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        // the sender's correlation ID applies while the message is handled
+        let prev = ctx.parts().set_correlation_id(Some(self.id));
+        self.env.handle(act, ctx);
+        ctx.parts().set_correlation_id(prev);
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.env.as_any_mut()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.env.message()
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.env.message_type()
+    }
+}
+
+struct ReplyEnvelopeProxy<A: Actor, R>
+where
+    R: Message + Send,
+    R::Result: Send,
+{
+    env: Envelope<A>,
+    reply: Option<(Receiver<R>, Recipient<R>)>,
+}
+
+impl<A, R> EnvelopeProxy<A> for ReplyEnvelopeProxy<A, R>
+where
+    A: Actor,
+    A::Context: AsyncContext<A>,
+    R: Message + Send + 'static,
+    R::Result: Send,
+{
+    fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        self.env.handle(act, ctx);
+
+        let (mut rx, reply) = match self.reply.take() {
+            Some(reply) => reply,
+            None => return,
+        };
+        match rx.try_recv() {
+            Ok(res) => deliver_reply(&reply, res),
+            // the response is produced asynchronously; it is forwarded from the runtime so it
+            // still arrives if this actor stops first
+            Err(TryRecvError::Empty) => {
+                actix_rt::spawn(async move {
+                    if let Ok(res) = rx.await {
+                        deliver_reply(&reply, res);
+                    }
+                });
+            }
+            Err(TryRecvError::Closed) => {}
+        }
+    }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.env.as_any_mut()
+    }
+
+    fn message(&self) -> Option<&dyn Any> {
+        self.env.message()
+    }
+
+    fn message_type(&self) -> &'static str {
+        self.env.message_type()
+    }
+}
+
+/// Sends the result to `reply`, or to its dead-letter handler if the recipient has stopped.
+fn deliver_reply<R>(reply: &Recipient<R>, res: R)
+where
+    R: Message + Send + 'static,
+    R::Result: Send,
+{
+    if let Err(SendError::Closed(res)) = reply.tx.do_send(res) {
+        debug!(
+            "Reply of type {} is a dead letter, its recipient has stopped",
+            type_name::<R>()
+        );
+        dead_letter::deliver(res);
+    }
 }
@@ -0,0 +1,144 @@
+use std::{marker::PhantomData, sync::Arc};
+
+use tokio::sync::oneshot::Receiver as OneshotReceiver;
+
+use super::{
+    channel::{Delivery, Sender, WeakSender},
+    SendError,
+};
+use crate::{clock::Instant, dead_letter, handler::Message};
+
+/// Sender converting messages of type `O` into messages of type `I` for the wrapped sender.
+pub(crate) struct MappedSender<I, O, F>
+where
+    I: Message + Send,
+    I::Result: Send,
+{
+    tx: Box<dyn Sender<I> + Sync>,
+    f: Arc<F>,
+    _msg: PhantomData<fn(O)>,
+}
+
+impl<I, O, F> MappedSender<I, O, F>
+where
+    I: Message + Send + 'static,
+    I::Result: Send,
+    O: Message<Result = I::Result> + Send + 'static,
+    F: Fn(O) -> I + Send + Sync + 'static,
+{
+    pub(crate) fn new(tx: Box<dyn Sender<I> + Sync>, f: Arc<F>) -> Self {
+        MappedSender {
+            tx,
+            f,
+            _msg: PhantomData,
+        }
+    }
+
+    // the message is only converted once the wrapped sender accepted it, so a refused message
+    // can be handed back unconverted
+    fn deliver(
+        &self,
+        delivery: Delivery,
+        msg: O,
+    ) -> Result<Option<OneshotReceiver<I::Result>>, SendError<O>> {
+        let mut msg = Some(msg);
+        let res = self
+            .tx
+            .send_deferred(delivery, &mut || (self.f)(msg.take().unwrap()));
+        res.map_err(|err| err.map(|_| msg.take().unwrap()))
+    }
+}
+
+impl<I, O, F> Sender<O> for MappedSender<I, O, F>
+where
+    I: Message + Send + 'static,
+    I::Result: Send,
+    O: Message<Result = I::Result> + Send + 'static,
+    F: Fn(O) -> I + Send + Sync + 'static,
+{
+    fn do_send(&self, msg: O) -> Result<(), SendError<O>> {
+        self.deliver(Delivery::DoSend, msg).map(|_| ())
+    }
+
+    fn try_send(&self, msg: O) -> Result<(), SendError<O>> {
+        self.deliver(Delivery::TrySend, msg).map(|_| ())
+    }
+
+    fn send(&self, msg: O) -> Result<OneshotReceiver<O::Result>, SendError<O>> {
+        self.deliver(Delivery::Send(None), msg)
+            .map(|rx| rx.expect("sending a message returns a receiver"))
+    }
+
+    fn send_with_deadline(
+        &self,
+        msg: O,
+        deadline: Instant,
+    ) -> Result<OneshotReceiver<O::Result>, SendError<O>> {
+        self.deliver(Delivery::Send(Some(deadline)), msg)
+            .map(|rx| rx.expect("sending a message returns a receiver"))
+    }
+
+    fn boxed(&self) -> Box<dyn Sender<O> + Sync> {
+        Box::new(MappedSender::new(self.tx.boxed(), Arc::clone(&self.f)))
+    }
+
+    fn hash(&self) -> usize {
+        self.tx.hash()
+    }
+
+    fn connected(&self) -> bool {
+        self.tx.connected()
+    }
+
+    fn downgrade(&self) -> Box<dyn WeakSender<O> + Sync + 'static> {
+        Box::new(MappedWeakSender {
+            wtx: self.tx.downgrade(),
+            f: Arc::clone(&self.f),
+            _msg: PhantomData,
+        })
+    }
+
+    fn send_deferred(
+        &self,
+        delivery: Delivery,
+        msg: &mut dyn FnMut() -> O,
+    ) -> Result<Option<OneshotReceiver<O::Result>>, SendError<()>> {
+        self.tx.send_deferred(delivery, &mut || (self.f)(msg()))
+    }
+
+    fn dead_letter(&self, msg: O) {
+        dead_letter::deliver(msg)
+    }
+}
+
+/// Weak counterpart of [`MappedSender`].
+struct MappedWeakSender<I, O, F>
+where
+    I: Message + Send,
+    I::Result: Send,
+{
+    wtx: Box<dyn WeakSender<I> + Sync>,
+    f: Arc<F>,
+    _msg: PhantomData<fn(O)>,
+}
+
+impl<I, O, F> WeakSender<O> for MappedWeakSender<I, O, F>
+where
+    I: Message + Send + 'static,
+    I::Result: Send,
+    O: Message<Result = I::Result> + Send + 'static,
+    F: Fn(O) -> I + Send + Sync + 'static,
+{
+    fn upgrade(&self) -> Option<Box<dyn Sender<O> + Sync>> {
+        let tx = self.wtx.upgrade()?;
+        Some(Box::new(MappedSender::new(tx, Arc::clone(&self.f))))
+    }
+
+    fn boxed(&self) -> Box<dyn WeakSender<O> + Sync> {
+        Box::new(MappedWeakSender {
+            wtx: self.wtx.boxed(),
+            f: Arc::clone(&self.f),
+            _msg: PhantomData,
+        })
+    }
+}
@@ -1,18 +1,30 @@
 use std::{
+    fmt,
     future::Future,
     pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
     task::{self, Poll},
     time::Duration,
 };
 
+use futures_core::{ready, stream::Stream, task::__internal::AtomicWaker};
+use futures_sink::Sink;
 use pin_project_lite::pin_project;
 use tokio::sync::oneshot;
 
 use super::{
     channel::{AddressSender, Sender},
-    MailboxError, SendError,
+    Addr, MailboxError, PipeError, SendError, ToEnvelope,
+};
+use crate::{
+    actor::Actor,
+    clock::{Instant, Sleep},
+    context_impl::AsyncContextParts,
+    handler::{Handler, Message},
 };
-use crate::{clock::Sleep, handler::Message};
 
 pub type Request<A, M> = MsgRequest<AddressSender<A>, M>;
 
@@ -32,6 +44,7 @@ pin_project! {
         info: Option<(S, M)>,
         #[pin]
         timeout: Option<Sleep>,
+        deadline: Option<Instant>,
     }
 }
 
@@ -46,9 +59,20 @@ where
             rx,
             info,
             timeout: None,
+            deadline: None,
         }
     }
 
+    /// Marks the message as dropped unhandled once `deadline` passes.
+    pub(crate) fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    fn expired(deadline: Option<Instant>) -> bool {
+        deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
     #[cfg(test)]
     pub(crate) fn rx_is_some(&self) -> bool {
         self.rx.is_some()
@@ -59,6 +83,26 @@ where
         self.timeout = Some(actix_rt::time::sleep(dur));
         self
     }
+
+    /// Sends the response to this request as a message to `addr`.
+    ///
+    /// The returned future resolves with the response of the second actor, so multi-stage
+    /// pipelines can be written as a chain of calls instead of nested `await`s. It fails with a
+    /// [`MailboxError`] if either actor does not respond. The message to `addr` is only sent
+    /// once the first response has arrived.
+    pub fn and_send_to<B>(self, addr: Addr<B>) -> AndSendTo<S, M, B>
+    where
+        B: Handler<M::Result>,
+        B::Context: ToEnvelope<B, M::Result>,
+        M::Result: Message + 'static,
+        <M::Result as Message>::Result: Send,
+    {
+        AndSendTo {
+            first: self,
+            addr: Some(addr),
+            second: None,
+        }
+    }
 }
 
 impl<S, M> Future for MsgRequest<S, M>
@@ -73,7 +117,15 @@ where
         let this = self.project();
 
         if let Some((sender, msg)) = this.info.take() {
-            match sender.send(msg) {
+            if Self::expired(*this.deadline) {
+                return Poll::Ready(Err(MailboxError::Timeout));
+            }
+
+            let res = match *this.deadline {
+                Some(deadline) => sender.send_with_deadline(msg, deadline),
+                None => sender.send(msg),
+            };
+            match res {
                 Ok(rx) => *this.rx = Some(rx),
                 Err(SendError::Full(msg)) => {
                     *this.info = Some((sender, msg));
@@ -85,6 +137,9 @@ where
 
         match this.rx {
             Some(rx) => match Pin::new(rx).poll(cx) {
+                Poll::Ready(Err(_)) if Self::expired(*this.deadline) => {
+                    Poll::Ready(Err(MailboxError::Timeout))
+                }
                 Poll::Ready(res) => Poll::Ready(res.map_err(|_| MailboxError::Closed)),
                 Poll::Pending => match this.timeout.as_pin_mut() {
                     Some(timeout) => timeout.poll(cx).map(|_| Err(MailboxError::Timeout)),
@@ -95,3 +150,452 @@ where
         }
     }
 }
+
+pin_project! {
+    /// A `Future` sending the response of a request on to another actor.
+    ///
+    /// Created by [`MsgRequest::and_send_to`].
+    #[must_use = "You must wait on the request otherwise the Message will not be delivered"]
+    pub struct AndSendTo<S, M, B>
+    where
+        S: Sender<M>,
+        M: Message,
+        M: Send,
+        M::Result: Message,
+        M::Result: Send,
+        M::Result: 'static,
+        <M::Result as Message>::Result: Send,
+        B: Handler<M::Result>,
+        B::Context: ToEnvelope<B, M::Result>,
+    {
+        #[pin]
+        first: MsgRequest<S, M>,
+        addr: Option<Addr<B>>,
+        #[pin]
+        second: Option<Request<B, M::Result>>,
+    }
+}
+
+impl<S, M, B> Future for AndSendTo<S, M, B>
+where
+    S: Sender<M>,
+    M: Message + Send,
+    M::Result: Message + Send + 'static,
+    <M::Result as Message>::Result: Send,
+    B: Handler<M::Result>,
+    B::Context: ToEnvelope<B, M::Result>,
+{
+    type Output = Result<<M::Result as Message>::Result, MailboxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if let Some(addr) = this.addr.take() {
+            match this.first.poll(cx) {
+                Poll::Ready(Ok(msg)) => this.second.set(Some(addr.send(msg))),
+                Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                Poll::Pending => {
+                    *this.addr = Some(addr);
+                    return Poll::Pending;
+                }
+            }
+        }
+
+        match this.second.as_pin_mut() {
+            Some(second) => second.poll(cx),
+            None => Poll::Ready(Err(MailboxError::Closed)),
+        }
+    }
+}
+
+/// A token for cancelling a request sent with [`Addr::send_cancellable`](super::Addr::send_cancellable).
+///
+/// Cancelling resolves the request with [`MailboxError::Closed`] and drops the response. A
+/// message that is still queued is dropped unhandled; a handler that is already running keeps
+/// going unless it checks the token, see [`Context::is_cancelled`](crate::Context::is_cancelled).
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelInner>,
+}
+
+#[derive(Default)]
+struct CancelInner {
+    cancelled: AtomicBool,
+    waker: AtomicWaker,
+}
+
+impl CancelToken {
+    /// Cancels the request.
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Release);
+        self.inner.waker.wake();
+    }
+
+    /// Returns `true` if the request has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for CancelToken {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CancelToken")
+            .field("cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// A `Future` which represents a cancellable message sending process.
+///
+/// Created by [`Addr::send_cancellable`](super::Addr::send_cancellable).
+#[must_use = "You must wait on the request otherwise the Message will not be delivered"]
+pub struct CancellableRequest<A, M>
+where
+    A: Actor,
+    M: Message + Send,
+    M::Result: Send,
+{
+    rx: Option<oneshot::Receiver<M::Result>>,
+    info: Option<(AddressSender<A>, M)>,
+    token: CancelToken,
+}
+
+impl<A, M> CancellableRequest<A, M>
+where
+    A: Actor,
+    M: Message + Send,
+    M::Result: Send,
+{
+    pub(crate) fn new(
+        rx: Option<oneshot::Receiver<M::Result>>,
+        info: Option<(AddressSender<A>, M)>,
+        token: CancelToken,
+    ) -> Self {
+        Self { rx, info, token }
+    }
+}
+
+impl<A, M> Unpin for CancellableRequest<A, M>
+where
+    A: Actor,
+    M: Message + Send,
+    M::Result: Send,
+{
+}
+
+impl<A, M> Future for CancellableRequest<A, M>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M> + AsyncContextParts<A>,
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    type Output = Result<M::Result, MailboxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        this.token.inner.waker.register(cx.waker());
+        if this.token.is_cancelled() {
+            this.rx = None;
+            this.info = None;
+            return Poll::Ready(Err(MailboxError::Closed));
+        }
+
+        if let Some((sender, msg)) = this.info.take() {
+            match sender.send_cancellable(msg, this.token.clone()) {
+                Ok(rx) => this.rx = Some(rx),
+                Err(SendError::Full(msg)) => {
+                    this.info = Some((sender, msg));
+                    return Poll::Pending;
+                }
+                Err(SendError::Closed(_)) => return Poll::Ready(Err(MailboxError::Closed)),
+            }
+        }
+
+        match this.rx {
+            Some(ref mut rx) => Pin::new(rx).poll(cx).map_err(|_| MailboxError::Closed),
+            None => Poll::Ready(Err(MailboxError::Closed)),
+        }
+    }
+}
+
+pin_project! {
+    /// A `Future` forwarding the responses to a stream of requests into a sink.
+    ///
+    /// Created by [`Addr::pipe_to_sink`](super::Addr::pipe_to_sink).
+    #[must_use = "futures do nothing unless polled"]
+    pub struct PipeToSink<A, M, St, Si>
+    where
+        A: Actor,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M: Message,
+        M: Send,
+        M: 'static,
+        M::Result: Send,
+    {
+        addr: Addr<A>,
+        #[pin]
+        requests: St,
+        #[pin]
+        request: Option<Request<A, M>>,
+        response: Option<M::Result>,
+        #[pin]
+        sink: Si,
+    }
+}
+
+impl<A, M, St, Si> PipeToSink<A, M, St, Si>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    pub(crate) fn new(addr: Addr<A>, requests: St, sink: Si) -> Self {
+        PipeToSink {
+            addr,
+            requests,
+            request: None,
+            response: None,
+            sink,
+        }
+    }
+}
+
+impl<A, M, St, Si> Future for PipeToSink<A, M, St, Si>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Send + 'static,
+    M::Result: Send,
+    St: Stream<Item = M>,
+    Si: Sink<M::Result>,
+{
+    type Output = Result<(), PipeError<Si::Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            if this.response.is_some() {
+                ready!(this.sink.as_mut().poll_ready(cx)).map_err(PipeError::Sink)?;
+                let response = this.response.take().unwrap();
+                this.sink
+                    .as_mut()
+                    .start_send(response)
+                    .map_err(PipeError::Sink)?;
+            }
+
+            if let Some(request) = this.request.as_mut().as_pin_mut() {
+                let response = ready!(request.poll(cx)).map_err(PipeError::Mailbox)?;
+                this.request.set(None);
+                *this.response = Some(response);
+                continue;
+            }
+
+            match this.requests.as_mut().poll_next(cx) {
+                Poll::Ready(Some(msg)) => this.request.set(Some(this.addr.send(msg))),
+                Poll::Ready(None) => {
+                    ready!(this.sink.as_mut().poll_close(cx)).map_err(PipeError::Sink)?;
+                    return Poll::Ready(Ok(()));
+                }
+                Poll::Pending => {
+                    // deliver what was sent so far while waiting for more requests
+                    ready!(this.sink.as_mut().poll_flush(cx)).map_err(PipeError::Sink)?;
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+pin_project! {
+    /// A `Stream` of the responses to a message sent to an actor over and over.
+    ///
+    /// Created by [`Addr::into_stream`](super::Addr::into_stream).
+    #[must_use = "streams do nothing unless polled"]
+    pub struct ResponseStream<A, M>
+    where
+        A: Actor,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M: Message,
+        M: Send,
+        M: 'static,
+        M::Result: Send,
+    {
+        addr: Addr<A>,
+        msg: M,
+        #[pin]
+        request: Option<Request<A, M>>,
+        done: bool,
+    }
+}
+
+impl<A, M> ResponseStream<A, M>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    pub(crate) fn new(addr: Addr<A>, msg: M) -> Self {
+        ResponseStream {
+            addr,
+            msg,
+            request: None,
+            done: false,
+        }
+    }
+}
+
+impl<A, M> Stream for ResponseStream<A, M>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Clone + Send + 'static,
+    M::Result: Send,
+{
+    type Item = Result<M::Result, MailboxError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        if this.request.is_none() {
+            let request = this.addr.send(this.msg.clone());
+            this.request.set(Some(request));
+        }
+
+        let res = ready!(this.request.as_mut().as_pin_mut().unwrap().poll(cx));
+        this.request.set(None);
+
+        match res {
+            Err(MailboxError::Closed) => {
+                *this.done = true;
+                Poll::Ready(None)
+            }
+            res => Poll::Ready(Some(res)),
+        }
+    }
+}
+
+pin_project! {
+    /// A future forwarding the responses of one actor to another, one at a time.
+    ///
+    /// Created by [`Addr::forward_to`](super::Addr::forward_to). Resolves to the number of
+    /// responses forwarded.
+    #[must_use = "futures do nothing unless polled"]
+    pub struct ForwardTo<A, M, B>
+    where
+        A: Actor,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        M: Message,
+        M: Send,
+        M: 'static,
+        M::Result: Message,
+        M::Result: Send,
+        M::Result: 'static,
+        <M::Result as Message>::Result: Send,
+        B: Handler<M::Result>,
+        B::Context: ToEnvelope<B, M::Result>,
+    {
+        #[pin]
+        responses: ResponseStream<A, M>,
+        dest: Addr<B>,
+        #[pin]
+        pending: Option<Request<B, M::Result>>,
+        forwarded: usize,
+    }
+}
+
+impl<A, M, B> ForwardTo<A, M, B>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Send + 'static,
+    M::Result: Message + Send + 'static,
+    <M::Result as Message>::Result: Send,
+    B: Handler<M::Result>,
+    B::Context: ToEnvelope<B, M::Result>,
+{
+    pub(crate) fn new(responses: ResponseStream<A, M>, dest: Addr<B>) -> Self {
+        ForwardTo {
+            responses,
+            dest,
+            pending: None,
+            forwarded: 0,
+        }
+    }
+}
+
+impl<A, M, B> Future for ForwardTo<A, M, B>
+where
+    A: Actor + Handler<M>,
+    A::Context: ToEnvelope<A, M>,
+    M: Message + Clone + Send + 'static,
+    M::Result: Message + Send + 'static,
+    <M::Result as Message>::Result: Send,
+    B: Handler<M::Result>,
+    B::Context: ToEnvelope<B, M::Result>,
+{
+    type Output = usize;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<usize> {
+        let mut this = self.project();
+
+        loop {
+            if let Some(pending) = this.pending.as_mut().as_pin_mut() {
+                let res = ready!(pending.poll(cx));
+                this.pending.set(None);
+                match res {
+                    Ok(_) => *this.forwarded += 1,
+                    Err(_) => return Poll::Ready(*this.forwarded),
+                }
+            }
+
+            match ready!(this.responses.as_mut().poll_next(cx)) {
+                Some(Ok(item)) => this.pending.set(Some(this.dest.send(item))),
+                Some(Err(_)) | None => return Poll::Ready(*this.forwarded),
+            }
+        }
+    }
+}
+
+/// A future resolving once a message can be sent to an actor without waiting for room in its
+/// mailbox.
+///
+/// Created by [`Addr::ready`](super::Addr::ready).
+#[must_use = "futures do nothing unless polled"]
+pub struct MailboxReady<'a, A: Actor> {
+    tx: &'a AddressSender<A>,
+}
+
+impl<'a, A: Actor> MailboxReady<'a, A> {
+    pub(crate) fn new(tx: &'a AddressSender<A>) -> Self {
+        MailboxReady { tx }
+    }
+}
+
+impl<A: Actor> fmt::Debug for MailboxReady<'_, A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("MailboxReady").finish_non_exhaustive()
+    }
+}
+
+impl<A: Actor> Future for MailboxReady<'_, A> {
+    type Output = Result<(), MailboxError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        match ready!(self.tx.poll_ready(cx)) {
+            true => Poll::Ready(Ok(())),
+            false => Poll::Ready(Err(MailboxError::Closed)),
+        }
+    }
+}
@@ -1,21 +1,36 @@
 use std::{
     error, fmt,
     hash::{Hash, Hasher},
+    sync::Arc,
+    time::Duration,
 };
 
 pub(crate) mod channel;
 mod envelope;
+mod mapped;
 mod message;
 mod queue;
+mod recipient_set;
+mod self_notifier;
 
 pub(crate) use self::channel::{AddressReceiver, AddressSenderProducer};
-use self::channel::{AddressSender, Sender, WeakAddressSender, WeakSender};
+use self::{
+    channel::{AddressSender, Sender, WeakAddressSender, WeakSender},
+    mapped::MappedSender,
+};
 pub use self::{
     envelope::{Envelope, EnvelopeProxy, ToEnvelope},
-    message::{RecipientRequest, Request},
+    message::{
+        AndSendTo, CancelToken, CancellableRequest, ForwardTo, MailboxReady, PipeToSink,
+        RecipientRequest, Request, ResponseStream,
+    },
+    recipient_set::RecipientSet,
+    self_notifier::SelfNotifier,
 };
 use crate::{
-    actor::Actor,
+    actor::{Actor, AsyncContext},
+    clock::Instant,
+    context_impl::AsyncContextParts,
     handler::{Handler, Message},
 };
 
@@ -26,8 +41,16 @@ pub enum SendError<T> {
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 /// The errors that can occur during the message delivery process.
+///
+/// This is a plain `Send + Sync + 'static` error without an underlying [`source`], so it can be
+/// boxed into `Box<dyn Error + Send + Sync>` and recovered with `downcast_ref`.
+///
+/// [`source`]: std::error::Error::source
 pub enum MailboxError {
+    /// The actor stopped before handling the message, or before responding to it.
     Closed,
+    /// The response did not arrive within the time set with `Request::timeout`, or the
+    /// message expired in the mailbox, see [`Addr::send_with_ttl`].
     Timeout,
 }
 
@@ -48,12 +71,40 @@ impl fmt::Display for MailboxError {
 
 impl error::Error for MailboxError {}
 
+/// The errors that can stop [`Addr::pipe_to_sink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PipeError<E> {
+    /// The actor stopped or a request timed out.
+    Mailbox(MailboxError),
+    /// The sink failed.
+    Sink(E),
+}
+
+impl<E: fmt::Display> fmt::Display for PipeError<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PipeError::Mailbox(err) => write!(fmt, "{}", err),
+            PipeError::Sink(err) => write!(fmt, "Sink failed: {}", err),
+        }
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> error::Error for PipeError<E> {}
+
 impl<T> SendError<T> {
     pub fn into_inner(self) -> T {
         match self {
             SendError::Full(msg) | SendError::Closed(msg) => msg,
         }
     }
+
+    /// Replaces the message carried by the error.
+    pub(crate) fn map<U>(self, f: impl FnOnce(T) -> U) -> SendError<U> {
+        match self {
+            SendError::Full(msg) => SendError::Full(f(msg)),
+            SendError::Closed(msg) => SendError::Closed(f(msg)),
+        }
+    }
 }
 
 impl<T> error::Error for SendError<T> {}
@@ -77,6 +128,17 @@ impl<T> fmt::Display for SendError<T> {
 }
 
 /// The address of an actor.
+///
+/// Messages queued by one sender are handled in the order they were queued, also when other
+/// senders queue messages through clones of the same address concurrently. A message counts as
+/// queued once `do_send` or `try_send` returns `Ok`, or once the request returned by `send` has
+/// been polled while the mailbox has capacity. A `send` request waiting on a full mailbox can
+/// therefore be overtaken by a later `do_send` from the same sender.
+///
+/// Addresses compare equal and hash alike when they point to the same mailbox, i.e. when one
+/// is a clone of the other. This compares the identity of actors, not their state, and allows
+/// keeping a set of unique actors in a `HashSet`. Addresses of distinct actors of the same type
+/// are never equal.
 pub struct Addr<A: Actor> {
     tx: AddressSender<A>,
 }
@@ -92,10 +154,25 @@ impl<A: Actor> Addr<A> {
         self.tx.connected()
     }
 
+    /// Returns the number of messages waiting in the actor's mailbox.
+    ///
+    /// Messages sent with [`AsyncContext::notify`] and the message being handled are not
+    /// counted. The value may be outdated by the time it is used, as other senders and the
+    /// actor keep changing it.
+    pub fn mailbox_len(&self) -> usize {
+        self.tx.queued()
+    }
+
     /// Sends a message unconditionally, ignoring any potential errors.
     ///
     /// The message is always queued, even if the mailbox for the receiver is full. If the mailbox
-    /// is closed, the message is silently dropped.
+    /// is closed, the message is passed to its [dead-letter handler](crate::dead_letter::DeadLetters)
+    /// or silently dropped if there is none.
+    ///
+    /// This can be called from any thread, including ones that run neither an actix system nor
+    /// a Tokio runtime, e.g. a thread servicing a C callback. The message is queued and the
+    /// actor's arbiter is woken to handle it. Dead letters are only delivered from threads
+    /// belonging to a system; elsewhere, messages to a closed mailbox are dropped.
     #[inline]
     pub fn do_send<M>(&self, msg: M)
     where
@@ -104,7 +181,39 @@ impl<A: Actor> Addr<A> {
         A: Handler<M>,
         A::Context: ToEnvelope<A, M>,
     {
-        let _ = self.tx.do_send(msg);
+        if let Err(SendError::Closed(msg)) = self.tx.do_send(msg) {
+            <A::Context as ToEnvelope<A, M>>::dead_letter(msg);
+        }
+    }
+
+    /// Sends a message like [`do_send`](Self::do_send), but hands it back if the actor has
+    /// stopped.
+    ///
+    /// The message is queued regardless of the mailbox capacity, without waiting and without
+    /// setting up a response channel. `Ok` means it was queued; the only error is
+    /// [`SendError::Closed`], which carries the message and is returned instead of passing the
+    /// message to a [dead-letter handler](crate::dead_letter::DeadLetters). Unlike
+    /// [`try_send`](Self::try_send), a full mailbox is not an error.
+    pub fn do_send_checked<M>(&self, msg: M) -> Result<(), SendError<M>>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+    {
+        self.tx.do_send(msg)
+    }
+
+    /// Waits until the actor's mailbox has room for a message from this address.
+    ///
+    /// Resolves once [`try_send`](Self::try_send) would queue a message instead of failing with
+    /// [`SendError::Full`], or fails with [`MailboxError::Closed`] once the actor has stopped.
+    /// This lets a producer throttle itself to the pace of the actor instead of retrying in a
+    /// loop. Readiness is per address: every clone of an `Addr` is guaranteed a slot of its own
+    /// on top of the mailbox capacity, so the next message sent from this address is queued even
+    /// if another one fills the mailbox in the meantime.
+    pub fn ready(&self) -> MailboxReady<'_, A> {
+        MailboxReady::new(&self.tx)
     }
 
     /// Tries to send a message.
@@ -121,10 +230,84 @@ impl<A: Actor> Addr<A> {
         self.tx.try_send(msg, true)
     }
 
+    /// Sends a message unless the actor's mailbox is at capacity.
+    ///
+    /// Like [`do_send`](Self::do_send), this does not wait, but when the mailbox already holds
+    /// as many messages as its [capacity](crate::Context::set_mailbox_capacity) allows, the
+    /// message is passed to `on_overflow` instead of being queued. The task is never parked. If
+    /// the mailbox is closed, the message is passed to its
+    /// [dead-letter handler](crate::dead_letter::DeadLetters) or silently dropped if there is none.
+    pub fn do_send_bounded<M, F>(&self, msg: M, on_overflow: F)
+    where
+        M: Message + Send,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        F: FnOnce(M),
+    {
+        match self.tx.do_send_bounded(msg) {
+            Ok(()) => {}
+            Err(SendError::Full(msg)) => on_overflow(msg),
+            Err(SendError::Closed(msg)) => <A::Context as ToEnvelope<A, M>>::dead_letter(msg),
+        }
+    }
+
+    /// Sends a message unconditionally, delivering the handler's result as a message to `reply`.
+    ///
+    /// Like [`do_send`](Self::do_send), this ignores the mailbox capacity and does not wait for
+    /// the response, which makes it possible to chain actors into pipelines without holding a
+    /// future. The result is sent to `reply` once the handler produces it, even if this actor
+    /// has stopped in the meantime. If the actor handling `reply` has stopped by then, the result
+    /// is passed to its [dead-letter handler](crate::dead_letter::DeadLetters) or dropped if
+    /// there is none. No result is sent if the handler never produces one, e.g. because its
+    /// response future is cancelled when this actor stops.
+    pub fn do_send_with_reply<M>(&self, msg: M, reply: Recipient<M::Result>)
+    where
+        M: Message + Send,
+        M::Result: Message + Send + 'static,
+        <M::Result as Message>::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M> + AsyncContext<A>,
+    {
+        let _ = self.tx.do_send_with_reply(msg, reply);
+    }
+
     /// Sends an asynchronous message and waits for a response.
     ///
     /// The communication channel to the actor is bounded. If the returned request future gets
     /// dropped, the message is cancelled.
+    ///
+    /// The actor may run on another thread, so both the message and its result must be `Send`.
+    /// A result type that is not, such as `Rc<T>`, is rejected at the call to `send`:
+    ///
+    /// ```compile_fail,E0277
+    /// # use std::rc::Rc;
+    /// # use actix::prelude::*;
+    /// struct Get;
+    ///
+    /// impl Message for Get {
+    ///     type Result = Rc<u32>;
+    /// }
+    ///
+    /// struct Store;
+    ///
+    /// impl Actor for Store {
+    ///     type Context = Context<Self>;
+    /// }
+    ///
+    /// impl Handler<Get> for Store {
+    ///     type Result = MessageResult<Get>;
+    ///
+    ///     fn handle(&mut self, _: Get, _: &mut Context<Self>) -> Self::Result {
+    ///         MessageResult(Rc::new(1))
+    ///     }
+    /// }
+    ///
+    /// # async fn run(addr: Addr<Store>) {
+    /// // error[E0277]: `Rc<u32>` cannot be sent between threads safely
+    /// let value = addr.send(Get).await;
+    /// # }
+    /// ```
     #[inline]
     pub fn send<M>(&self, msg: M) -> Request<A, M>
     where
@@ -140,6 +323,144 @@ impl<A: Actor> Addr<A> {
         }
     }
 
+    /// Sends an asynchronous message that expires after `ttl` and waits for a response.
+    ///
+    /// If the actor has not started handling the message within `ttl`, the message is passed to
+    /// its [dead-letter handler](crate::dead_letter::DeadLetters), or dropped if there is none,
+    /// without being handled and the request resolves to [`MailboxError::Timeout`]. This keeps an
+    /// overloaded actor from spending effort on stale work. Once handling has started, the
+    /// message is processed to completion regardless of the TTL.
+    pub fn send_with_ttl<M>(&self, msg: M, ttl: Duration) -> Request<A, M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+    {
+        let deadline = Instant::now() + ttl;
+        let req = match self.tx.send_with_deadline(msg, deadline) {
+            Ok(rx) => Request::new(Some(rx), None),
+            Err(SendError::Full(msg)) => Request::new(None, Some((self.tx.clone(), msg))),
+            Err(SendError::Closed(_)) => Request::new(None, None),
+        };
+        req.with_deadline(deadline)
+    }
+
+    /// Sends an asynchronous message that can be cancelled before its response arrives.
+    ///
+    /// Returns the request together with a [`CancelToken`]. Cancelling the token makes the
+    /// request resolve to [`MailboxError::Closed`] and drops the response. A message that is still
+    /// queued is dropped unhandled. A handler that is already running can check
+    /// [`Context::is_cancelled`](crate::Context::is_cancelled) to abort early.
+    pub fn send_cancellable<M>(&self, msg: M) -> (CancellableRequest<A, M>, CancelToken)
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M> + AsyncContextParts<A>,
+    {
+        let token = CancelToken::default();
+        let req = match self.tx.send_cancellable(msg, token.clone()) {
+            Ok(rx) => CancellableRequest::new(Some(rx), None, token.clone()),
+            Err(SendError::Full(msg)) => {
+                CancellableRequest::new(None, Some((self.tx.clone(), msg)), token.clone())
+            }
+            Err(SendError::Closed(_)) => CancellableRequest::new(None, None, token.clone()),
+        };
+        (req, token)
+    }
+
+    /// Sends each message of `requests` to the actor and forwards the responses into `sink`.
+    ///
+    /// Requests are sent one at a time, so responses reach the sink in the order of the
+    /// requests. The returned future resolves:
+    ///
+    /// - to `Ok(())` once `requests` ends and the sink has been closed;
+    /// - to [`PipeError::Mailbox`] when the actor stops, leaving the sink open;
+    /// - to [`PipeError::Sink`] as soon as the sink fails, e.g. because its peer went away. The
+    ///   response that could not be sent is dropped. The actor is not notified; whoever awaits
+    ///   the pipe decides how to react, e.g. an actor spawning the pipe into its own context
+    ///   can stop itself.
+    ///
+    /// Dropping the future stops the pipe.
+    pub fn pipe_to_sink<M, St, Si>(&self, requests: St, sink: Si) -> PipeToSink<A, M, St, Si>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        St: futures_core::stream::Stream<Item = M>,
+        Si: futures_sink::Sink<M::Result>,
+    {
+        PipeToSink::new(self.clone(), requests, sink)
+    }
+
+    /// Returns a stream sending `msg` to the actor over and over, yielding each response.
+    ///
+    /// This is polling-based: a copy of `msg` is only sent once the previous response has been
+    /// taken from the stream, so a handler answering e.g. "the next event" is asked once per
+    /// item. The stream ends once the actor stops.
+    ///
+    /// ```
+    /// # use actix::prelude::*;
+    /// use futures_util::stream::StreamExt as _;
+    ///
+    /// #[derive(Clone, Message)]
+    /// #[rtype(result = "usize")]
+    /// struct Next;
+    ///
+    /// struct Counter(usize);
+    ///
+    /// impl Actor for Counter {
+    ///     type Context = Context<Self>;
+    /// }
+    ///
+    /// impl Handler<Next> for Counter {
+    ///     type Result = usize;
+    ///
+    ///     fn handle(&mut self, _: Next, _: &mut Self::Context) -> usize {
+    ///         self.0 += 1;
+    ///         self.0
+    ///     }
+    /// }
+    ///
+    /// # #[actix::main]
+    /// # async fn main() {
+    /// let counts = Counter(0).start().into_stream(Next).take(3);
+    /// let counts: Vec<_> = counts.collect().await;
+    /// assert_eq!(counts, [Ok(1), Ok(2), Ok(3)]);
+    /// # }
+    /// ```
+    pub fn into_stream<M>(self, msg: M) -> ResponseStream<A, M>
+    where
+        M: Message + Clone + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+    {
+        ResponseStream::new(self, msg)
+    }
+
+    /// Sends `msg` to the actor over and over like [`into_stream`](Addr::into_stream), sending
+    /// each response on to `dest`.
+    ///
+    /// A response is only forwarded once `dest` has handled the previous one, and the actor is
+    /// only asked for the next response after that. A slow or full `dest` therefore holds the
+    /// producing actor back instead of letting messages pile up in its mailbox. The returned
+    /// future resolves to the number of responses forwarded once either actor stops.
+    pub fn forward_to<M, B>(self, msg: M, dest: Addr<B>) -> ForwardTo<A, M, B>
+    where
+        M: Message + Clone + Send + 'static,
+        M::Result: Message + Send + 'static,
+        <M::Result as Message>::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+        B: Handler<M::Result>,
+        B::Context: ToEnvelope<B, M::Result>,
+    {
+        ForwardTo::new(self.into_stream(msg), dest)
+    }
+
     /// Returns the [`Recipient`] for a specific message type.
     pub fn recipient<M>(self) -> Recipient<M>
     where
@@ -270,8 +591,18 @@ where
     /// Sends a message.
     ///
     /// The message is always queued, even if the mailbox for the receiver is full. If the mailbox
-    /// is closed, the message is silently dropped.
+    /// is closed, the message is passed to its [dead-letter handler](crate::dead_letter::DeadLetters)
+    /// or silently dropped if there is none.
+    ///
+    /// Like [`Addr::do_send`], this can be called from threads outside of any actix system.
     pub fn do_send(&self, msg: M) {
+        if let Err(SendError::Closed(msg)) = self.tx.do_send(msg) {
+            self.tx.dead_letter(msg);
+        }
+    }
+
+    /// Like [`do_send`](Self::do_send), but drops the message if the mailbox is closed.
+    pub(crate) fn do_send_unrouted(&self, msg: M) {
         let _ = self.tx.do_send(msg);
     }
 
@@ -305,6 +636,55 @@ where
             wtx: self.tx.downgrade(),
         }
     }
+
+    /// Returns a recipient of messages of type `O`, which are converted with `f` and sent to this
+    /// recipient.
+    ///
+    /// A message is only converted once the actor's mailbox accepted it, so messages refused
+    /// with a [`SendError`] are handed back unconverted. The mapped recipient compares equal to
+    /// other recipients mapped from the same actor.
+    ///
+    /// ```
+    /// # use actix::prelude::*;
+    /// #[derive(Message)]
+    /// #[rtype(result = "()")]
+    /// enum SessionEvent {
+    ///     Text(String),
+    ///     Closed,
+    /// }
+    ///
+    /// #[derive(Message)]
+    /// #[rtype(result = "()")]
+    /// struct ChatEvent(String);
+    ///
+    /// struct Session;
+    ///
+    /// impl Actor for Session {
+    ///     type Context = Context<Self>;
+    /// }
+    ///
+    /// impl Handler<SessionEvent> for Session {
+    ///     type Result = ();
+    ///
+    ///     fn handle(&mut self, _: SessionEvent, _: &mut Self::Context) {}
+    /// }
+    ///
+    /// # #[actix::main]
+    /// # async fn main() {
+    /// let session = Session.start().recipient::<SessionEvent>();
+    /// let chat: Recipient<ChatEvent> = session.map(|ChatEvent(text)| SessionEvent::Text(text));
+    ///
+    /// chat.do_send(ChatEvent("hello".to_owned()));
+    /// # }
+    /// ```
+    pub fn map<O, F>(self, f: F) -> Recipient<O>
+    where
+        M: 'static,
+        O: Message<Result = M::Result> + Send + 'static,
+        F: Fn(O) -> M + Send + Sync + 'static,
+    {
+        Recipient::new(Box::new(MappedSender::new(self.tx, Arc::new(f))))
+    }
 }
 
 impl<A: Actor, M: Message + Send + 'static> From<Addr<A>> for Recipient<M>
@@ -445,6 +825,45 @@ where
     }
 }
 
+/// Creates a tuple of [`Recipient`]s, one for each listed message type, from an [`Addr`].
+///
+/// This is shorthand for calling [`Addr::recipient`] on a clone of the address for every message
+/// type. Each message type is checked to be handled by the actor at compile time.
+///
+/// ```
+/// # use actix::prelude::*;
+/// # #[derive(Message)]
+/// # #[rtype(result = "()")]
+/// # struct Join;
+/// # #[derive(Message)]
+/// # #[rtype(result = "()")]
+/// # struct Leave;
+/// # struct Session;
+/// # impl Actor for Session {
+/// #     type Context = Context<Self>;
+/// # }
+/// # impl Handler<Join> for Session {
+/// #     type Result = ();
+/// #     fn handle(&mut self, _: Join, _: &mut Self::Context) {}
+/// # }
+/// # impl Handler<Leave> for Session {
+/// #     type Result = ();
+/// #     fn handle(&mut self, _: Leave, _: &mut Self::Context) {}
+/// # }
+/// # #[actix::main]
+/// # async fn main() {
+/// let addr = Session.start();
+/// let (join, leave): (Recipient<Join>, Recipient<Leave>) = actix::recipients!(addr => [Join, Leave]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! recipients {
+    ($addr:expr => [$($msg:ty),+ $(,)?]) => {{
+        let addr = &$addr;
+        ($($crate::Addr::clone(addr).recipient::<$msg>(),)+)
+    }};
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::{
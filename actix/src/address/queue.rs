@@ -123,23 +123,55 @@ impl<T> Queue<T> {
     ///
     /// This function is unsafe because only one thread can call it at a time.
     pub(super) unsafe fn pop(&self) -> PopResult<T> {
-        let tail = *self.tail.get();
-        let next = (*tail).next.load(Ordering::Acquire);
-
-        if !next.is_null() {
-            *self.tail.get() = next;
-            assert!((*tail).value.is_none());
-            assert!((*next).value.is_some());
-            let ret = (*next).value.take().unwrap();
-            drop(Box::from_raw(tail));
-            return Data(ret);
+        loop {
+            let tail = *self.tail.get();
+            let next = (*tail).next.load(Ordering::Acquire);
+
+            if !next.is_null() {
+                *self.tail.get() = next;
+                assert!((*tail).value.is_none());
+                drop(Box::from_raw(tail));
+                // nodes emptied by `take_where` are skipped
+                match (*next).value.take() {
+                    Some(ret) => return Data(ret),
+                    None => continue,
+                }
+            }
+
+            return if self.head.load(Ordering::Acquire) == tail {
+                Empty
+            } else {
+                Inconsistent
+            };
         }
+    }
 
-        if self.head.load(Ordering::Acquire) == tail {
-            Empty
-        } else {
-            Inconsistent
+    /// Takes out the values for which `f` returns `Some`, leaving the other values queued in
+    /// their original order.
+    ///
+    /// Values are taken out of their nodes in place rather than popped and pushed again, so
+    /// values pushed concurrently stay behind the ones already queued. Such values may or may
+    /// not be visited.
+    ///
+    /// This function is unsafe because only the popping thread can call it, and not while it
+    /// pops.
+    pub(super) unsafe fn take_where<U, F>(&self, mut f: F) -> Vec<U>
+    where
+        F: FnMut(&mut T) -> Option<U>,
+    {
+        let mut taken = Vec::new();
+        // pushers only write to the `next` of the last node, never to values already queued
+        let mut node = (*(*self.tail.get())).next.load(Ordering::Acquire);
+        while !node.is_null() {
+            if let Some(value) = &mut (*node).value {
+                if let Some(u) = f(value) {
+                    taken.push(u);
+                    (*node).value = None;
+                }
+            }
+            node = (*node).next.load(Ordering::Acquire);
         }
+        taken
     }
 
     /// Pop an element similarly to `pop` function, but spin-wait on inconsistent
@@ -0,0 +1,172 @@
+use std::{collections::HashSet, fmt};
+
+use super::Recipient;
+use crate::handler::Message;
+
+/// A set of [`Recipient`]s of the same message type, for broadcasting messages.
+///
+/// Recipients are compared by the actor they point to, so inserting a second recipient of the
+/// same actor has no effect. Recipients of stopped actors are not removed automatically; call
+/// [`prune`](RecipientSet::prune) periodically, e.g. from
+/// [`run_interval`](crate::AsyncContext::run_interval), or enable
+/// [`set_prune_on_send`](RecipientSet::set_prune_on_send) to drop them while broadcasting.
+///
+/// ```
+/// # use actix::prelude::*;
+/// #[derive(Clone, Message)]
+/// #[rtype(result = "()")]
+/// struct Announce(String);
+///
+/// struct Member;
+///
+/// impl Actor for Member {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<Announce> for Member {
+///     type Result = ();
+///
+///     fn handle(&mut self, _: Announce, _: &mut Self::Context) {}
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let mut members = RecipientSet::new();
+/// members.insert(Member.start().recipient());
+/// members.insert(Member.start().recipient());
+///
+/// members.broadcast(Announce("hello".to_owned()));
+/// # }
+/// ```
+pub struct RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    recipients: HashSet<Recipient<M>>,
+    prune_on_send: bool,
+}
+
+impl<M> RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        RecipientSet {
+            recipients: HashSet::new(),
+            prune_on_send: false,
+        }
+    }
+
+    /// Sets whether [`broadcast`](RecipientSet::broadcast) removes disconnected recipients it
+    /// comes across.
+    pub fn set_prune_on_send(&mut self, prune: bool) {
+        self.prune_on_send = prune;
+    }
+
+    /// Adds a recipient to the set. Returns `false` if it was already present.
+    pub fn insert(&mut self, recipient: Recipient<M>) -> bool {
+        self.recipients.insert(recipient)
+    }
+
+    /// Removes a recipient from the set. Returns `false` if it was not present.
+    pub fn remove(&mut self, recipient: &Recipient<M>) -> bool {
+        self.recipients.remove(recipient)
+    }
+
+    /// Returns the number of recipients in the set, including disconnected ones.
+    pub fn len(&self) -> usize {
+        self.recipients.len()
+    }
+
+    /// Returns `true` if the set contains no recipients.
+    pub fn is_empty(&self) -> bool {
+        self.recipients.is_empty()
+    }
+
+    /// Returns an iterator over the recipients in the set.
+    pub fn iter(&self) -> impl Iterator<Item = &Recipient<M>> {
+        self.recipients.iter()
+    }
+
+    /// Removes recipients whose actor has stopped. Returns the number of recipients removed.
+    pub fn prune(&mut self) -> usize {
+        let len = self.recipients.len();
+        self.recipients.retain(Recipient::connected);
+        len - self.recipients.len()
+    }
+
+    /// Sends a clone of `msg` to every recipient, ignoring mailbox capacity.
+    ///
+    /// Returns the number of recipients removed when prune-on-send is enabled, `0` otherwise.
+    pub fn broadcast(&mut self, msg: M) -> usize
+    where
+        M: Clone,
+    {
+        if !self.prune_on_send {
+            for recipient in &self.recipients {
+                recipient.do_send(msg.clone());
+            }
+            return 0;
+        }
+
+        let len = self.recipients.len();
+        self.recipients.retain(|recipient| {
+            if recipient.connected() {
+                recipient.do_send(msg.clone());
+                true
+            } else {
+                false
+            }
+        });
+        len - self.recipients.len()
+    }
+}
+
+impl<M> Default for RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    fn default() -> Self {
+        RecipientSet::new()
+    }
+}
+
+impl<M> Clone for RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    fn clone(&self) -> Self {
+        RecipientSet {
+            recipients: self.recipients.clone(),
+            prune_on_send: self.prune_on_send,
+        }
+    }
+}
+
+impl<M> fmt::Debug for RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RecipientSet")
+            .field("len", &self.recipients.len())
+            .field("prune_on_send", &self.prune_on_send)
+            .finish()
+    }
+}
+
+impl<M> Extend<Recipient<M>> for RecipientSet<M>
+where
+    M: Message + Send,
+    M::Result: Send,
+{
+    fn extend<I: IntoIterator<Item = Recipient<M>>>(&mut self, iter: I) {
+        self.recipients.extend(iter)
+    }
+}
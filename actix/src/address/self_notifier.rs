@@ -0,0 +1,125 @@
+use std::fmt;
+
+use super::WeakRecipient;
+use crate::handler::Message;
+
+/// A handle for sending messages of type `M` back to the actor that created it.
+///
+/// Created by [`Context::self_notifier`](crate::Context::self_notifier). The handle only holds a
+/// weak reference, so it does not keep the actor alive, and sending through it after the actor
+/// has stopped does nothing. A message set with [`on_drop`](SelfNotifier::on_drop) is sent when
+/// the handle is dropped, which makes it usable as a guard reporting that a piece of spawned
+/// work has ended, also when the work is cancelled.
+///
+/// ```
+/// # use actix::prelude::*;
+/// # use std::time::Duration;
+/// #[derive(Message)]
+/// #[rtype(result = "()")]
+/// struct Finished(usize);
+///
+/// struct Worker;
+///
+/// impl Actor for Worker {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Self::Context) {
+///         let guard = ctx.self_notifier().on_drop(Finished(1));
+///
+///         actix_rt::spawn(async move {
+///             let _guard = guard;
+///             actix_rt::time::sleep(Duration::from_millis(10)).await;
+///         });
+///     }
+/// }
+///
+/// impl Handler<Finished> for Worker {
+///     type Result = ();
+///
+///     fn handle(&mut self, msg: Finished, _: &mut Self::Context) {
+///         println!("operation {} finished", msg.0);
+///     }
+/// }
+/// ```
+pub struct SelfNotifier<M>
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    recipient: WeakRecipient<M>,
+    on_drop: Option<M>,
+}
+
+impl<M> SelfNotifier<M>
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    pub(crate) fn new(recipient: WeakRecipient<M>) -> Self {
+        SelfNotifier {
+            recipient,
+            on_drop: None,
+        }
+    }
+
+    /// Sets the message sent to the actor when this handle is dropped.
+    pub fn on_drop(mut self, msg: M) -> Self {
+        self.on_drop = Some(msg);
+        self
+    }
+
+    /// Removes the message set with [`on_drop`](SelfNotifier::on_drop), returning it.
+    pub fn disarm(&mut self) -> Option<M> {
+        self.on_drop.take()
+    }
+
+    /// Sends a message to the actor, ignoring mailbox capacity.
+    ///
+    /// Does nothing if the actor has stopped.
+    pub fn notify(&self, msg: M) {
+        if let Some(recipient) = self.recipient.upgrade() {
+            if recipient.connected() {
+                recipient.do_send(msg);
+            }
+        }
+    }
+}
+
+/// Clones are not armed: the message set with [`on_drop`](SelfNotifier::on_drop) is only sent
+/// when the handle it was set on is dropped.
+impl<M> Clone for SelfNotifier<M>
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    fn clone(&self) -> Self {
+        SelfNotifier {
+            recipient: self.recipient.clone(),
+            on_drop: None,
+        }
+    }
+}
+
+impl<M> fmt::Debug for SelfNotifier<M>
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SelfNotifier")
+            .field("armed", &self.on_drop.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<M> Drop for SelfNotifier<M>
+where
+    M: Message + Send + 'static,
+    M::Result: Send,
+{
+    fn drop(&mut self) {
+        if let Some(msg) = self.on_drop.take() {
+            self.notify(msg);
+        }
+    }
+}
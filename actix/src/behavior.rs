@@ -0,0 +1,117 @@
+//! Swappable message handling, similar to Erlang's `become`.
+
+use std::fmt;
+
+use crate::{
+    actor::Actor,
+    handler::{Handler, Message},
+};
+
+/// Function handling messages of type `M` for actor `A`.
+pub type BehaviorFn<A, M> = fn(&mut A, M, &mut <A as Actor>::Context) -> <A as Handler<M>>::Result;
+
+/// The current way an actor handles messages of type `M`.
+///
+/// An actor keeps a `Behavior` for each message type whose handling changes at runtime and
+/// delegates its [`Handler`] implementation to it. Calling [`set`](Behavior::set), also from
+/// within a behavior, changes how the following messages are handled without restarting the
+/// actor. Messages the actor handles the same way in every state keep a plain `Handler`
+/// implementation.
+///
+/// ```
+/// use actix::{prelude::*, Behavior};
+///
+/// #[derive(Message)]
+/// #[rtype(result = "bool")]
+/// struct Press;
+///
+/// struct Light {
+///     on_press: Behavior<Light, Press>,
+/// }
+///
+/// impl Light {
+///     fn off(&mut self, _: Press, _: &mut Context<Self>) -> bool {
+///         self.on_press.set(Light::on);
+///         true
+///     }
+///
+///     fn on(&mut self, _: Press, _: &mut Context<Self>) -> bool {
+///         self.on_press.set(Light::off);
+///         false
+///     }
+/// }
+///
+/// impl Actor for Light {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<Press> for Light {
+///     type Result = bool;
+///
+///     fn handle(&mut self, msg: Press, ctx: &mut Context<Self>) -> Self::Result {
+///         (self.on_press.handler())(self, msg, ctx)
+///     }
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let light = Light { on_press: Behavior::new(Light::off) }.start();
+/// assert!(light.send(Press).await.unwrap());
+/// assert!(!light.send(Press).await.unwrap());
+/// # }
+/// ```
+pub struct Behavior<A, M>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+    handler: BehaviorFn<A, M>,
+}
+
+impl<A, M> Behavior<A, M>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+    /// Creates a behavior handling messages with `handler`.
+    pub fn new(handler: BehaviorFn<A, M>) -> Self {
+        Behavior { handler }
+    }
+
+    /// Replaces the handler used for subsequent messages.
+    pub fn set(&mut self, handler: BehaviorFn<A, M>) {
+        self.handler = handler;
+    }
+
+    /// Returns the current handler.
+    pub fn handler(&self) -> BehaviorFn<A, M> {
+        self.handler
+    }
+}
+
+impl<A, M> Clone for Behavior<A, M>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A, M> Copy for Behavior<A, M>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+}
+
+impl<A, M> fmt::Debug for Behavior<A, M>
+where
+    A: Actor + Handler<M>,
+    M: Message,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Behavior").finish_non_exhaustive()
+    }
+}
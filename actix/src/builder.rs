@@ -0,0 +1,142 @@
+use std::{borrow::Cow, fmt, marker::PhantomData, time::Duration};
+
+use crate::{
+    actor::Actor,
+    address::Addr,
+    context::{Context, StartReady},
+};
+
+/// Starts an actor with its context configured up front.
+///
+/// Options that would otherwise be set one by one on the [`Context`] in [`Actor::started`] are
+/// collected by the builder and applied before the actor is created, so they are in effect from
+/// the first message on.
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use actix::{prelude::*, ActorBuilder};
+///
+/// struct Indexer;
+///
+/// impl Actor for Indexer {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Self::Context) {
+///         assert_eq!(ctx.name(), "indexer");
+///     }
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let addr = ActorBuilder::new(|_| Indexer)
+///     .name("indexer")
+///     .mailbox(64)
+///     .slow_handler_threshold(Duration::from_millis(100))
+///     .start();
+/// # }
+/// ```
+pub struct ActorBuilder<A, F>
+where
+    A: Actor<Context = Context<A>>,
+    F: FnOnce(&mut Context<A>) -> A,
+{
+    factory: F,
+    name: Option<Cow<'static, str>>,
+    mailbox: Option<usize>,
+    slow_handler: Option<Duration>,
+    log_dropped_on_stop: bool,
+    _actor: PhantomData<fn() -> A>,
+}
+
+impl<A, F> ActorBuilder<A, F>
+where
+    A: Actor<Context = Context<A>>,
+    F: FnOnce(&mut Context<A>) -> A,
+{
+    /// Creates a builder for the actor returned by `factory`.
+    ///
+    /// The factory receives the configured context, like the closure passed to
+    /// [`Actor::create`].
+    pub fn new(factory: F) -> Self {
+        ActorBuilder {
+            factory,
+            name: None,
+            mailbox: None,
+            slow_handler: None,
+            log_dropped_on_stop: false,
+            _actor: PhantomData,
+        }
+    }
+
+    /// Sets the name of the actor, see [`Context::set_name`].
+    pub fn name<N: Into<Cow<'static, str>>>(mut self, name: N) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the mailbox capacity, see [`Context::set_mailbox_capacity`].
+    pub fn mailbox(mut self, cap: usize) -> Self {
+        self.mailbox = Some(cap);
+        self
+    }
+
+    /// Sets the threshold for slow handler warnings, see
+    /// [`Context::set_slow_handler_threshold`].
+    pub fn slow_handler_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_handler = Some(threshold);
+        self
+    }
+
+    /// Enables logging of the messages dropped when the actor stops, see
+    /// [`Context::log_dropped_on_stop`].
+    pub fn log_dropped_on_stop(mut self, enabled: bool) -> Self {
+        self.log_dropped_on_stop = enabled;
+        self
+    }
+
+    /// Starts the actor, returning its address.
+    pub fn start(self) -> Addr<A> {
+        let (ctx, act) = self.build();
+        ctx.run(act)
+    }
+
+    /// Starts the actor, returning a future that resolves to its address once the actor is
+    /// ready, see [`Actor::start_ready`].
+    pub fn start_ready(self) -> StartReady<A> {
+        let (ctx, act) = self.build();
+        ctx.run_ready(act)
+    }
+
+    fn build(self) -> (Context<A>, A) {
+        let mut ctx = Context::new();
+        if let Some(name) = self.name {
+            ctx.set_name(name);
+        }
+        if let Some(cap) = self.mailbox {
+            ctx.set_mailbox_capacity(cap);
+        }
+        if let Some(threshold) = self.slow_handler {
+            ctx.set_slow_handler_threshold(threshold);
+        }
+        ctx.log_dropped_on_stop(self.log_dropped_on_stop);
+
+        let act = (self.factory)(&mut ctx);
+        (ctx, act)
+    }
+}
+
+impl<A, F> fmt::Debug for ActorBuilder<A, F>
+where
+    A: Actor<Context = Context<A>>,
+    F: FnOnce(&mut Context<A>) -> A,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ActorBuilder")
+            .field("name", &self.name)
+            .field("mailbox", &self.mailbox)
+            .field("slow_handler", &self.slow_handler)
+            .field("log_dropped_on_stop", &self.log_dropped_on_stop)
+            .finish()
+    }
+}
@@ -7,3 +7,4 @@
 //! [`tokio::time` module]: https://docs.rs/tokio/1.0.1/tokio/time/index.html
 
 pub use actix_rt::time::*;
+pub use tokio::time::MissedTickBehavior;
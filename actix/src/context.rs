@@ -1,11 +1,33 @@
-use std::fmt;
+use std::{
+    any::Any,
+    borrow::Cow,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    task::{self, Poll, Waker},
+    time::Duration,
+};
+
+use actix_rt::System;
+use futures_core::stream::Stream;
+use log::error;
+use tokio::sync::{mpsc, oneshot};
 
 use crate::{
     actor::{Actor, ActorContext, ActorState, AsyncContext, SpawnHandle},
-    address::{Addr, AddressReceiver},
+    address::{Addr, AddressReceiver, CancelToken, SelfNotifier},
+    clock::Instant,
     context_impl::{AsyncContextParts, ContextFut, ContextParts},
-    fut::ActorFuture,
+    context_items::ActorDelayedMessageItem,
+    extensions::Extensions,
+    fut::{ActorFuture, ActorFutureExt, Throttle},
+    handler::{Handler, Message},
     mailbox::Mailbox,
+    stream::{BackpressureStream, ItemTimeoutStream, ReceiverStream, StreamHandler},
 };
 
 /// An actor execution context.
@@ -15,6 +37,7 @@ where
 {
     parts: ContextParts<A>,
     mb: Option<Mailbox<A>>,
+    extensions: Extensions,
 }
 
 impl<A: Actor<Context = Context<A>>> fmt::Debug for Context<A> {
@@ -22,6 +45,7 @@ impl<A: Actor<Context = Context<A>>> fmt::Debug for Context<A> {
         fmt.debug_struct("Context")
             .field("parts", &self.parts)
             .field("mb", &self.mb)
+            .field("extensions", &self.extensions)
             .finish()
     }
 }
@@ -129,6 +153,7 @@ where
         Self {
             parts: ContextParts::new(mb.sender_producer()),
             mb: Some(mb),
+            extensions: Extensions::new(),
         }
     }
 
@@ -138,6 +163,7 @@ where
         Self {
             parts: ContextParts::new(mb.sender_producer()),
             mb: Some(mb),
+            extensions: Extensions::new(),
         }
     }
 
@@ -149,6 +175,19 @@ where
         addr
     }
 
+    /// Spawns the context like [`run`](Context::run), returning a future that resolves to the
+    /// actor's address once the actor is ready to handle messages.
+    ///
+    /// See [`Actor::start_ready`].
+    pub fn run_ready(mut self, act: A) -> StartReady<A> {
+        let (tx, rx) = oneshot::channel();
+        self.parts.set_ready(tx);
+        StartReady {
+            addr: Some(self.run(act)),
+            rx,
+        }
+    }
+
     pub fn into_future(mut self, act: A) -> ContextFut<A, Self> {
         let mb = self.mb.take().unwrap();
         ContextFut::new(self, act, mb)
@@ -162,6 +201,245 @@ where
         self.parts.curr_handle()
     }
 
+    /// Cancels all futures spawned into this context so far.
+    ///
+    /// Returns the number of futures that got cancelled, not counting futures already
+    /// cancelled with [`cancel_future`](AsyncContext::cancel_future) or an earlier call. Futures
+    /// spawned after this call are not affected.
+    ///
+    /// This includes the timers the context spawns itself: the ones of
+    /// [`set_lifetime`](Self::set_lifetime), [`set_idle_lifetime`](Self::set_idle_lifetime),
+    /// [`notify_later_debounced`](Self::notify_later_debounced) and
+    /// [`set_slow_consumer_threshold`](Self::set_slow_consumer_threshold), which have to be set
+    /// up again afterwards if still needed.
+    pub fn cancel_all_futures(&mut self) -> usize {
+        self.parts.cancel_all_futures()
+    }
+
+    /// Removes messages of type `M` still queued in the mailbox and returns them.
+    ///
+    /// This is intended for use in [`Actor::stopping`] and [`Actor::stopped`], where it allows
+    /// an actor to hand its backlog over to a replacement instead of dropping it. Other messages
+    /// are left in the mailbox in their original order.
+    ///
+    /// Only messages sent through an [`Addr`] or a [`Recipient`](crate::Recipient) are
+    /// recoverable. Messages scheduled with [`notify`](AsyncContext::notify) or
+    /// [`notify_later`](AsyncContext::notify_later), or coming from message streams, are not in
+    /// the mailbox and are not returned. Senders waiting on a response for a drained message
+    /// receive [`MailboxError::Closed`](crate::MailboxError::Closed).
+    pub fn drain_pending<M>(&mut self) -> Vec<M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        self.parts.drain_pending()
+    }
+
+    /// Returns the [`CancelToken`] of the message currently being handled.
+    ///
+    /// Returns `None` unless the message was sent with
+    /// [`Addr::send_cancellable`](crate::Addr::send_cancellable). Handlers returning a future can
+    /// keep the token and check it with [`is_cancelled`](Context::is_cancelled) to abort early.
+    pub fn cancel_token(&self) -> Option<CancelToken> {
+        self.parts.cancel_token()
+    }
+
+    /// Returns `true` if `token` has been cancelled or the actor is stopping.
+    pub fn is_cancelled(&self, token: &CancelToken) -> bool {
+        token.is_cancelled() || self.state().stopping()
+    }
+
+    /// Returns the name of the actor.
+    ///
+    /// Defaults to the actor's type name, see [`Actor::start_named`] and
+    /// [`set_name`](Context::set_name).
+    pub fn name(&self) -> &str {
+        self.parts.name()
+    }
+
+    /// Sets the name of the actor.
+    pub fn set_name<N: Into<Cow<'static, str>>>(&mut self, name: N) {
+        self.parts.set_name(name.into());
+    }
+
+    /// Returns the time elapsed since the actor was started.
+    ///
+    /// Measured with a monotonic clock from just before [`Actor::started`] ran, so it is not
+    /// affected by changes to the system time. A restarted [supervised](crate::Supervised) actor
+    /// counts from its latest start. Returns zero before the actor has started.
+    pub fn uptime(&self) -> Duration {
+        self.parts.uptime()
+    }
+
+    /// Returns the [`System`] the actor belongs to.
+    ///
+    /// This is the system that was current on the thread the context was created on, e.g. the
+    /// arbiter thread for actors started with [`Actor::start_in_arbiter`]. Unlike
+    /// [`System::current`], it does not depend on thread-local state at the time of the call.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the context was created outside of a running system and no system is running
+    /// on the current thread either.
+    pub fn system(&self) -> System {
+        match self.parts.system() {
+            Some(system) => system.clone(),
+            None => System::current(),
+        }
+    }
+
+    /// Returns the values stored on this context, keyed by their type.
+    ///
+    /// See [`extensions_mut`](Context::extensions_mut).
+    pub fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    /// Returns the values stored on this context for modification.
+    ///
+    /// Values stored here are available to all handlers and spawned futures of the actor, which
+    /// lets helpers shared between actor types keep their own per-actor state. They are kept
+    /// when a [supervised](crate::Supervised) actor restarts.
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    /// Returns the correlation ID of this actor or of the message being handled.
+    ///
+    /// See [`set_correlation_id`](Context::set_correlation_id).
+    pub fn correlation_id(&self) -> Option<u64> {
+        self.parts.correlation_id()
+    }
+
+    /// Sets the correlation ID attached to messages this actor sends to other actors.
+    ///
+    /// While a message carrying a correlation ID is handled, the ID is available through
+    /// [`correlation_id`](Context::correlation_id) and is attached to messages the handler sends
+    /// in turn, so the ID follows a request across a chain of actors. Afterwards the actor's own
+    /// ID is restored. Futures returned by a handler run later and see the actor's own ID.
+    ///
+    /// Only messages sent through an [`Addr`] or a [`Recipient`](crate::Recipient) to actors
+    /// running in a [`Context`] carry the ID.
+    pub fn set_correlation_id(&mut self, id: Option<u64>) {
+        self.parts.set_correlation_id(id);
+    }
+
+    /// Adds an interceptor that runs before each mailbox message is handled.
+    ///
+    /// The interceptor gets the actor, the message and the context. It can inspect the message
+    /// by downcasting it to a concrete type, and returns `false` to drop the message instead of
+    /// handling it, in which case the sender of a request gets [`MailboxError::Closed`].
+    /// Interceptors run in the order they were added, up to the first one dropping the message.
+    ///
+    /// Messages sent with [`notify`](AsyncContext::notify) and stream items do not go through the
+    /// mailbox and are not intercepted.
+    ///
+    /// ```
+    /// # use actix::prelude::*;
+    /// #[derive(Message)]
+    /// #[rtype(result = "()")]
+    /// struct Ping;
+    ///
+    /// struct MyActor {
+    ///     handled: usize,
+    /// }
+    ///
+    /// impl Actor for MyActor {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn started(&mut self, ctx: &mut Self::Context) {
+    ///         ctx.add_interceptor(|act, msg, _| {
+    ///             if msg.is::<Ping>() {
+    ///                 act.handled += 1;
+    ///             }
+    ///             true
+    ///         });
+    ///     }
+    /// }
+    /// # impl Handler<Ping> for MyActor {
+    /// #     type Result = ();
+    /// #     fn handle(&mut self, _: Ping, _: &mut Self::Context) {}
+    /// # }
+    /// ```
+    ///
+    /// [`MailboxError::Closed`]: crate::MailboxError::Closed
+    pub fn add_interceptor<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A, &dyn Any, &mut Self) -> bool + 'static,
+    {
+        self.parts.add_interceptor(f)
+    }
+
+    /// Enables logging of the messages left in the mailbox when the actor stops.
+    ///
+    /// Messages still queued once the actor has stopped are dropped, and senders waiting for a
+    /// response get [`MailboxError::Closed`](crate::MailboxError::Closed). With this enabled, a
+    /// warning lists how many messages of each type were dropped, which helps tracking down lost
+    /// messages without setting up [dead-letter handlers](crate::dead_letter::DeadLetters).
+    /// Disabled by default.
+    pub fn log_dropped_on_stop(&mut self, enabled: bool) {
+        self.parts.set_log_dropped_on_stop(enabled)
+    }
+
+    /// Waits for a future like [`wait`](AsyncContext::wait), running `on_cancel` if the actor
+    /// stops before the future completes.
+    ///
+    /// A stopping actor drops the futures it waits on wherever they are suspended. When such a
+    /// future leaves the actor's state half updated, e.g. after writing part of a frame,
+    /// `on_cancel` gets the chance to restore it before [`Actor::stopped`] runs. It is not called
+    /// if the future completes, nor if [`Actor::stopping`] keeps the actor running, in which case
+    /// the future is resumed.
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use actix::prelude::*;
+    /// struct Uploader {
+    ///     in_flight: bool,
+    /// }
+    ///
+    /// impl Actor for Uploader {
+    ///     type Context = Context<Self>;
+    ///
+    ///     fn started(&mut self, ctx: &mut Self::Context) {
+    ///         self.in_flight = true;
+    ///         ctx.wait_with_cleanup(
+    ///             actix_rt::time::sleep(Duration::from_secs(10))
+    ///                 .into_actor(self)
+    ///                 .map(|_, act, _| act.in_flight = false),
+    ///             |act, _| act.in_flight = false,
+    ///         );
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_with_cleanup<F, C>(&mut self, fut: F, on_cancel: C)
+    where
+        F: ActorFuture<A, Output = ()> + 'static,
+        C: FnOnce(&mut A, &mut Self) + 'static,
+    {
+        self.parts.wait_with_cleanup(fut, on_cancel)
+    }
+
+    /// Pauses processing of mailbox messages.
+    ///
+    /// The actor stays alive and its address keeps accepting messages, which are queued until
+    /// [`resume`](Context::resume) is called and then handled in order. Spawned futures,
+    /// [`notify`](AsyncContext::notify) messages and streams keep being processed, so `resume`
+    /// has to be called from one of those. Bounded sends still apply back-pressure once the
+    /// mailbox capacity is reached.
+    pub fn pause(&mut self) {
+        self.parts.pause()
+    }
+
+    /// Resumes processing of mailbox messages paused with [`pause`](Context::pause).
+    pub fn resume(&mut self) {
+        self.parts.resume()
+    }
+
+    /// Returns whether processing of mailbox messages is paused.
+    pub fn paused(&self) -> bool {
+        self.parts.paused()
+    }
+
     /// Sets the mailbox capacity.
     ///
     /// The default mailbox capacity is 16 messages.
@@ -188,6 +466,266 @@ where
         self.parts.set_mailbox_capacity(cap)
     }
 
+    /// Sets how many mailbox messages are handled in a row before spawned futures get polled.
+    ///
+    /// This keeps an actor with a constantly busy mailbox from starving its own futures, such as
+    /// [`run_later`](AsyncContext::run_later) timers. The default budget is 128 messages; a
+    /// budget of 0 is treated as 1.
+    pub fn set_mailbox_budget(&mut self, budget: usize) {
+        self.parts.set_mailbox_budget(budget)
+    }
+
+    /// Returns a handle for sending messages of type `M` to this actor from spawned work.
+    ///
+    /// Unlike an [`Addr`], the handle does not keep the actor alive. See [`SelfNotifier`].
+    pub fn self_notifier<M>(&self) -> SelfNotifier<M>
+    where
+        A: Handler<M>,
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        SelfNotifier::new(self.address().downgrade().recipient())
+    }
+
+    /// Creates a [`Throttle`] limiting how many futures of this actor run at the same time.
+    ///
+    /// Futures are subject to the limit once wrapped with
+    /// [`ActorFutureExt::throttle`](crate::fut::ActorFutureExt::throttle); the other futures of
+    /// the actor are not affected. Use one throttle per kind of work to limit, e.g. outgoing
+    /// connections, and keep it in the actor to reuse it.
+    pub fn throttle(&self, max_concurrent: usize) -> Throttle {
+        Throttle::new(max_concurrent)
+    }
+
+    /// Logs a warning when handling a single mailbox message takes longer than `threshold`.
+    ///
+    /// A handler that runs too long blocks the whole arbiter, not only its own actor. The
+    /// warning names the actor and message types along with the time taken. Only the
+    /// synchronous part of the handler is timed: futures returned by the handler or spawned
+    /// from it are not included. The check is disabled by default.
+    pub fn set_slow_handler_threshold(&mut self, threshold: Duration) {
+        self.parts.set_slow_handler_threshold(Some(threshold))
+    }
+
+    /// Registers a stream like [`add_stream`](AsyncContext::add_stream), but only takes items
+    /// from it while fewer than `watermark` messages wait in the mailbox.
+    ///
+    /// Stream items are handled as soon as they are taken, so an actor never holds more than
+    /// one of them. What can pile up is the work handlers queue in the actor's own mailbox, e.g.
+    /// by sending messages to themselves. In this mode the mailbox is worked down first, and a
+    /// stream that is not read, such as a `FramedRead` over TCP, slows its producer down. A
+    /// watermark of 0 is treated as 1.
+    ///
+    /// While the mailbox is [paused](Context::pause) and at or above the watermark, the stream
+    /// is not read at all.
+    pub fn add_stream_with_backpressure<S>(&mut self, stream: S, watermark: usize) -> SpawnHandle
+    where
+        S: Stream + 'static,
+        A: StreamHandler<S::Item>,
+    {
+        if self.state() == ActorState::Stopped {
+            error!("Context::add_stream_with_backpressure called for stopped actor.");
+            SpawnHandle::default()
+        } else {
+            self.spawn(BackpressureStream::new(stream, watermark))
+        }
+    }
+
+    /// Registers a stream like [`add_stream`](AsyncContext::add_stream), but bounds how long
+    /// handling one of its items can keep the actor waiting.
+    ///
+    /// A handler that calls [`wait`](AsyncContext::wait) blocks the actor, and with it the
+    /// stream, until the future completes. For items of this stream, the actor stops waiting
+    /// once `timeout` has passed: a warning is logged, the future keeps running as if it had been
+    /// [spawned](AsyncContext::spawn), and the following items are handled in the meantime.
+    pub fn add_stream_with_item_timeout<S>(&mut self, stream: S, timeout: Duration) -> SpawnHandle
+    where
+        S: Stream + 'static,
+        A: StreamHandler<S::Item>,
+    {
+        if self.state() == ActorState::Stopped {
+            error!("Context::add_stream_with_item_timeout called for stopped actor.");
+            SpawnHandle::default()
+        } else {
+            self.spawn(ItemTimeoutStream::new(stream, timeout))
+        }
+    }
+
+    /// Registers the messages received by a tokio [`mpsc::Receiver`] as a stream handled by
+    /// [`StreamHandler`].
+    ///
+    /// Once all senders are dropped and the buffered messages are handled,
+    /// [`StreamHandler::finished`] is called, as for any other stream. Returns the handle of the
+    /// stream.
+    pub fn add_tokio_receiver<M>(&mut self, rx: mpsc::Receiver<M>) -> SpawnHandle
+    where
+        M: 'static,
+        A: StreamHandler<M>,
+    {
+        self.add_stream(ReceiverStream::new(rx))
+    }
+
+    /// Cancels the stream registered under `old` and registers `stream` in its place.
+    ///
+    /// Items of the new stream go to the same [`StreamHandler`], so the actor keeps its state,
+    /// e.g. when a dropped connection is re-established. Cancelling the old stream does not
+    /// call [`StreamHandler::finished`], but [`StreamHandler::started`] is called again for the
+    /// new one. If the old stream has already finished, `finished` was called for it as usual
+    /// and its default implementation has stopped the actor; override it to keep the actor
+    /// running until the replacement arrives.
+    ///
+    /// Returns the handle of the new stream.
+    pub fn replace_stream<S>(&mut self, old: SpawnHandle, stream: S) -> SpawnHandle
+    where
+        S: Stream + 'static,
+        A: StreamHandler<S::Item>,
+    {
+        self.cancel_future(old);
+        self.add_stream(stream)
+    }
+
+    /// Sends the message `msg` to self after `dur`, unless this is called again with the same
+    /// `key` in the meantime.
+    ///
+    /// A message still pending under `key` is dropped and the timer starts over with `msg`, so
+    /// a burst of calls delivers only the last message, once `dur` has passed without another
+    /// call. This suits work like saving state after a series of changes has settled. Keys are
+    /// shared between message types.
+    ///
+    /// Returns the handle of the pending message, which can be passed to
+    /// [`cancel_future`](AsyncContext::cancel_future) to drop it.
+    pub fn notify_later_debounced<M, K>(&mut self, msg: M, dur: Duration, key: K) -> SpawnHandle
+    where
+        A: Handler<M>,
+        M: Message + 'static,
+        K: Into<Cow<'static, str>>,
+    {
+        if self.state() == ActorState::Stopped {
+            error!("Context::notify_later_debounced called for stopped actor.");
+            return SpawnHandle::default();
+        }
+
+        let key = key.into();
+        let fired = key.clone();
+        let item = ActorDelayedMessageItem::new(msg, dur).map(move |(), _, ctx: &mut Self| {
+            // forget the key once its message went out, unless it was scheduled again
+            ctx.parts.remove_debounced(&fired, ctx.handle());
+        });
+        let handle = self.spawn(item);
+        if let Some(pending) = self.parts.replace_debounced(key, handle) {
+            self.cancel_future(pending);
+        }
+        handle
+    }
+
+    /// Stops the actor once `dur` has passed since it started, whether or not it is busy.
+    ///
+    /// The actor is stopped as with [`stop`](ActorContext::stop), so [`Actor::stopping`] can
+    /// still keep it running. Calling this again replaces the previous lifetime. A restarted
+    /// [supervised](crate::Supervised) actor has no lifetime until it sets one again.
+    ///
+    /// Returns the handle of the timer, which can be passed to
+    /// [`cancel_future`](AsyncContext::cancel_future) to lift the limit.
+    pub fn set_lifetime(&mut self, dur: Duration) -> SpawnHandle {
+        if self.state() == ActorState::Stopped {
+            error!("Context::set_lifetime called for stopped actor.");
+            return SpawnHandle::default();
+        }
+
+        let remaining = dur.saturating_sub(self.uptime());
+        let handle = self.run_later(remaining, |_, ctx| ctx.stop());
+        if let Some(previous) = self.parts.replace_lifetime(handle) {
+            self.cancel_future(previous);
+        }
+        handle
+    }
+
+    /// Stops the actor once its mailbox has been idle for `dur`.
+    ///
+    /// Every message taken from the mailbox restarts the countdown, so only an actor that
+    /// received nothing for `dur` is stopped, as with [`stop`](ActorContext::stop). Messages
+    /// do not spawn or cancel timers: a single timer checks the time of the last message when
+    /// it fires and is rescheduled for the remainder. Calling this again replaces the previous
+    /// idle lifetime. A restarted [supervised](crate::Supervised) actor has no idle lifetime
+    /// until it sets one again.
+    pub fn set_idle_lifetime(&mut self, dur: Duration) {
+        if self.state() == ActorState::Stopped {
+            error!("Context::set_idle_lifetime called for stopped actor.");
+            return;
+        }
+
+        let timer = self.run_later(dur, Self::check_idle);
+        if let Some(previous) = self.parts.set_idle_lifetime(dur, timer) {
+            self.cancel_future(previous);
+        }
+    }
+
+    fn check_idle(_: &mut A, ctx: &mut Self) {
+        match ctx.parts.idle_time() {
+            Some((dur, idle)) if idle >= dur => ctx.stop(),
+            Some((dur, idle)) => {
+                let timer = ctx.run_later(dur - idle, Self::check_idle);
+                ctx.parts.set_idle_timer(timer);
+            }
+            None => {}
+        }
+    }
+
+    /// Returns a token that fires when the actor starts stopping.
+    ///
+    /// Plain futures spawned with [`actix_rt::spawn`] outlive the actor; they can hold a clone of
+    /// the token and finish once it fires, by awaiting it or by checking
+    /// [`StopToken::is_stopped`]. A token that has fired stays fired, even if
+    /// [`Actor::stopping`] keeps the actor running. A restarted [supervised](crate::Supervised)
+    /// actor hands out a new token.
+    pub fn stop_token(&mut self) -> StopToken {
+        self.parts.stop_token()
+    }
+
+    /// Returns the number of messages waiting in the mailbox.
+    pub fn mailbox_len(&self) -> usize {
+        self.parts.mailbox_len()
+    }
+
+    /// Calls `callback` when the mailbox holds more than `len` messages for at least `dur`.
+    ///
+    /// This flags slow consumers, i.e. actors that receive messages faster than they handle
+    /// them. The mailbox length is sampled four times per `dur`, so short dips below the
+    /// threshold between samples go unnoticed. The callback receives the current mailbox length.
+    /// It fires once while the mailbox stays above the threshold, and again only after the
+    /// mailbox has dropped to `len` or below in between.
+    ///
+    /// Returns the handle of the background check, which can be passed to
+    /// [`cancel_future`](AsyncContext::cancel_future) to remove it.
+    pub fn set_slow_consumer_threshold<F>(
+        &mut self,
+        len: usize,
+        dur: Duration,
+        mut callback: F,
+    ) -> SpawnHandle
+    where
+        F: FnMut(&mut A, &mut Self, usize) + 'static,
+    {
+        let period = (dur / 4).max(Duration::from_millis(1));
+        let mut above_since = None;
+        let mut reported = false;
+
+        self.run_interval(period, move |act, ctx| {
+            let queued = ctx.mailbox_len();
+            if queued <= len {
+                above_since = None;
+                reported = false;
+                return;
+            }
+
+            let since = *above_since.get_or_insert_with(Instant::now);
+            if !reported && since.elapsed() >= dur {
+                reported = true;
+                callback(act, ctx, queued);
+            }
+        })
+    }
+
     /// Returns whether any addresses are still connected.
     pub fn connected(&self) -> bool {
         self.parts.connected()
@@ -213,6 +751,92 @@ where
     }
 }
 
+/// Future returned by [`Actor::start_ready`], resolving to the address of the actor once it is
+/// ready to handle messages.
+#[must_use = "futures do nothing unless polled"]
+pub struct StartReady<A: Actor> {
+    addr: Option<Addr<A>>,
+    rx: oneshot::Receiver<()>,
+}
+
+impl<A: Actor> fmt::Debug for StartReady<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("StartReady").finish_non_exhaustive()
+    }
+}
+
+impl<A: Actor> Future for StartReady<A> {
+    type Output = Addr<A>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // the sender is dropped without notifying if the actor stops before getting ready
+        let _ = futures_core::ready!(Pin::new(&mut self.rx).poll(cx));
+        Poll::Ready(
+            self.addr
+                .take()
+                .expect("StartReady polled after completion"),
+        )
+    }
+}
+
+/// Signal that an actor has started stopping, returned by [`Context::stop_token`].
+///
+/// Clones share the same signal and can be awaited from any task; the future resolves once the
+/// actor enters its stopping state.
+#[derive(Clone, Default)]
+pub struct StopToken {
+    inner: Arc<StopInner>,
+}
+
+#[derive(Default)]
+struct StopInner {
+    stopped: AtomicBool,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl StopToken {
+    /// Returns `true` once the actor has started stopping.
+    pub fn is_stopped(&self) -> bool {
+        self.inner.stopped.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn fire(&self) {
+        self.inner.stopped.store(true, Ordering::Release);
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock().unwrap());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+impl Future for StopToken {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<()> {
+        if self.is_stopped() {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = self.inner.wakers.lock().unwrap();
+        // checked again under the lock, `fire` may have drained the wakers in between
+        if self.is_stopped() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+impl fmt::Debug for StopToken {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("StopToken")
+            .field("stopped", &self.is_stopped())
+            .finish()
+    }
+}
+
 /// Helper trait which can spawn a future into the actor's context.
 pub trait ContextFutureSpawner<A>
 where
@@ -246,3 +870,60 @@ where
         ctx.wait(self);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::prelude::*;
+
+    struct Save;
+
+    impl Message for Save {
+        type Result = ();
+    }
+
+    struct Pending;
+
+    impl Message for Pending {
+        type Result = usize;
+    }
+
+    struct Users;
+
+    impl Actor for Users {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            for user in 0..100 {
+                let key = format!("user-{}", user);
+                ctx.notify_later_debounced(Save, Duration::from_millis(10), key);
+            }
+        }
+    }
+
+    impl Handler<Save> for Users {
+        type Result = ();
+
+        fn handle(&mut self, _: Save, _: &mut Self::Context) {}
+    }
+
+    impl Handler<Pending> for Users {
+        type Result = usize;
+
+        fn handle(&mut self, _: Pending, ctx: &mut Self::Context) -> usize {
+            ctx.parts.debounced_len()
+        }
+    }
+
+    #[test]
+    fn fired_debounce_keys_are_forgotten() {
+        System::new().block_on(async {
+            let addr = Users.start();
+            assert_eq!(addr.send(Pending).await.unwrap(), 100);
+
+            actix_rt::time::sleep(Duration::from_millis(50)).await;
+            assert_eq!(addr.send(Pending).await.unwrap(), 0);
+        });
+    }
+}
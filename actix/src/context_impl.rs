@@ -1,20 +1,32 @@
 use std::{
+    any::{type_name, Any},
+    borrow::Cow,
+    cell::Cell,
+    collections::HashMap,
     fmt,
     future::Future,
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
+use actix_rt::System;
 use bitflags::bitflags;
 use futures_core::ready;
+use log::warn;
 use smallvec::SmallVec;
+use tokio::sync::oneshot;
 
 use crate::{
     actor::{Actor, ActorContext, ActorState, AsyncContext, Running, SpawnHandle, Supervised},
-    address::{Addr, AddressSenderProducer},
+    address::{Addr, AddressSenderProducer, CancelToken, EnvelopeProxy},
+    clock::Instant,
+    context::StopToken,
     context_items::ActorWaitItem,
     fut::ActorFuture,
-    mailbox::Mailbox,
+    handler::{Message, ResponseActFuture},
+    lifecycle::{self, LifecycleStage},
+    mailbox::{Mailbox, DEFAULT_BUDGET},
 };
 
 bitflags! {
@@ -24,13 +36,48 @@ bitflags! {
         const STARTED =  0b0000_0001;
         const RUNNING =  0b0000_0010;
         const STOPPING = 0b0000_0100;
+        const CANCEL_ALL = 0b0000_1000;
         const STOPPED =  0b0001_0000;
         const MB_CAP_CHANGED = 0b0010_0000;
+        const PAUSED = 0b0100_0000;
+        const RESUMED = 0b1000_0000;
     }
 }
 
 type Item<A> = (SpawnHandle, Pin<Box<dyn ActorFuture<A, Output = ()>>>);
 
+type Interceptor<A> = Box<dyn FnMut(&mut A, &dyn Any, &mut <A as Actor>::Context) -> bool>;
+
+thread_local! {
+    // correlation ID of the actor, or message, currently being processed on this thread
+    static CORRELATION_ID: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// Returns the correlation ID of the actor or message currently being processed.
+pub(crate) fn current_correlation_id() -> Option<u64> {
+    CORRELATION_ID.with(Cell::get)
+}
+
+/// Sets the current correlation ID, returning the previous one.
+pub(crate) fn replace_correlation_id(id: Option<u64>) -> Option<u64> {
+    CORRELATION_ID.with(|cell| cell.replace(id))
+}
+
+/// Restores the previous correlation ID when dropped.
+struct CorrelationScope(Option<u64>);
+
+impl CorrelationScope {
+    fn enter(id: Option<u64>) -> Self {
+        CorrelationScope(replace_correlation_id(id))
+    }
+}
+
+impl Drop for CorrelationScope {
+    fn drop(&mut self) {
+        replace_correlation_id(self.0);
+    }
+}
+
 pub trait AsyncContextParts<A>: ActorContext + AsyncContext<A>
 where
     A: Actor<Context = Self>,
@@ -48,6 +95,33 @@ where
     wait: SmallVec<[ActorWaitItem<A>; 2]>,
     items: SmallVec<[Item<A>; 3]>,
     handles: SmallVec<[SpawnHandle; 2]>,
+    // handles of the futures already merged into `ContextFut`
+    merged: SmallVec<[SpawnHandle; 3]>,
+    // futures with handles up to this one are dropped by `CANCEL_ALL`
+    cancel_upto: SpawnHandle,
+    mb_budget: usize,
+    slow_handler: Option<Duration>,
+    // token of the cancellable message currently being handled
+    cancel_token: Option<CancelToken>,
+    correlation_id: Option<u64>,
+    name: Option<Cow<'static, str>>,
+    // pending debounced messages by key
+    debounced: HashMap<Cow<'static, str>, SpawnHandle>,
+    // timer stopping the actor at the end of its lifetime
+    lifetime: Option<SpawnHandle>,
+    // set by `Context::set_idle_lifetime`
+    idle: Option<IdleLifetime>,
+    // handed out by `Context::stop_token`, fired when the actor starts stopping
+    stop_token: Option<StopToken>,
+    // when `Actor::started` last ran
+    started_at: Option<Instant>,
+    // notified once the actor is ready to handle mailbox messages
+    ready: Option<oneshot::Sender<()>>,
+    // run in order before each mailbox message is handled
+    interceptors: Vec<Interceptor<A>>,
+    log_dropped_on_stop: bool,
+    // system of the thread the context was created on
+    system: Option<System>,
 }
 
 impl<A> fmt::Debug for ContextParts<A>
@@ -57,6 +131,7 @@ where
 {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("ContextParts")
+            .field("name", &self.name())
             .field("flags", &self.flags)
             .finish()
     }
@@ -76,6 +151,22 @@ where
             wait: SmallVec::new(),
             items: SmallVec::new(),
             handles: SmallVec::from_slice(&[SpawnHandle::default(), SpawnHandle::default()]),
+            merged: SmallVec::new(),
+            cancel_upto: SpawnHandle::default(),
+            mb_budget: DEFAULT_BUDGET,
+            slow_handler: None,
+            cancel_token: None,
+            correlation_id: None,
+            name: None,
+            debounced: HashMap::new(),
+            lifetime: None,
+            idle: None,
+            stop_token: None,
+            started_at: None,
+            ready: None,
+            interceptors: Vec::new(),
+            log_dropped_on_stop: false,
+            system: System::try_current(),
         }
     }
 
@@ -150,6 +241,26 @@ where
         self.wait.push(ActorWaitItem::new(f));
     }
 
+    /// Like [`ContextParts::wait`], running `on_cancel` if the actor stops before the future
+    /// completes
+    pub fn wait_with_cleanup<F, C>(&mut self, f: F, on_cancel: C)
+    where
+        F: ActorFuture<A, Output = ()> + 'static,
+        C: FnOnce(&mut A, &mut A::Context) + 'static,
+    {
+        self.wait
+            .push(ActorWaitItem::new(f).with_cleanup(on_cancel));
+    }
+
+    /// Stop waiting for the futures passed to [`ContextParts::wait`] since the last poll of
+    /// the context once `timeout` has passed, and let them run in the background instead
+    pub(crate) fn detach_wait_after(&mut self, timeout: Duration) {
+        self.wait = std::mem::take(&mut self.wait)
+            .into_iter()
+            .map(|item| item.detach_after(timeout))
+            .collect();
+    }
+
     #[inline]
     /// Cancel previously scheduled future.
     pub fn cancel_future(&mut self, handle: SpawnHandle) -> bool {
@@ -157,17 +268,234 @@ where
         true
     }
 
+    #[inline]
+    /// Cancel all previously scheduled futures.
+    ///
+    /// Returns the number of futures that got cancelled.
+    pub fn cancel_all_futures(&mut self) -> usize {
+        let upto = self.cancel_upto.into_usize();
+        let queued = &self.handles[2..];
+        let cancelled = self
+            .items
+            .iter()
+            .map(|(handle, _)| handle)
+            .chain(&self.merged)
+            .filter(|handle| handle.into_usize() > upto && !queued.contains(handle))
+            .count();
+        self.items.clear();
+        // the debounce timers are among the cancelled futures
+        self.debounced.clear();
+        self.cancel_upto = self.handles[0];
+        self.flags.insert(ContextFlags::CANCEL_ALL);
+        cancelled
+    }
+
+    /// Removes queued messages of type `M` from the mailbox.
+    pub fn drain_pending<M>(&mut self) -> Vec<M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+    {
+        self.addr.take_queued()
+    }
+
+    #[inline]
+    /// Stop processing mailbox messages until [`ContextParts::resume`] is called.
+    pub fn pause(&mut self) {
+        self.flags.insert(ContextFlags::PAUSED);
+    }
+
+    #[inline]
+    /// Continue processing mailbox messages.
+    pub fn resume(&mut self) {
+        if self.flags.contains(ContextFlags::PAUSED) {
+            self.flags.remove(ContextFlags::PAUSED);
+            self.flags.insert(ContextFlags::RESUMED);
+        }
+    }
+
+    #[inline]
+    /// Is mailbox processing paused
+    pub fn paused(&self) -> bool {
+        self.flags.contains(ContextFlags::PAUSED)
+    }
+
     #[inline]
     pub fn capacity(&mut self) -> usize {
         self.addr.capacity()
     }
 
+    #[inline]
+    /// Number of messages waiting in the mailbox
+    pub fn mailbox_len(&self) -> usize {
+        self.addr.queued()
+    }
+
     #[inline]
     pub fn set_mailbox_capacity(&mut self, cap: usize) {
         self.flags.insert(ContextFlags::MB_CAP_CHANGED);
         self.addr.set_capacity(cap);
     }
 
+    #[inline]
+    pub fn set_mailbox_budget(&mut self, budget: usize) {
+        self.mb_budget = budget;
+    }
+
+    #[inline]
+    pub fn set_slow_handler_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_handler = threshold;
+    }
+
+    #[inline]
+    /// Token of the cancellable message currently being handled
+    pub fn cancel_token(&self) -> Option<CancelToken> {
+        self.cancel_token.clone()
+    }
+
+    #[inline]
+    pub(crate) fn set_cancel_token(&mut self, token: Option<CancelToken>) -> Option<CancelToken> {
+        std::mem::replace(&mut self.cancel_token, token)
+    }
+
+    /// Name of the actor if one was set explicitly
+    #[inline]
+    pub(crate) fn explicit_name(&self) -> Option<&Cow<'static, str>> {
+        self.name.as_ref()
+    }
+
+    #[inline]
+    /// Name of the actor, defaults to its type name
+    pub fn name(&self) -> &str {
+        match self.name {
+            Some(ref name) => name,
+            None => type_name::<A>(),
+        }
+    }
+
+    #[inline]
+    /// Set name of the actor
+    pub fn set_name(&mut self, name: Cow<'static, str>) {
+        self.name = Some(name);
+    }
+
+    #[inline]
+    /// Correlation ID attached to messages sent while this actor is running
+    pub fn correlation_id(&self) -> Option<u64> {
+        self.correlation_id
+    }
+
+    #[inline]
+    /// Set correlation ID, returns the previous one
+    pub fn set_correlation_id(&mut self, id: Option<u64>) -> Option<u64> {
+        replace_correlation_id(id);
+        std::mem::replace(&mut self.correlation_id, id)
+    }
+
+    #[inline]
+    /// Register the debounced message for `key`, returns the previously registered one
+    pub fn replace_debounced(
+        &mut self,
+        key: Cow<'static, str>,
+        handle: SpawnHandle,
+    ) -> Option<SpawnHandle> {
+        self.debounced.insert(key, handle)
+    }
+
+    #[inline]
+    /// Unregister the debounced message for `key` if it is still the one with `handle`
+    pub fn remove_debounced(&mut self, key: &str, handle: SpawnHandle) {
+        if self.debounced.get(key) == Some(&handle) {
+            self.debounced.remove(key);
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn debounced_len(&self) -> usize {
+        self.debounced.len()
+    }
+
+    #[inline]
+    /// Register the timer ending the actor's lifetime, returns the previously registered one
+    pub fn replace_lifetime(&mut self, handle: SpawnHandle) -> Option<SpawnHandle> {
+        self.lifetime.replace(handle)
+    }
+
+    /// Token fired when the actor starts stopping, fired already if it has stopped
+    pub fn stop_token(&mut self) -> StopToken {
+        let stopped = !self.flags.contains(ContextFlags::RUNNING);
+        let token = self.stop_token.get_or_insert_with(StopToken::default);
+        if stopped {
+            token.fire();
+        }
+        token.clone()
+    }
+
+    fn fire_stop_token(&mut self) {
+        if let Some(token) = &self.stop_token {
+            token.fire();
+        }
+    }
+
+    /// Set the idle lifetime, counting from now; returns the timer of the previous one
+    pub fn set_idle_lifetime(&mut self, dur: Duration, timer: SpawnHandle) -> Option<SpawnHandle> {
+        self.idle
+            .replace(IdleLifetime {
+                dur,
+                last_active: Instant::now(),
+                timer,
+            })
+            .map(|idle| idle.timer)
+    }
+
+    /// Idle lifetime and time since the last mailbox message, if an idle lifetime is set
+    pub fn idle_time(&self) -> Option<(Duration, Duration)> {
+        self.idle
+            .as_ref()
+            .map(|idle| (idle.dur, idle.last_active.elapsed()))
+    }
+
+    #[inline]
+    /// Register the rescheduled idle timer
+    pub fn set_idle_timer(&mut self, timer: SpawnHandle) {
+        if let Some(idle) = &mut self.idle {
+            idle.timer = timer;
+        }
+    }
+
+    #[inline]
+    /// Time since the actor was started, zero before that
+    pub fn uptime(&self) -> Duration {
+        self.started_at
+            .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+
+    #[inline]
+    /// Notify `tx` once the actor has started and finished the futures it waits on
+    pub(crate) fn set_ready(&mut self, tx: oneshot::Sender<()>) {
+        self.ready = Some(tx);
+    }
+
+    /// Add an interceptor that runs before each mailbox message is handled
+    pub fn add_interceptor<F>(&mut self, f: F)
+    where
+        F: FnMut(&mut A, &dyn Any, &mut A::Context) -> bool + 'static,
+    {
+        self.interceptors.push(Box::new(f));
+    }
+
+    #[inline]
+    /// Log the messages still queued in the mailbox when the actor is dropped
+    pub fn set_log_dropped_on_stop(&mut self, enabled: bool) {
+        self.log_dropped_on_stop = enabled;
+    }
+
+    #[inline]
+    /// System the context was created in, if any
+    pub fn system(&self) -> Option<&System> {
+        self.system.as_ref()
+    }
+
     #[inline]
     pub fn address(&self) -> Addr<A> {
         Addr::new(self.addr.sender())
@@ -183,6 +511,12 @@ where
         self.wait = SmallVec::new();
         self.items = SmallVec::new();
         self.handles[0] = SpawnHandle::default();
+        self.merged.clear();
+        self.cancel_upto = SpawnHandle::default();
+        self.debounced.clear();
+        self.lifetime = None;
+        self.idle = None;
+        self.stop_token = None;
     }
 
     #[inline]
@@ -197,6 +531,38 @@ where
     }
 }
 
+/// Runs the interceptors of the context on `msg`, returns `false` if one of them drops it.
+///
+/// Also records the activity for the idle lifetime, as every mailbox message passes through.
+fn intercept<A, C>(act: &mut A, ctx: &mut C, msg: &dyn Any) -> bool
+where
+    C: AsyncContextParts<A>,
+    A: Actor<Context = C>,
+{
+    if let Some(idle) = &mut ctx.parts().idle {
+        idle.last_active = Instant::now();
+    }
+
+    if ctx.parts().interceptors.is_empty() {
+        return true;
+    }
+
+    // interceptors get the context, so they can't stay in it while they run
+    let mut interceptors = std::mem::take(&mut ctx.parts().interceptors);
+    let pass = interceptors.iter_mut().all(|f| f(act, msg, ctx));
+    let added = std::mem::replace(&mut ctx.parts().interceptors, interceptors);
+    ctx.parts().interceptors.extend(added);
+    pass
+}
+
+struct IdleLifetime {
+    dur: Duration,
+    // when the last mailbox message was received
+    last_active: Instant,
+    // fires when `dur` could have passed, then checks `last_active`
+    timer: SpawnHandle,
+}
+
 pub struct ContextFut<A, C>
 where
     C: AsyncContextParts<A> + Unpin,
@@ -207,6 +573,8 @@ where
     mailbox: Mailbox<A>,
     wait: SmallVec<[ActorWaitItem<A>; 2]>,
     items: SmallVec<[Item<A>; 3]>,
+    finalizing: bool,
+    finalizer: Option<ResponseActFuture<A, ()>>,
 }
 
 impl<A, C> fmt::Debug for ContextFut<A, C>
@@ -229,7 +597,11 @@ where
             self.ctx.parts().stop();
             let waker = futures_task::noop_waker();
             let mut cx = std::task::Context::from_waker(&waker);
-            let _ = Pin::new(self).poll(&mut cx);
+            let _ = Pin::new(&mut *self).poll(&mut cx);
+        }
+
+        if self.ctx.parts().log_dropped_on_stop {
+            self.log_dropped();
         }
     }
 }
@@ -246,6 +618,8 @@ where
             mailbox,
             wait: SmallVec::new(),
             items: SmallVec::new(),
+            finalizing: false,
+            finalizer: None,
         }
     }
 
@@ -279,6 +653,11 @@ where
         }
     }
 
+    /// Returns whether the actor has started and no [`AsyncContext::wait`] future is pending.
+    pub(crate) fn ready(&mut self) -> bool {
+        self.ctx.parts().started() && !self.ctx.parts().waiting() && self.wait.is_empty()
+    }
+
     /// Restarts the [`AsyncContext`] of this [`ContextFut`] returning whether the [`Context`] was
     /// restarted.
     ///
@@ -299,14 +678,83 @@ where
         if self.mailbox.connected() {
             self.wait = SmallVec::new();
             self.items = SmallVec::new();
+            self.finalizing = false;
+            self.finalizer = None;
             self.ctx.parts().restart();
             self.act.restarting(&mut self.ctx);
+            self.emit(LifecycleStage::Restarted);
             true
         } else {
             false
         }
     }
 
+    /// Closes the mailbox, logging the number and types of the messages still queued.
+    fn log_dropped(&mut self) {
+        let mut dropped: Vec<(&'static str, usize)> = Vec::new();
+        self.mailbox.close_with(|env| {
+            let msg_type = env.message_type();
+            match dropped.iter_mut().find(|(name, _)| *name == msg_type) {
+                Some((_, count)) => *count += 1,
+                None => dropped.push((msg_type, 1)),
+            }
+        });
+
+        if dropped.is_empty() {
+            return;
+        }
+        let total: usize = dropped.iter().map(|(_, count)| count).sum();
+        let types = dropped
+            .iter()
+            .map(|(name, count)| format!("{} x{}", name, count))
+            .collect::<Vec<_>>()
+            .join(", ");
+        warn!(
+            "{} stopped with {} queued messages dropped: {}",
+            self.ctx.parts().name(),
+            total,
+            types
+        );
+    }
+
+    fn emit(&mut self, stage: LifecycleStage) {
+        lifecycle::emit(stage, type_name::<A>(), self.ctx.parts().explicit_name());
+    }
+
+    fn call_stopping(&mut self) -> Running {
+        self.emit(LifecycleStage::Stopping);
+        self.ctx.parts().fire_stop_token();
+        Actor::stopping(&mut self.act, &mut self.ctx)
+    }
+
+    /// Runs the actor's finalizer, if any, then calls [`Actor::stopped`].
+    fn finish(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.finalizing {
+            self.cancel_waits();
+            self.finalizing = true;
+            self.ctx.parts().flags = ContextFlags::STOPPED | ContextFlags::STARTED;
+            self.finalizer = Actor::finalize(&mut self.act, &mut self.ctx);
+        }
+
+        if let Some(fut) = self.finalizer.as_mut() {
+            ready!(fut.as_mut().poll(&mut self.act, &mut self.ctx, cx));
+            self.finalizer = None;
+        }
+
+        Actor::stopped(&mut self.act, &mut self.ctx);
+        self.emit(LifecycleStage::Stopped);
+        Poll::Ready(())
+    }
+
+    /// Abandons the futures the actor is still waiting on, most recent first, running their
+    /// cleanups.
+    fn cancel_waits(&mut self) {
+        self.merge();
+        for item in std::mem::take(&mut self.wait).into_iter().rev() {
+            item.cancel(&mut self.act, &mut self.ctx);
+        }
+    }
+
     fn merge(&mut self) -> bool {
         let mut modified = false;
 
@@ -317,20 +765,34 @@ where
         }
         if !parts.items.is_empty() {
             modified = true;
-            self.items.extend(parts.items.drain(0..));
+            for item in parts.items.drain(0..) {
+                parts.merged.push(item.0);
+                self.items.push(item);
+            }
         }
         //
         if parts.flags.contains(ContextFlags::MB_CAP_CHANGED) {
             modified = true;
             parts.flags.remove(ContextFlags::MB_CAP_CHANGED);
         }
-        if parts.handles.len() > 2 {
+        // mailbox has to be polled again
+        if parts.flags.contains(ContextFlags::RESUMED) {
+            modified = true;
+            parts.flags.remove(ContextFlags::RESUMED);
+        }
+        if parts.handles.len() > 2 || parts.flags.contains(ContextFlags::CANCEL_ALL) {
             modified = true;
         }
 
         modified
     }
 
+    #[inline]
+    fn has_cancelled(&mut self) -> bool {
+        let parts = self.ctx.parts();
+        parts.handles.len() > 2 || parts.flags.contains(ContextFlags::CANCEL_ALL)
+    }
+
     fn clean_canceled_handle(&mut self) {
         fn remove_item_by_handle<C>(
             items: &mut SmallVec<[Item<C>; 3]>,
@@ -340,7 +802,8 @@ where
             let mut removed = false;
             while idx < items.len() {
                 if &items[idx].0 == handle {
-                    items.swap_remove(idx);
+                    // keep the remaining items, e.g. notifications, in order
+                    items.remove(idx);
                     removed = true;
                 } else {
                     idx += 1;
@@ -358,6 +821,17 @@ where
                 remove_item_by_handle(&mut self.ctx.parts().items, &handle);
             }
         }
+
+        let parts = self.ctx.parts();
+        if parts.flags.contains(ContextFlags::CANCEL_ALL) {
+            parts.flags.remove(ContextFlags::CANCEL_ALL);
+            let upto = parts.cancel_upto.into_usize();
+            self.items.retain(|(handle, _)| handle.into_usize() > upto);
+        }
+        parts.merged.clear();
+        parts
+            .merged
+            .extend(self.items.iter().map(|(handle, _)| *handle));
     }
 }
 
@@ -371,10 +845,17 @@ where
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let this = self.get_mut();
+        let _correlation = CorrelationScope::enter(this.ctx.parts().correlation_id);
+
+        if this.finalizing {
+            return this.finish(cx);
+        }
 
         if !this.ctx.parts().flags.contains(ContextFlags::STARTED) {
             this.ctx.parts().flags.insert(ContextFlags::STARTED);
+            this.ctx.parts().started_at = Some(Instant::now());
             Actor::started(&mut this.act, &mut this.ctx);
+            this.emit(LifecycleStage::Started);
 
             // check cancelled handles, just in case
             if this.merge() {
@@ -389,13 +870,32 @@ where
             while !this.wait.is_empty() && !this.stopping() {
                 let idx = this.wait.len() - 1;
                 let item = this.wait.last_mut().unwrap();
-                ready!(Pin::new(item).poll(&mut this.act, &mut this.ctx, cx));
+                if Pin::new(item)
+                    .poll(&mut this.act, &mut this.ctx, cx)
+                    .is_pending()
+                {
+                    if !this.stopping() {
+                        return Poll::Pending;
+                    }
+                    // the future stopped the actor, let `Actor::stopping` decide on it
+                    break;
+                }
                 this.wait.remove(idx);
                 this.merge();
             }
 
+            if let Some(tx) = this.ctx.parts().ready.take() {
+                let _ = tx.send(());
+            }
+
             // process mailbox
-            this.mailbox.poll(&mut this.act, &mut this.ctx, cx);
+            if !this.ctx.parts().paused() {
+                this.mailbox.set_budget(this.ctx.parts().mb_budget);
+                this.mailbox
+                    .set_slow_handler_threshold(this.ctx.parts().slow_handler);
+                this.mailbox
+                    .poll_intercepted(&mut this.act, &mut this.ctx, cx, intercept);
+            }
             if !this.wait.is_empty() && !this.stopping() {
                 continue;
             }
@@ -412,7 +912,7 @@ where
                         }
 
                         // check cancelled handles
-                        if this.ctx.parts().handles.len() > 2 {
+                        if this.has_cancelled() {
                             // this code is not very efficient, relaying on fact that
                             // cancellation should be rear also number of futures
                             // in actor context should be small
@@ -437,7 +937,9 @@ where
                         }
                     }
                     Poll::Ready(()) => {
-                        this.items.swap_remove(idx);
+                        // keep the remaining items, e.g. notifications, in order
+                        let (handle, _) = this.items.remove(idx);
+                        this.ctx.parts().merged.retain(|merged| *merged != handle);
 
                         // got new waiting item. merge
                         if this.ctx.waiting() {
@@ -461,6 +963,7 @@ where
                 // as we're sure there are no more items to be cancelled.
                 if this.items.is_empty() {
                     this.ctx.parts().handles.truncate(2);
+                    this.ctx.parts().flags.remove(ContextFlags::CANCEL_ALL);
                 }
                 continue;
             }
@@ -468,23 +971,22 @@ where
             // check state
             if this.ctx.parts().flags.contains(ContextFlags::RUNNING) {
                 // possible stop condition
-                if !this.alive() && Actor::stopping(&mut this.act, &mut this.ctx) == Running::Stop {
-                    this.ctx.parts().flags = ContextFlags::STOPPED | ContextFlags::STARTED;
-                    Actor::stopped(&mut this.act, &mut this.ctx);
-                    return Poll::Ready(());
+                if !this.alive() && this.call_stopping() == Running::Stop {
+                    return this.finish(cx);
                 }
             } else if this.ctx.parts().flags.contains(ContextFlags::STOPPING) {
-                if Actor::stopping(&mut this.act, &mut this.ctx) == Running::Stop {
-                    this.ctx.parts().flags = ContextFlags::STOPPED | ContextFlags::STARTED;
-                    Actor::stopped(&mut this.act, &mut this.ctx);
-                    return Poll::Ready(());
+                if this.call_stopping() == Running::Stop {
+                    return this.finish(cx);
                 } else {
                     this.ctx.parts().flags.remove(ContextFlags::STOPPING);
                     this.ctx.parts().flags.insert(ContextFlags::RUNNING);
                     continue;
                 }
             } else if this.ctx.parts().flags.contains(ContextFlags::STOPPED) {
+                this.ctx.parts().fire_stop_token();
+                this.cancel_waits();
                 Actor::stopped(&mut this.act, &mut this.ctx);
+                this.emit(LifecycleStage::Stopped);
                 return Poll::Ready(());
             }
 
@@ -1,4 +1,5 @@
 use std::{
+    any::type_name,
     future::Future,
     pin::Pin,
     task::{self, Poll},
@@ -6,16 +7,22 @@ use std::{
 };
 
 use futures_core::{ready, stream::Stream};
+use log::warn;
 use pin_project_lite::pin_project;
 
 use crate::{
     actor::{Actor, ActorContext, AsyncContext},
-    clock::Sleep,
+    clock::{sleep, Instant, Sleep},
     fut::ActorFuture,
     handler::{Handler, Message, MessageResponse},
 };
 
-pub(crate) struct ActorWaitItem<A: Actor>(Pin<Box<dyn ActorFuture<A, Output = ()>>>);
+type OnCancel<A> = Box<dyn FnOnce(&mut A, &mut <A as Actor>::Context)>;
+
+pub(crate) struct ActorWaitItem<A: Actor> {
+    fut: Pin<Box<dyn ActorFuture<A, Output = ()>>>,
+    on_cancel: Option<OnCancel<A>>,
+}
 
 impl<A> ActorWaitItem<A>
 where
@@ -27,7 +34,26 @@ where
     where
         F: ActorFuture<A, Output = ()> + 'static,
     {
-        ActorWaitItem(Box::pin(fut))
+        ActorWaitItem {
+            fut: Box::pin(fut),
+            on_cancel: None,
+        }
+    }
+
+    /// Runs `on_cancel` if the item is abandoned before the future completes.
+    pub fn with_cleanup<F>(mut self, on_cancel: F) -> Self
+    where
+        F: FnOnce(&mut A, &mut A::Context) + 'static,
+    {
+        self.on_cancel = Some(Box::new(on_cancel));
+        self
+    }
+
+    /// Abandons the item, running its cleanup, if any.
+    pub fn cancel(mut self, act: &mut A, ctx: &mut A::Context) {
+        if let Some(on_cancel) = self.on_cancel.take() {
+            on_cancel(act, ctx);
+        }
     }
 
     pub fn poll(
@@ -36,17 +62,70 @@ where
         ctx: &mut A::Context,
         task: &mut task::Context<'_>,
     ) -> Poll<()> {
-        match self.0.as_mut().poll(act, ctx, task) {
-            Poll::Pending => {
-                if ctx.state().alive() {
-                    Poll::Pending
-                } else {
-                    Poll::Ready(())
-                }
-            }
+        match self.fut.as_mut().poll(act, ctx, task) {
+            // a future with a cleanup that stopped the actor stays queued, `Actor::stopping`
+            // decides whether it is resumed or cancelled
+            Poll::Pending if ctx.state().alive() || self.on_cancel.is_some() => Poll::Pending,
+            Poll::Pending => Poll::Ready(()),
             Poll::Ready(_) => Poll::Ready(()),
         }
     }
+
+    /// Stops waiting for the future once `timeout` has passed, leaving it running as a spawned
+    /// future. A cleanup is not run for the detached future.
+    pub fn detach_after(self, timeout: Duration) -> Self {
+        ActorWaitItem {
+            fut: Box::pin(DetachAfter {
+                fut: Some(self.fut),
+                sleep: sleep(timeout),
+                timeout,
+            }),
+            on_cancel: self.on_cancel,
+        }
+    }
+}
+
+pin_project! {
+    struct DetachAfter<A: Actor> {
+        fut: Option<Pin<Box<dyn ActorFuture<A, Output = ()>>>>,
+        #[pin]
+        sleep: Sleep,
+        timeout: Duration,
+    }
+}
+
+impl<A> ActorFuture<A> for DetachAfter<A>
+where
+    A: Actor,
+    A::Context: AsyncContext<A>,
+{
+    type Output = ();
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut task::Context<'_>,
+    ) -> Poll<()> {
+        let this = self.project();
+        let fut = match this.fut {
+            Some(fut) => fut,
+            None => return Poll::Ready(()),
+        };
+
+        if fut.as_mut().poll(act, ctx, task).is_ready() {
+            return Poll::Ready(());
+        }
+        ready!(this.sleep.poll(task));
+
+        warn!(
+            "{} waited more than {:?} for a future, continuing while it runs in the background",
+            type_name::<A>(),
+            this.timeout
+        );
+        ctx.spawn(this.fut.take().unwrap());
+        Poll::Ready(())
+    }
 }
 
 pin_project! {
@@ -64,6 +143,14 @@ impl<M: Message> ActorDelayedMessageItem<M> {
             timeout: actix_rt::time::sleep(timeout),
         }
     }
+
+    /// Delivers the message at `deadline`, or on the first poll if it has already passed.
+    pub fn at(msg: M, deadline: Instant) -> Self {
+        Self {
+            msg: Some(msg),
+            timeout: actix_rt::time::sleep_until(deadline),
+        }
+    }
 }
 
 impl<A, M> ActorFuture<A> for ActorDelayedMessageItem<M>
@@ -0,0 +1,131 @@
+//! Handling of messages that could not be delivered.
+//!
+//! A message sent with [`Addr::do_send`](crate::Addr::do_send) or
+//! [`Recipient::do_send`](crate::Recipient::do_send) to an actor that has already stopped is
+//! dropped silently by default. [`DeadLetters`] lets important message types be routed to a
+//! handler instead, while all other types keep being dropped.
+
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use actix_rt::System;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use crate::{
+    address::{Recipient, WeakRecipient},
+    handler::Message,
+};
+
+/// A message that could not be delivered to its actor.
+pub struct DeadLetter<M>(pub M);
+
+impl<M> Message for DeadLetter<M> {
+    type Result = ();
+}
+
+type HandlerMap = HashMap<(usize, TypeId), Box<dyn Any + Send>>;
+
+static HANDLERS: Lazy<Mutex<HandlerMap>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Per-system registry of dead-letter handlers, keyed by message type.
+///
+/// Handlers are held weakly, so they neither keep their actor alive nor outlive its system.
+/// Whoever sets a handler keeps its actor running; once the actor has stopped, dead letters
+/// of its type are dropped again.
+///
+/// ```
+/// use actix::{
+///     dead_letter::{DeadLetter, DeadLetters},
+///     prelude::*,
+/// };
+///
+/// #[derive(Message)]
+/// #[rtype(result = "()")]
+/// struct Disconnect(usize);
+///
+/// struct Audit;
+///
+/// impl Actor for Audit {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<DeadLetter<Disconnect>> for Audit {
+///     type Result = ();
+///
+///     fn handle(&mut self, msg: DeadLetter<Disconnect>, _: &mut Self::Context) {
+///         println!("session {} disconnected after its server stopped", (msg.0).0);
+///     }
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let audit = Audit.start();
+/// DeadLetters::set_handler_for(audit.clone().recipient());
+/// # }
+/// ```
+pub struct DeadLetters;
+
+impl DeadLetters {
+    /// Routes undeliverable messages of type `M` in the current system to `recipient`.
+    ///
+    /// Replaces the handler previously set for `M`, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a running system.
+    pub fn set_handler_for<M>(recipient: Recipient<DeadLetter<M>>)
+    where
+        M: Message + Send + 'static,
+    {
+        let key = (System::current().id(), TypeId::of::<M>());
+        HANDLERS.lock().insert(key, Box::new(recipient.downgrade()));
+    }
+
+    /// Removes the dead-letter handler for messages of type `M` in the current system.
+    ///
+    /// Returns `false` if no handler was set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a running system.
+    pub fn remove_handler_for<M>() -> bool
+    where
+        M: Message + Send + 'static,
+    {
+        let key = (System::current().id(), TypeId::of::<M>());
+        HANDLERS.lock().remove(&key).is_some()
+    }
+}
+
+/// Passes an undeliverable message to its dead-letter handler, or drops it if there is none.
+pub(crate) fn deliver<M>(msg: M)
+where
+    M: Message + Send + 'static,
+{
+    let sys = match System::try_current() {
+        Some(sys) => sys,
+        None => return,
+    };
+
+    let key = (sys.id(), TypeId::of::<M>());
+    let recipient = {
+        let mut handlers = HANDLERS.lock();
+        let recipient = handlers
+            .get(&key)
+            .and_then(|handler| handler.downcast_ref::<WeakRecipient<DeadLetter<M>>>())
+            .map(WeakRecipient::upgrade);
+        // forget handlers whose actor has stopped
+        if let Some(None) = recipient {
+            handlers.remove(&key);
+        }
+        recipient.flatten()
+    };
+
+    // undeliverable dead letters are not routed again
+    if let Some(recipient) = recipient {
+        recipient.do_send_unrouted(DeadLetter(msg));
+    }
+}
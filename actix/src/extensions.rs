@@ -0,0 +1,69 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    fmt,
+};
+
+/// A map storing at most one value of each type.
+///
+/// Each [`Context`](crate::Context) owns one, see
+/// [`extensions`](crate::Context::extensions). It holds data that is needed across handlers
+/// and spawned futures of an actor, but does not belong in the actor itself, such as values
+/// attached by shared middleware-like helpers.
+#[derive(Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Extensions {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts a value, returning the value of the same type that was stored before.
+    pub fn insert<T: 'static>(&mut self, val: T) -> Option<T> {
+        self.map
+            .insert(TypeId::of::<T>(), Box::new(val))
+            .and_then(|prev| prev.downcast().ok().map(|prev| *prev))
+    }
+
+    /// Returns a reference to the value of type `T`.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.map
+            .get(&TypeId::of::<T>())
+            .and_then(|val| val.downcast_ref())
+    }
+
+    /// Returns a mutable reference to the value of type `T`.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.map
+            .get_mut(&TypeId::of::<T>())
+            .and_then(|val| val.downcast_mut())
+    }
+
+    /// Returns `true` if a value of type `T` is stored.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Removes the value of type `T` and returns it.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.map
+            .remove(&TypeId::of::<T>())
+            .and_then(|val| val.downcast().ok().map(|val| *val))
+    }
+
+    /// Removes all values.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
@@ -9,7 +9,9 @@ use std::{
 
 pub use map::Map;
 use pin_project_lite::pin_project;
+pub use select::{select, Select};
 pub use then::Then;
+pub use throttle::{Throttle, Throttled};
 pub use timeout::Timeout;
 
 use crate::actor::Actor;
@@ -17,7 +19,9 @@ use crate::actor::Actor;
 mod either;
 mod map;
 pub mod result;
+mod select;
 mod then;
+mod throttle;
 mod timeout;
 
 /// Trait for types which are a placeholder of a value that may become
@@ -154,6 +158,17 @@ pub trait ActorFutureExt<A: Actor>: ActorFuture<A> {
         Timeout::new(self, timeout)
     }
 
+    /// Delays running the future while `throttle` already has its maximum number of futures
+    /// running.
+    ///
+    /// See [`Throttle`] for details.
+    fn throttle(self, throttle: &Throttle) -> Throttled<Self>
+    where
+        Self: Sized,
+    {
+        Throttled::new(self, throttle)
+    }
+
     /// Wrap the future in a Box, pinning it.
     ///
     /// A shortcut for wrapping in [`Box::pin`].
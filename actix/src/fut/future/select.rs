@@ -0,0 +1,102 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::future::Either;
+
+use crate::{actor::Actor, fut::ActorFuture};
+
+/// Future for the [`select`] function, resolving with whichever of two actor futures finishes
+/// first.
+///
+/// This is created by the [`select`] function.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct Select<A, B> {
+    inner: Option<(A, B)>,
+}
+
+/// Waits for either one of two differently-typed actor futures to complete.
+///
+/// Resolves to [`Either::Left`] with the output of `a` and the unfinished `b`, or to
+/// [`Either::Right`] with the output of `b` and the unfinished `a`. The unfinished future can be
+/// polled further or dropped to cancel it. When both are ready on the same poll, `a` wins.
+///
+/// This mirrors [`futures::future::select`](futures_util::future::select) and likewise requires
+/// both futures to be [`Unpin`]; use [`boxed_local`](super::ActorFutureExt::boxed_local) or
+/// [`Box::pin`] for futures that are not.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use actix::prelude::*;
+/// use actix::{clock::sleep, fut};
+/// use futures_util::future::Either;
+///
+/// struct MyActor;
+///
+/// impl Actor for MyActor {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Context<Self>) {
+///         let response = sleep(Duration::from_millis(50)).into_actor(self).boxed_local();
+///         let deadline = sleep(Duration::from_millis(10)).into_actor(self).boxed_local();
+///
+///         fut::select(response, deadline)
+///             .map(|res, _, _| {
+///                 assert!(matches!(res, Either::Right(_)));
+///                 System::current().stop();
+///             })
+///             .spawn(ctx);
+///     }
+/// }
+/// # fn main() {
+/// # let sys = System::new();
+/// # sys.block_on(async { MyActor.start() });
+/// # sys.run().unwrap();
+/// # }
+/// ```
+pub fn select<A, B, Act>(a: A, b: B) -> Select<A, B>
+where
+    A: ActorFuture<Act> + Unpin,
+    B: ActorFuture<Act> + Unpin,
+    Act: Actor,
+{
+    Select {
+        inner: Some((a, b)),
+    }
+}
+
+impl<A, B, Act> ActorFuture<Act> for Select<A, B>
+where
+    A: ActorFuture<Act> + Unpin,
+    B: ActorFuture<Act> + Unpin,
+    Act: Actor,
+{
+    type Output = Either<(A::Output, B), (B::Output, A)>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut Act,
+        ctx: &mut Act::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let (a, b) = this
+            .inner
+            .as_mut()
+            .expect("ActorFuture polled after finish");
+
+        if let Poll::Ready(res) = Pin::new(a).poll(act, ctx, task) {
+            let (_, b) = this.inner.take().unwrap();
+            return Poll::Ready(Either::Left((res, b)));
+        }
+
+        if let Poll::Ready(res) = Pin::new(b).poll(act, ctx, task) {
+            let (a, _) = this.inner.take().unwrap();
+            return Poll::Ready(Either::Right((res, a)));
+        }
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::ready;
+use pin_project_lite::pin_project;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::PollSemaphore;
+
+use crate::{actor::Actor, fut::ActorFuture};
+
+/// Limits how many futures wrapped with [`throttle`](super::ActorFutureExt::throttle) run at the
+/// same time.
+///
+/// Futures beyond the limit wait, in the order they were first polled, until a running one
+/// completes or is dropped. A waiting future is not polled at all, so work it starts lazily,
+/// such as an outgoing request, is not started either. Clones share the same limit.
+///
+/// ```
+/// # use actix::prelude::*;
+/// # use std::time::Duration;
+/// struct Crawler;
+///
+/// impl Actor for Crawler {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Self::Context) {
+///         // at most 2 fetches in flight
+///         let throttle = ctx.throttle(2);
+///
+///         for _ in 0..10 {
+///             let fetch = fut::wrap_future(actix_rt::time::sleep(Duration::from_millis(10)));
+///             ctx.spawn(fetch.throttle(&throttle));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct Throttle {
+    semaphore: Arc<Semaphore>,
+}
+
+impl Throttle {
+    /// Creates a throttle allowing `max_concurrent` futures to run at the same time.
+    pub fn new(max_concurrent: usize) -> Self {
+        Throttle {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+        }
+    }
+
+    /// Returns how many more futures could start running right away.
+    pub fn available(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+}
+
+impl fmt::Debug for Throttle {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Throttle")
+            .field("available", &self.available())
+            .finish()
+    }
+}
+
+pin_project! {
+    /// Future for the [`throttle`](super::ActorFutureExt::throttle) combinator, delays polling
+    /// the wrapped future until its [`Throttle`] allows it to run.
+    ///
+    /// This is created by the [`throttle`](super::ActorFutureExt::throttle) method.
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Throttled<F> {
+        #[pin]
+        fut: F,
+        semaphore: PollSemaphore,
+        permit: Option<OwnedSemaphorePermit>,
+    }
+}
+
+impl<F> Throttled<F> {
+    pub(super) fn new(future: F, throttle: &Throttle) -> Self {
+        Self {
+            fut: future,
+            semaphore: PollSemaphore::new(Arc::clone(&throttle.semaphore)),
+            permit: None,
+        }
+    }
+}
+
+impl<F> fmt::Debug for Throttled<F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Throttled")
+            .field("running", &self.permit.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F, A> ActorFuture<A> for Throttled<F>
+where
+    F: ActorFuture<A>,
+    A: Actor,
+{
+    type Output = F::Output;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if this.permit.is_none() {
+            // the semaphore is never closed
+            *this.permit = ready!(this.semaphore.poll_acquire(task));
+        }
+
+        let res = ready!(this.fut.poll(act, ctx, task));
+        *this.permit = None;
+        Poll::Ready(res)
+    }
+}
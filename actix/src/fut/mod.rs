@@ -7,8 +7,9 @@ pub mod try_future;
 pub use self::{
     future::{
         result::{err, ok, ready, result, Ready},
-        wrap_future, ActorFuture, ActorFutureExt, LocalBoxActorFuture, WrapFuture,
+        select, wrap_future, ActorFuture, ActorFutureExt, LocalBoxActorFuture, Throttle,
+        WrapFuture,
     },
     stream::{wrap_stream, ActorStream, ActorStreamExt, WrapStream},
-    try_future::{ActorTryFuture, ActorTryFutureExt},
+    try_future::{retry, ActorTryFuture, ActorTryFutureExt, RetryPolicy},
 };
@@ -0,0 +1,68 @@
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    actor::Actor,
+    fut::{ActorFuture, ActorStream},
+};
+
+pin_project! {
+    /// Future for the [`for_each`](super::ActorStreamExt::for_each) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct ForEach<S, F, Fut> {
+        #[pin]
+        stream: S,
+        f: F,
+        #[pin]
+        future: Option<Fut>,
+    }
+}
+
+pub(super) fn new<S, A, F, Fut>(stream: S, f: F) -> ForEach<S, F, Fut>
+where
+    S: ActorStream<A>,
+    A: Actor,
+    F: FnMut(S::Item, &mut A, &mut A::Context) -> Fut,
+    Fut: ActorFuture<A, Output = ()>,
+{
+    ForEach {
+        stream,
+        f,
+        future: None,
+    }
+}
+
+impl<S, A, F, Fut> ActorFuture<A> for ForEach<S, F, Fut>
+where
+    S: ActorStream<A>,
+    A: Actor,
+    F: FnMut(S::Item, &mut A, &mut A::Context) -> Fut,
+    Fut: ActorFuture<A, Output = ()>,
+{
+    type Output = ();
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+        loop {
+            if let Some(fut) = this.future.as_mut().as_pin_mut() {
+                ready!(fut.poll(act, ctx, task));
+                this.future.set(None);
+            } else if let Some(item) = ready!(this.stream.as_mut().poll_next(act, ctx, task)) {
+                this.future.set(Some((this.f)(item, act, ctx)));
+            } else {
+                return Poll::Ready(());
+            }
+        }
+    }
+}
@@ -6,8 +6,10 @@ use std::{
 };
 
 pub use collect::Collect;
+pub use filter::Filter;
 pub use finish::Finish;
 pub use fold::Fold;
+pub use for_each::ForEach;
 use futures_core::stream::Stream;
 pub use map::Map;
 use pin_project_lite::pin_project;
@@ -20,8 +22,10 @@ use super::future::ActorFuture;
 use crate::actor::Actor;
 
 mod collect;
+mod filter;
 mod finish;
 mod fold;
+mod for_each;
 mod map;
 mod skip_while;
 mod take_while;
@@ -97,6 +101,37 @@ pub trait ActorStreamExt<A: Actor>: ActorStream<A> {
         fold::new(self, f, init)
     }
 
+    /// Filters the values produced by this stream according to the provided
+    /// asynchronous predicate.
+    ///
+    /// As values of this stream are made available, the provided predicate `f`
+    /// will be run against them. If the predicate resolves to `true`, then the
+    /// stream will yield the value, but if the predicate resolves to `false`,
+    /// then the value will be discarded and the next value will be produced.
+    fn filter<F, Fut>(self, f: F) -> Filter<Self, Self::Item, F, Fut>
+    where
+        F: FnMut(&Self::Item, &mut A, &mut A::Context) -> Fut,
+        Fut: ActorFuture<A, Output = bool>,
+        Self: Sized,
+    {
+        filter::new(self, f)
+    }
+
+    /// Runs this stream to completion, executing the provided asynchronous
+    /// closure for each element on the stream.
+    ///
+    /// The closure provided will be called for each item this stream produces,
+    /// yielding a future. That future will then be executed to completion
+    /// before moving on to the next item.
+    fn for_each<F, Fut>(self, f: F) -> ForEach<Self, F, Fut>
+    where
+        F: FnMut(Self::Item, &mut A, &mut A::Context) -> Fut,
+        Fut: ActorFuture<A, Output = ()>,
+        Self: Sized,
+    {
+        for_each::new(self, f)
+    }
+
     /// Take elements from this stream while the provided asynchronous predicate
     /// resolves to `true`.
     ///
@@ -0,0 +1,174 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures_util::ready;
+use pin_project_lite::pin_project;
+
+use crate::{
+    actor::Actor,
+    clock::{sleep, Sleep},
+    fut::{future::ActorFuture, try_future::ActorTryFuture},
+};
+
+/// Limits and backoff for the [`retry`] combinator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    backoff: Duration,
+    multiplier: u32,
+}
+
+impl RetryPolicy {
+    /// Creates a policy making at most `max_attempts` attempts and waiting `backoff` between
+    /// them.
+    ///
+    /// The first attempt is always made, so `max_attempts` of 0 behaves like 1.
+    pub fn new(max_attempts: usize, backoff: Duration) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff,
+            multiplier: 1,
+        }
+    }
+
+    /// Multiplies the backoff by `multiplier` after every failed attempt.
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+}
+
+pin_project! {
+    #[project = StateProj]
+    #[derive(Debug)]
+    enum State<Fut> {
+        Start,
+        Running {
+            #[pin]
+            fut: Fut,
+        },
+        Waiting {
+            #[pin]
+            sleep: Sleep,
+        },
+    }
+}
+
+pin_project! {
+    /// Future for the [`retry`] combinator, re-running a failing actor future.
+    ///
+    /// This is created by the [`retry`] function.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless polled"]
+    pub struct Retry<Fut, F> {
+        #[pin]
+        state: State<Fut>,
+        factory: F,
+        policy: RetryPolicy,
+        attempts: usize,
+        backoff: Duration,
+    }
+}
+
+/// Runs the actor future created by `factory` until it resolves to [`Ok`], or until `policy`
+/// runs out of attempts, in which case the last [`Err`] is returned.
+///
+/// The factory is called with the actor and its context for every attempt, so each attempt can
+/// use the current actor state. Attempts are separated by the policy's backoff.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use actix::prelude::*;
+/// use actix::fut::{self, RetryPolicy};
+///
+/// struct Client {
+///     failures: usize,
+/// }
+///
+/// impl Actor for Client {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Context<Self>) {
+///         let policy = RetryPolicy::new(3, Duration::from_millis(10));
+///
+///         fut::retry(policy, |act: &mut Self, _: &mut Context<Self>| {
+///             // stands in for a call to a flaky service
+///             act.failures += 1;
+///             fut::result(if act.failures < 3 { Err(()) } else { Ok(()) })
+///         })
+///         .map(|res, _, _| {
+///             assert!(res.is_ok());
+///             System::current().stop();
+///         })
+///         .spawn(ctx);
+///     }
+/// }
+/// # fn main() {
+/// # let sys = System::new();
+/// # sys.block_on(async { Client { failures: 0 }.start() });
+/// # sys.run().unwrap();
+/// # }
+/// ```
+pub fn retry<A, F, Fut>(policy: RetryPolicy, factory: F) -> Retry<Fut, F>
+where
+    A: Actor,
+    F: FnMut(&mut A, &mut A::Context) -> Fut,
+    Fut: ActorTryFuture<A>,
+{
+    Retry {
+        state: State::Start,
+        factory,
+        backoff: policy.backoff,
+        policy,
+        attempts: 0,
+    }
+}
+
+impl<A, F, Fut> ActorFuture<A> for Retry<Fut, F>
+where
+    A: Actor,
+    F: FnMut(&mut A, &mut A::Context) -> Fut,
+    Fut: ActorTryFuture<A>,
+{
+    type Output = Result<Fut::Ok, Fut::Error>;
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        loop {
+            match this.state.as_mut().project() {
+                StateProj::Start => {
+                    let fut = (this.factory)(act, ctx);
+                    *this.attempts += 1;
+                    this.state.set(State::Running { fut });
+                }
+                StateProj::Running { fut } => match ready!(fut.try_poll(act, ctx, task)) {
+                    Ok(res) => return Poll::Ready(Ok(res)),
+                    Err(err) if *this.attempts >= this.policy.max_attempts => {
+                        return Poll::Ready(Err(err))
+                    }
+                    Err(_) => {
+                        let backoff = *this.backoff;
+                        *this.backoff = backoff.saturating_mul(this.policy.multiplier);
+                        this.state.set(State::Waiting {
+                            sleep: sleep(backoff),
+                        });
+                    }
+                },
+                StateProj::Waiting { sleep } => {
+                    ready!(sleep.poll(task));
+                    this.state.set(State::Start);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,10 @@
-use std::{fmt, future::Future, pin::Pin, sync::Arc};
+use std::{
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
 
 pub use tokio::sync::oneshot::Sender as OneshotSender;
 
@@ -245,6 +251,8 @@ pub type ResponseFuture<I> = Pin<Box<dyn Future<Output = I>>>;
 ///   need to access Actor's internal state or context to progress, either
 ///   because it's completely agnostic to it or because the required data has
 ///   already been moved to it and it won't need Actor state to continue.
+/// - [`UnboxedResponse`] can be used instead of [`ResponseFuture`] when the future type is
+///   known and [`Unpin`], to avoid boxing it.
 /// - [`ResponseActFuture`] should be used when the future returned
 ///   will, at some point, need to access Actor's internal state or context
 ///   in order to finish.
@@ -397,6 +405,119 @@ where
     }
 }
 
+/// An unboxed future for asynchronous message handling.
+///
+/// Works like [`ResponseFuture`] for handlers whose future type is known and [`Unpin`], but
+/// stores the future inline. A future that is ready when the handler returns is resolved
+/// without allocating; otherwise it is spawned, like a [`ResponseFuture`].
+///
+/// ```
+/// use std::future::{ready, Ready};
+///
+/// use actix::{prelude::*, UnboxedResponse};
+///
+/// #[derive(Message)]
+/// #[rtype(result = "usize")]
+/// struct Len(String);
+///
+/// struct MyActor;
+///
+/// impl Actor for MyActor {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<Len> for MyActor {
+///     type Result = UnboxedResponse<Ready<usize>>;
+///
+///     fn handle(&mut self, msg: Len, _: &mut Context<Self>) -> Self::Result {
+///         UnboxedResponse(ready(msg.0.len()))
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct UnboxedResponse<F>(pub F);
+
+impl<A, M, F> MessageResponse<A, M> for UnboxedResponse<F>
+where
+    A: Actor,
+    M: Message,
+    F: Future<Output = M::Result> + Unpin + 'static,
+{
+    fn handle(self, _: &mut A::Context, tx: Option<OneshotSender<M::Result>>) {
+        let mut fut = self.0;
+
+        let waker = futures_task::noop_waker();
+        match Pin::new(&mut fut).poll(&mut Context::from_waker(&waker)) {
+            Poll::Ready(res) => tx.send(res),
+            // polled again by the spawned task, which registers a real waker
+            Poll::Pending => {
+                actix_rt::spawn(async { tx.send(fut.await) });
+            }
+        }
+    }
+}
+
+/// A response computed on the blocking thread pool, see [`respond_blocking`].
+pub struct BlockingResponse<I>(Box<dyn FnOnce() -> I + Send>);
+
+impl<I> fmt::Debug for BlockingResponse<I> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("BlockingResponse").finish()
+    }
+}
+
+/// Responds with the return value of `f`, running it on the blocking thread pool of
+/// [`actix_rt`].
+///
+/// Handlers doing CPU-bound work or blocking I/O would otherwise keep the arbiter's thread, and
+/// every actor on it, from making progress until they return. The actor goes on handling
+/// messages while `f` runs; the sender receives the result once it is done. If `f` panics, the
+/// sender gets [`MailboxError::Closed`](crate::MailboxError::Closed).
+///
+/// ```
+/// use actix::{prelude::*, respond_blocking, BlockingResponse};
+///
+/// #[derive(Message)]
+/// #[rtype(result = "u64")]
+/// struct Checksum(Vec<u8>);
+///
+/// struct Hasher;
+///
+/// impl Actor for Hasher {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<Checksum> for Hasher {
+///     type Result = BlockingResponse<u64>;
+///
+///     fn handle(&mut self, msg: Checksum, _: &mut Self::Context) -> Self::Result {
+///         respond_blocking(move || msg.0.iter().map(|&b| u64::from(b)).sum())
+///     }
+/// }
+/// ```
+pub fn respond_blocking<F, I>(f: F) -> BlockingResponse<I>
+where
+    F: FnOnce() -> I + Send + 'static,
+{
+    BlockingResponse(Box::new(f))
+}
+
+impl<A, M> MessageResponse<A, M> for BlockingResponse<M::Result>
+where
+    A: Actor,
+    M: Message,
+    M::Result: Send + 'static,
+{
+    fn handle(self, _: &mut A::Context, tx: Option<OneshotSender<M::Result>>) {
+        let task = actix_rt::task::spawn_blocking(self.0);
+        actix_rt::spawn(async {
+            if let Ok(res) = task.await {
+                tx.send(res);
+            }
+        });
+    }
+}
+
 enum ResponseTypeItem<I> {
     Result(I),
     Fut(Pin<Box<dyn Future<Output = I>>>),
@@ -1,24 +1,35 @@
 use std::{
     cell::RefCell,
     collections::VecDeque,
-    io,
+    fmt,
+    future::Future,
+    io::{self, IoSlice},
     marker::PhantomData,
     ops::DerefMut,
     pin::Pin,
     rc::Rc,
     task,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use bitflags::bitflags;
-use bytes::BytesMut;
+use bytes::{Buf, Bytes, BytesMut};
 use futures_sink::Sink;
-use tokio::io::{AsyncWrite, AsyncWriteExt};
-use tokio_util::codec::Encoder;
+use smallvec::SmallVec;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt},
+    sync::oneshot,
+};
+use tokio_util::codec::{Decoder, Encoder, FramedRead};
 
 use crate::{
     actor::{Actor, ActorContext, AsyncContext, Running, SpawnHandle},
+    clock::{sleep, Sleep},
     fut::ActorFuture,
+    handler::{Message, MessageResponse, OneshotSender},
+    stream::StreamHandler,
+    MailboxError,
 };
 
 /// A helper trait for write handling.
@@ -45,6 +56,15 @@ where
     fn finished(&mut self, ctx: &mut Self::Context) {
         ctx.stop()
     }
+
+    /// Called instead of [`finished`](Self::finished) when the writer stops because
+    /// [`error`](Self::error) returned `Running::Stop`.
+    ///
+    /// The error itself is passed to `error` beforehand. By default this method calls
+    /// `finished`.
+    fn errored(&mut self, ctx: &mut Self::Context) {
+        self.finished(ctx)
+    }
 }
 
 bitflags! {
@@ -57,6 +77,15 @@ bitflags! {
 const LOW_WATERMARK: usize = 4 * 1024;
 const HIGH_WATERMARK: usize = 4 * LOW_WATERMARK;
 
+/// Maximum number of buffers passed to a single vectored write.
+const MAX_WRITE_SLICES: usize = 64;
+
+/// Number of bytes written in a single poll before yielding to other tasks.
+const WRITE_BUDGET: usize = 256 * 1024;
+
+/// Default number of consecutive zero-byte writes tolerated before reporting an error.
+const MAX_ZERO_WRITES: usize = 1;
+
 /// A wrapper for `AsyncWrite` types.
 pub struct Writer<T: AsyncWrite, E: From<io::Error>> {
     inner: UnsafeWriter<T, E>,
@@ -72,12 +101,151 @@ impl<T: AsyncWrite, E: From<io::Error>> Clone for UnsafeWriter<T, E> {
 
 struct InnerWriter<E: From<io::Error>> {
     flags: Flags,
+    // chunks queued by `Writer::write_bytes`, written before `buffer`
+    chunks: VecDeque<Bytes>,
     buffer: BytesMut,
-    error: Option<E>,
+    // reported to `WriteHandler::error` in order on the next poll
+    errors: VecDeque<E>,
     low: usize,
     high: usize,
     handle: SpawnHandle,
     task: Option<task::Waker>,
+    write_timeout: Option<Duration>,
+    // armed while data is pending and the IO makes no progress
+    timer: Option<Pin<Box<Sleep>>>,
+    // set by `close_with_timeout`, the writer is closed forcibly once it elapses
+    close_deadline: Option<Pin<Box<Sleep>>>,
+    // notified on the next successful flush, `None` once the writer task has ended
+    flush_waiters: Option<Vec<oneshot::Sender<()>>>,
+    // writes in a row that accepted no bytes
+    zero_writes: usize,
+    max_zero_writes: usize,
+}
+
+impl<E: From<io::Error>> InnerWriter<E> {
+    /// Checks if there is no data left to write.
+    fn is_empty(&self) -> bool {
+        self.chunks.is_empty() && self.buffer.is_empty()
+    }
+
+    /// Returns the number of bytes left to write.
+    fn buffered(&self) -> usize {
+        self.chunks.iter().map(Bytes::len).sum::<usize>() + self.buffer.len()
+    }
+
+    /// Writes pending data to `io`, using a vectored write if there are queued chunks.
+    fn poll_write_to<T: AsyncWrite>(
+        &self,
+        io: Pin<&mut T>,
+        task: &mut Context<'_>,
+    ) -> Poll<io::Result<usize>> {
+        if self.chunks.is_empty() {
+            return io.poll_write(task, &self.buffer);
+        }
+
+        let mut slices = self
+            .chunks
+            .iter()
+            .take(MAX_WRITE_SLICES)
+            .map(|chunk| IoSlice::new(chunk))
+            .collect::<SmallVec<[_; 16]>>();
+        if slices.len() < MAX_WRITE_SLICES && !self.buffer.is_empty() {
+            slices.push(IoSlice::new(&self.buffer));
+        }
+
+        io.poll_write_vectored(task, &slices)
+    }
+
+    /// Polls the write timeout, arming it first if needed.
+    ///
+    /// Returns `true` if the timeout elapsed without any progress being made.
+    fn poll_timeout(&mut self, task: &mut Context<'_>) -> bool {
+        let timeout = match self.write_timeout {
+            Some(timeout) => timeout,
+            None => return false,
+        };
+
+        let timer = self.timer.get_or_insert_with(|| Box::pin(sleep(timeout)));
+        if timer.as_mut().poll(task).is_ready() {
+            self.timer = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if the deadline set by `close_with_timeout` has elapsed.
+    fn poll_close_deadline(&mut self, task: &mut Context<'_>) -> bool {
+        match self.close_deadline {
+            Some(ref mut deadline) => deadline.as_mut().poll(task).is_ready(),
+            None => false,
+        }
+    }
+
+    /// Returns `Pending` for a stalled drain, or records a timeout error and stops the drain so
+    /// that the writer reports it.
+    fn drain_pending(&mut self, task: &mut Context<'_>) -> Poll<()> {
+        if self.poll_close_deadline(task) {
+            // the writer closes itself on its next poll
+            Poll::Ready(())
+        } else if self.poll_timeout(task) {
+            self.errors.push_back(write_timeout_error().into());
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    /// Returns a receiver notified once everything written so far has been flushed.
+    ///
+    /// The sender is dropped without notifying if the writer stops first.
+    fn after_flush(&mut self) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        if let Some(waiters) = &mut self.flush_waiters {
+            waiters.push(tx);
+            if let Some(task) = self.task.take() {
+                task.wake_by_ref();
+            }
+        }
+        rx
+    }
+
+    /// Records a write that accepted no bytes.
+    ///
+    /// Returns `true` once `max_zero_writes` of them happened in a row, which is then reported
+    /// as a [`io::ErrorKind::WriteZero`] error.
+    fn zero_write(&mut self) -> bool {
+        self.zero_writes += 1;
+        if self.zero_writes >= self.max_zero_writes {
+            self.zero_writes = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Removes `n` written bytes from the front of the pending data.
+    fn advance(&mut self, mut n: usize) {
+        self.timer = None;
+        self.zero_writes = 0;
+
+        while n > 0 {
+            match self.chunks.front_mut() {
+                Some(chunk) if chunk.len() <= n => {
+                    n -= chunk.len();
+                    self.chunks.pop_front();
+                }
+                Some(chunk) => {
+                    chunk.advance(n);
+                    return;
+                }
+                None => {
+                    let _ = self.buffer.split_to(n);
+                    return;
+                }
+            }
+        }
+    }
 }
 
 impl<T: AsyncWrite, E: From<io::Error> + 'static> Writer<T, E> {
@@ -90,12 +258,19 @@ impl<T: AsyncWrite, E: From<io::Error> + 'static> Writer<T, E> {
         let inner = UnsafeWriter(
             Rc::new(RefCell::new(InnerWriter {
                 flags: Flags::empty(),
+                chunks: VecDeque::new(),
                 buffer: BytesMut::new(),
-                error: None,
+                errors: VecDeque::new(),
                 low: LOW_WATERMARK,
                 high: HIGH_WATERMARK,
                 handle: SpawnHandle::default(),
                 task: None,
+                write_timeout: None,
+                timer: None,
+                close_deadline: None,
+                flush_waiters: Some(Vec::new()),
+                zero_writes: 0,
+                max_zero_writes: MAX_ZERO_WRITES,
             })),
             Rc::new(RefCell::new(io)),
         );
@@ -120,6 +295,48 @@ impl<T: AsyncWrite, E: From<io::Error> + 'static> Writer<T, E> {
         self.inner.0.borrow().flags.contains(Flags::CLOSED)
     }
 
+    /// Requests the buffered data to be written out and the underlying IO to be flushed.
+    ///
+    /// This only wakes up the writer task: each of its polls writes out the buffered data and
+    /// then flushes the underlying IO, so nothing else needs to be recorded. Use
+    /// [`reply_after_flush`](Self::reply_after_flush) to find out when the flush completed.
+    pub fn flush(&mut self) {
+        let mut inner = self.inner.0.borrow_mut();
+        if let Some(task) = inner.task.take() {
+            task.wake_by_ref();
+        }
+    }
+
+    /// Returns a response that resolves to `reply` only once the data written so far has been
+    /// flushed, see [`ResponseAfterFlush`].
+    pub fn reply_after_flush<R>(&mut self, reply: R) -> ResponseAfterFlush<R> {
+        ResponseAfterFlush {
+            reply,
+            flushed: self.inner.0.borrow_mut().after_flush(),
+        }
+    }
+
+    /// Sets the write timeout.
+    ///
+    /// If pending data makes no progress for `timeout`, [`WriteHandler::error`] is called with an
+    /// [`io::ErrorKind::TimedOut`] error. This detects peers that stopped reading. There is no
+    /// timeout by default.
+    pub fn set_write_timeout(&mut self, timeout: Duration) {
+        let mut inner = self.inner.0.borrow_mut();
+        inner.write_timeout = Some(timeout);
+        inner.timer = None;
+    }
+
+    /// Sets how many writes in a row may accept no bytes before [`WriteHandler::error`] is
+    /// called with an [`io::ErrorKind::WriteZero`] error.
+    ///
+    /// The writer retries on its next poll after each zero-byte write, so a misbehaving IO that
+    /// keeps accepting nothing is reported instead of keeping the actor busy. If `error` returns
+    /// [`Running::Continue`], the count starts over. Defaults to 1; 0 is treated as 1.
+    pub fn set_max_zero_writes(&mut self, max: usize) {
+        self.inner.0.borrow_mut().max_zero_writes = max.max(1);
+    }
+
     /// Sets the write buffer capacity.
     pub fn set_buffer_capacity(&mut self, low_watermark: usize, high_watermark: usize) {
         let mut inner = self.inner.0.borrow_mut();
@@ -131,6 +348,24 @@ impl<T: AsyncWrite, E: From<io::Error> + 'static> Writer<T, E> {
     pub fn write(&mut self, msg: &[u8]) {
         let mut inner = self.inner.0.borrow_mut();
         inner.buffer.extend_from_slice(msg);
+        // the waker is taken so that successive writes before the next poll wake the task once
+        if let Some(task) = inner.task.take() {
+            task.wake_by_ref();
+        }
+    }
+
+    /// Sends a chunk of bytes to the sink without copying it.
+    ///
+    /// Queued chunks are written with vectored IO, which avoids copying large payloads into the
+    /// write buffer.
+    pub fn write_bytes(&mut self, buf: Bytes) {
+        let mut inner = self.inner.0.borrow_mut();
+        // keep ordering with data previously passed to `write`
+        if !inner.buffer.is_empty() {
+            let pending = inner.buffer.split().freeze();
+            inner.chunks.push_back(pending);
+        }
+        inner.chunks.push_back(buf);
         if let Some(task) = inner.task.take() {
             task.wake_by_ref();
         }
@@ -167,61 +402,107 @@ where
     ) -> Poll<Self::Output> {
         let this = self.get_mut();
         let mut inner = this.inner.0.borrow_mut();
-        if let Some(err) = inner.error.take() {
+        while let Some(err) = inner.errors.pop_front() {
             if act.error(err, ctx) == Running::Stop {
-                act.finished(ctx);
+                act.errored(ctx);
                 return Poll::Ready(());
             }
         }
 
+        if inner.poll_close_deadline(task) {
+            inner.close_deadline = None;
+            inner.flags |= Flags::CLOSED;
+            inner.chunks.clear();
+            inner.buffer.clear();
+            // let the handler inspect the writer
+            drop(inner);
+            let _ = act.error(close_timeout_error().into(), ctx);
+            act.errored(ctx);
+            return Poll::Ready(());
+        }
+
         let mut io = this.inner.1.borrow_mut();
         inner.task = None;
-        while !inner.buffer.is_empty() {
-            match Pin::new(io.deref_mut()).poll_write(task, &inner.buffer) {
-                Poll::Ready(Ok(n)) => {
-                    if n == 0
-                        && act.error(
-                            io::Error::new(
-                                io::ErrorKind::WriteZero,
-                                "failed to write frame to transport",
-                            )
-                            .into(),
-                            ctx,
-                        ) == Running::Stop
+        let mut written = 0;
+        while !inner.is_empty() {
+            // a large buffer and a fast IO would otherwise monopolize the executor
+            if written >= WRITE_BUDGET {
+                task.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            match inner.poll_write_to(Pin::new(io.deref_mut()), task) {
+                Poll::Ready(Ok(0)) => {
+                    if inner.zero_write()
+                        && act.error(write_zero_error().into(), ctx) == Running::Stop
                     {
-                        act.finished(ctx);
+                        act.errored(ctx);
                         return Poll::Ready(());
                     }
-                    let _ = inner.buffer.split_to(n);
+                    // retry on the next poll instead of spinning on an IO that accepts nothing
+                    task.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Poll::Ready(Ok(n)) => {
+                    inner.advance(n);
+                    written += n;
                 }
                 Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
-                    if inner.buffer.len() > inner.high {
+                    if inner.buffered() > inner.high {
                         ctx.wait(WriterDrain {
                             inner: this.inner.clone(),
                         });
+                    } else if write_timed_out(&mut inner, act, ctx, task) {
+                        act.errored(ctx);
+                        return Poll::Ready(());
                     }
                     return Poll::Pending;
                 }
                 Poll::Ready(Err(e)) => {
                     if act.error(e.into(), ctx) == Running::Stop {
-                        act.finished(ctx);
+                        act.errored(ctx);
+                        return Poll::Ready(());
+                    }
+                }
+                Poll::Pending => {
+                    if write_timed_out(&mut inner, act, ctx, task) {
+                        act.errored(ctx);
                         return Poll::Ready(());
                     }
+                    return Poll::Pending;
                 }
-                Poll::Pending => return Poll::Pending,
             }
         }
 
         // Try flushing the underlying IO
         match Pin::new(io.deref_mut()).poll_flush(task) {
-            Poll::Ready(Ok(_)) => (),
-            Poll::Pending => return Poll::Pending,
+            Poll::Ready(Ok(_)) => {
+                inner.timer = None;
+                for waiter in inner
+                    .flush_waiters
+                    .iter_mut()
+                    .flat_map(|waiters| waiters.drain(..))
+                {
+                    let _ = waiter.send(());
+                }
+            }
             Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
+                if write_timed_out(&mut inner, act, ctx, task) {
+                    act.errored(ctx);
+                    return Poll::Ready(());
+                }
+                return Poll::Pending;
+            }
+            Poll::Pending => {
+                if write_timed_out(&mut inner, act, ctx, task) {
+                    act.errored(ctx);
+                    return Poll::Ready(());
+                }
                 return Poll::Pending;
             }
             Poll::Ready(Err(e)) => {
                 if act.error(e.into(), ctx) == Running::Stop {
-                    act.finished(ctx);
+                    act.errored(ctx);
                     return Poll::Ready(());
                 }
             }
@@ -239,6 +520,127 @@ where
     }
 }
 
+impl<T, E> Drop for WriterFut<T, E>
+where
+    T: AsyncWrite + Unpin,
+    E: From<io::Error>,
+{
+    fn drop(&mut self) {
+        // pending replies fail instead of waiting for a flush that never comes
+        if let Ok(mut inner) = self.inner.0.try_borrow_mut() {
+            inner.flush_waiters = None;
+        }
+    }
+}
+
+/// A response that is held back until the writer has flushed the reply's bytes.
+///
+/// Created by [`FramedWrite::reply_after_flush`] or [`Writer::reply_after_flush`]. Returning it
+/// from a handler ties the [`Request`](crate::dev::Request) to the flush of everything the
+/// handler wrote beforehand, so the sender learns that its reply went out to the underlying IO
+/// rather than only that it was queued. If the writer stops before flushing, e.g. because
+/// [`WriteHandler::error`] returned [`Running::Stop`], the request fails with
+/// [`MailboxError::Closed`](crate::MailboxError::Closed).
+///
+/// ```
+/// # use std::io;
+/// use actix::{io::{FramedWrite, ResponseAfterFlush, WriteHandler}, prelude::*};
+/// use bytes::Bytes;
+/// use tokio::io::Sink;
+/// use tokio_util::codec::BytesCodec;
+///
+/// #[derive(Message)]
+/// #[rtype(result = "u32")]
+/// struct Reply(u32, Bytes);
+///
+/// struct Conn(FramedWrite<Bytes, Sink, BytesCodec>);
+///
+/// impl Actor for Conn {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl WriteHandler<io::Error> for Conn {}
+///
+/// impl Handler<Reply> for Conn {
+///     type Result = ResponseAfterFlush<u32>;
+///
+///     fn handle(&mut self, msg: Reply, _: &mut Self::Context) -> Self::Result {
+///         self.0.write(msg.1);
+///         self.0.reply_after_flush(msg.0)
+///     }
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let conn = Conn::create(|ctx| Conn(FramedWrite::new(tokio::io::sink(), BytesCodec::new(), ctx)));
+/// assert_eq!(conn.send(Reply(7, Bytes::from_static(b"pong"))).await.unwrap(), 7);
+/// # }
+/// ```
+pub struct ResponseAfterFlush<R> {
+    reply: R,
+    flushed: oneshot::Receiver<()>,
+}
+
+impl<R> fmt::Debug for ResponseAfterFlush<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ResponseAfterFlush")
+            .finish_non_exhaustive()
+    }
+}
+
+impl<A, M> MessageResponse<A, M> for ResponseAfterFlush<M::Result>
+where
+    A: Actor,
+    M: Message,
+    M::Result: 'static,
+{
+    fn handle(self, _: &mut A::Context, tx: Option<OneshotSender<M::Result>>) {
+        actix_rt::spawn(async move {
+            if self.flushed.await.is_ok() {
+                if let Some(tx) = tx {
+                    let _ = tx.send(self.reply);
+                }
+            }
+        });
+    }
+}
+
+/// Reports write timeouts to the actor. Returns `true` if the writer should stop.
+fn write_timed_out<A, E>(
+    inner: &mut InnerWriter<E>,
+    act: &mut A,
+    ctx: &mut A::Context,
+    task: &mut Context<'_>,
+) -> bool
+where
+    A: Actor + WriteHandler<E>,
+    A::Context: AsyncContext<A>,
+    E: From<io::Error>,
+{
+    // a new timer is armed when the actor chooses to continue
+    while inner.poll_timeout(task) {
+        if act.error(write_timeout_error().into(), ctx) == Running::Stop {
+            return true;
+        }
+    }
+    false
+}
+
+fn write_zero_error() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::WriteZero,
+        "failed to write frame to transport",
+    )
+}
+
+fn write_timeout_error() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "write timed out")
+}
+
+fn close_timeout_error() -> io::Error {
+    io::Error::new(io::ErrorKind::TimedOut, "close timed out")
+}
+
 struct WriterDrain<T, E>
 where
     T: AsyncWrite + Unpin,
@@ -264,37 +666,42 @@ where
     ) -> Poll<Self::Output> {
         let this = self.get_mut();
         let mut inner = this.inner.0.borrow_mut();
-        if inner.error.is_some() {
+        if !inner.errors.is_empty() {
             return Poll::Ready(());
         }
         let mut io = this.inner.1.borrow_mut();
-        while !inner.buffer.is_empty() {
-            match Pin::new(io.deref_mut()).poll_write(task, &inner.buffer) {
-                Poll::Ready(Ok(n)) => {
-                    if n == 0 {
-                        inner.error = Some(
-                            io::Error::new(
-                                io::ErrorKind::WriteZero,
-                                "failed to write frame to transport",
-                            )
-                            .into(),
-                        );
+        let mut written = 0;
+        while !inner.is_empty() {
+            if written >= WRITE_BUDGET {
+                task.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            match inner.poll_write_to(Pin::new(io.deref_mut()), task) {
+                Poll::Ready(Ok(0)) => {
+                    if inner.zero_write() {
+                        inner.errors.push_back(write_zero_error().into());
                         return Poll::Ready(());
                     }
-                    let _ = inner.buffer.split_to(n);
+                    task.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                Poll::Ready(Ok(n)) => {
+                    inner.advance(n);
+                    written += n;
                 }
                 Poll::Ready(Err(ref e)) if e.kind() == io::ErrorKind::WouldBlock => {
-                    return if inner.buffer.len() < inner.low {
+                    return if inner.buffered() < inner.low {
                         Poll::Ready(())
                     } else {
-                        Poll::Pending
+                        inner.drain_pending(task)
                     };
                 }
                 Poll::Ready(Err(e)) => {
-                    inner.error = Some(e.into());
+                    inner.errors.push_back(e.into());
                     return Poll::Ready(());
                 }
-                Poll::Pending => return Poll::Pending,
+                Poll::Pending => return inner.drain_pending(task),
             }
         }
         Poll::Ready(())
@@ -319,12 +726,19 @@ impl<I, T: AsyncWrite + Unpin, U: Encoder<I>> FramedWrite<I, T, U> {
         let inner = UnsafeWriter(
             Rc::new(RefCell::new(InnerWriter {
                 flags: Flags::empty(),
+                chunks: VecDeque::new(),
                 buffer: BytesMut::new(),
-                error: None,
+                errors: VecDeque::new(),
                 low: LOW_WATERMARK,
                 high: HIGH_WATERMARK,
                 handle: SpawnHandle::default(),
                 task: None,
+                write_timeout: None,
+                timer: None,
+                close_deadline: None,
+                flush_waiters: Some(Vec::new()),
+                zero_writes: 0,
+                max_zero_writes: MAX_ZERO_WRITES,
             })),
             Rc::new(RefCell::new(io)),
         );
@@ -347,12 +761,19 @@ impl<I, T: AsyncWrite + Unpin, U: Encoder<I>> FramedWrite<I, T, U> {
         let inner = UnsafeWriter(
             Rc::new(RefCell::new(InnerWriter {
                 buffer,
+                chunks: VecDeque::new(),
                 flags: Flags::empty(),
-                error: None,
+                errors: VecDeque::new(),
                 low: LOW_WATERMARK,
                 high: HIGH_WATERMARK,
                 handle: SpawnHandle::default(),
                 task: None,
+                write_timeout: None,
+                timer: None,
+                close_deadline: None,
+                flush_waiters: Some(Vec::new()),
+                zero_writes: 0,
+                max_zero_writes: MAX_ZERO_WRITES,
             })),
             Rc::new(RefCell::new(io)),
         );
@@ -372,11 +793,68 @@ impl<I, T: AsyncWrite + Unpin, U: Encoder<I>> FramedWrite<I, T, U> {
         self.inner.0.borrow_mut().flags.insert(Flags::CLOSING);
     }
 
+    /// Gracefully closes the sink like [`close`](Self::close), but gives up after `timeout`.
+    ///
+    /// If the buffered data has not been written out and flushed by then, e.g. because the peer
+    /// stopped reading, the sink is marked closed, [`WriteHandler::error`] is called with an
+    /// [`io::ErrorKind::TimedOut`] error and then [`WriteHandler::errored`]. The return value of
+    /// `error` is ignored, as the writer stops either way. Unwritten data is discarded.
+    pub fn close_with_timeout(&mut self, timeout: Duration) {
+        let mut inner = self.inner.0.borrow_mut();
+        inner.flags.insert(Flags::CLOSING);
+        inner.close_deadline = Some(Box::pin(sleep(timeout)));
+        if let Some(task) = inner.task.take() {
+            task.wake_by_ref();
+        }
+    }
+
     /// Checks if the sink is closed.
     pub fn closed(&self) -> bool {
         self.inner.0.borrow().flags.contains(Flags::CLOSED)
     }
 
+    /// Requests the buffered data to be written out and the underlying IO to be flushed.
+    ///
+    /// This only wakes up the writer task: each of its polls writes out the buffered data and
+    /// then flushes the underlying IO, so nothing else needs to be recorded. Use
+    /// [`reply_after_flush`](Self::reply_after_flush) to find out when the flush completed.
+    pub fn flush(&mut self) {
+        let mut inner = self.inner.0.borrow_mut();
+        if let Some(task) = inner.task.take() {
+            task.wake_by_ref();
+        }
+    }
+
+    /// Returns a response that resolves to `reply` only once the data written so far has been
+    /// flushed, see [`ResponseAfterFlush`].
+    pub fn reply_after_flush<R>(&mut self, reply: R) -> ResponseAfterFlush<R> {
+        ResponseAfterFlush {
+            reply,
+            flushed: self.inner.0.borrow_mut().after_flush(),
+        }
+    }
+
+    /// Sets the write timeout.
+    ///
+    /// If pending data makes no progress for `timeout`, [`WriteHandler::error`] is called with an
+    /// [`io::ErrorKind::TimedOut`] error. This detects peers that stopped reading. There is no
+    /// timeout by default.
+    pub fn set_write_timeout(&mut self, timeout: Duration) {
+        let mut inner = self.inner.0.borrow_mut();
+        inner.write_timeout = Some(timeout);
+        inner.timer = None;
+    }
+
+    /// Sets how many writes in a row may accept no bytes before [`WriteHandler::error`] is
+    /// called with an [`io::ErrorKind::WriteZero`] error.
+    ///
+    /// The writer retries on its next poll after each zero-byte write, so a misbehaving IO that
+    /// keeps accepting nothing is reported instead of keeping the actor busy. If `error` returns
+    /// [`Running::Continue`], the count starts over. Defaults to 1; 0 is treated as 1.
+    pub fn set_max_zero_writes(&mut self, max: usize) {
+        self.inner.0.borrow_mut().max_zero_writes = max.max(1);
+    }
+
     /// Sets the write buffer capacity.
     pub fn set_buffer_capacity(&mut self, low: usize, high: usize) {
         let mut inner = self.inner.0.borrow_mut();
@@ -385,11 +863,19 @@ impl<I, T: AsyncWrite + Unpin, U: Encoder<I>> FramedWrite<I, T, U> {
     }
 
     /// Writes an item to the sink.
+    ///
+    /// If encoding fails, the item is dropped along with anything the encoder wrote for it, and
+    /// the error is passed to [`WriteHandler::error`] on the next poll of the writer. Returning
+    /// [`Running::Continue`] from there keeps writing the other items, e.g. to skip a message
+    /// that is too large without dropping the connection.
     pub fn write(&mut self, item: I) {
         let mut inner = self.inner.0.borrow_mut();
-        let _ = self.enc.encode(item, &mut inner.buffer).map_err(|e| {
-            inner.error = Some(e);
-        });
+        let len = inner.buffer.len();
+        if let Err(err) = self.enc.encode(item, &mut inner.buffer) {
+            inner.buffer.truncate(len);
+            inner.errors.push_back(err);
+        }
+        // the waker is taken so that successive writes before the next poll wake the task once
         if let Some(task) = inner.task.take() {
             task.wake_by_ref();
         }
@@ -432,6 +918,12 @@ impl<I: 'static, S: Sink<I> + Unpin + 'static> SinkWrite<I, S> {
             task: None,
             handle: SpawnHandle::default(),
             buffer: VecDeque::new(),
+            reconnect: None,
+            retain: None,
+            in_flight: VecDeque::new(),
+            queued: 0,
+            sent: 0,
+            flush_waiters: Some(VecDeque::new()),
         }));
 
         let handle = ctxt.spawn(SinkWriteFuture {
@@ -447,7 +939,10 @@ impl<I: 'static, S: Sink<I> + Unpin + 'static> SinkWrite<I, S> {
     /// Returns unsent item if sink is closing or closed.
     pub fn write(&mut self, item: I) -> Result<(), I> {
         if self.inner.borrow().closing_flag.is_empty() {
-            self.inner.borrow_mut().buffer.push_back(item);
+            let mut inner = self.inner.borrow_mut();
+            inner.buffer.push_back(item);
+            inner.queued += 1;
+            drop(inner);
             self.notify_task();
             Ok(())
         } else {
@@ -468,6 +963,65 @@ impl<I: 'static, S: Sink<I> + Unpin + 'static> SinkWrite<I, S> {
         self.inner.borrow_mut().closing_flag.contains(Flags::CLOSED)
     }
 
+    /// Sets a factory used to replace the sink after it errors.
+    ///
+    /// When the sink emits an error and [`WriteHandler::error`] returns [`Running::Continue`],
+    /// the failed sink is dropped and `factory` is called to produce a new one. Queued items that
+    /// have not been handed to the failed sink yet are written to the new sink.
+    ///
+    /// Items that were already handed to the failed sink are lost, so every item is delivered
+    /// *at most once*. Use [`set_reconnect_replay`](Self::set_reconnect_replay) for
+    /// *at least once* delivery instead.
+    pub fn set_reconnect<F>(&mut self, factory: F)
+    where
+        F: FnMut() -> S + 'static,
+    {
+        let mut inner = self.inner.borrow_mut();
+        inner.reconnect = Some(Box::new(factory));
+        inner.retain = None;
+        inner.in_flight.clear();
+    }
+
+    /// Sets a factory used to replace the sink after it errors, replaying unflushed items.
+    ///
+    /// Works like [`set_reconnect`](Self::set_reconnect), but a copy of every item handed to
+    /// the sink is kept until the sink is successfully flushed. After reconnecting, these items
+    /// are written to the new sink ahead of the queued ones, so every item is delivered *at
+    /// least once*: an item the failed sink managed to deliver before erroring is sent again.
+    pub fn set_reconnect_replay<F>(&mut self, factory: F)
+    where
+        F: FnMut() -> S + 'static,
+        I: Clone,
+    {
+        let mut inner = self.inner.borrow_mut();
+        inner.reconnect = Some(Box::new(factory));
+        inner.retain = Some(I::clone);
+    }
+
+    /// Writes all queued items to the sink and flushes it.
+    ///
+    /// The returned future resolves once every item queued before this call has been pushed
+    /// through the sink's `poll_flush`. This lets an actor confirm that a reply actually went out
+    /// before proceeding. The writing itself is left to the writer, so dropping the future does
+    /// not affect it.
+    ///
+    /// Errors emitted by the sink are passed to [`WriteHandler::error`] as usual. If the writer
+    /// stops before the items were flushed, the future fails with
+    /// [`MailboxError::Closed`](crate::MailboxError::Closed).
+    pub fn flush_and_wait(&self) -> impl Future<Output = Result<(), MailboxError>> {
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut inner = self.inner.borrow_mut();
+            let queued = inner.queued;
+            if let Some(waiters) = &mut inner.flush_waiters {
+                waiters.push_back((queued, tx));
+            }
+        }
+        self.notify_task();
+
+        async move { rx.await.map_err(|_| MailboxError::Closed) }
+    }
+
     fn notify_task(&self) {
         if let Some(task) = &self.inner.borrow().task {
             task.wake_by_ref()
@@ -490,6 +1044,57 @@ struct InnerSinkWrite<I, S: Sink<I>> {
     // buffer of items to be sent so that multiple
     // calls to start_send don't silently skip items
     buffer: VecDeque<I>,
+
+    // produces a new sink after the current one errors
+    reconnect: Option<Box<dyn FnMut() -> S>>,
+
+    // copies items handed to the sink when replaying is enabled
+    retain: Option<fn(&I) -> I>,
+
+    // items handed to the sink since the last successful flush
+    in_flight: VecDeque<I>,
+
+    // number of items written so far, and how many of them were handed to the sink
+    queued: u64,
+    sent: u64,
+
+    // notified once the sink was flushed after the given number of items was handed to it,
+    // `None` once the writer has stopped
+    flush_waiters: Option<VecDeque<(u64, oneshot::Sender<()>)>>,
+}
+
+impl<I, S: Sink<I>> InnerSinkWrite<I, S> {
+    /// Replaces the sink using the reconnect factory, if there is one.
+    fn reconnect(&mut self) -> bool {
+        match self.reconnect {
+            Some(ref mut factory) => {
+                self.sink = factory();
+
+                // unflushed items go out first, in their original order
+                while let Some(item) = self.in_flight.pop_back() {
+                    self.buffer.push_front(item);
+                    self.sent -= 1;
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Notifies the waiters whose items have all been handed to the sink, after a flush.
+    fn flushed(&mut self) {
+        self.in_flight.clear();
+        if let Some(waiters) = &mut self.flush_waiters {
+            while waiters
+                .front()
+                .is_some_and(|(queued, _)| *queued <= self.sent)
+            {
+                let (_, tx) = waiters.pop_front().unwrap();
+                let _ = tx.send(());
+            }
+        }
+    }
 }
 
 struct SinkWriteFuture<I: 'static, S: Sink<I>> {
@@ -517,33 +1122,51 @@ where
         // and wake this task later.
         loop {
             // ensure sink is ready to receive next item
-            match Pin::new(&mut inner.sink).poll_ready(cx) {
-                Poll::Ready(Ok(())) => {
-                    if let Some(item) = inner.buffer.pop_front() {
+            let err = match Pin::new(&mut inner.sink).poll_ready(cx) {
+                Poll::Ready(Ok(())) => match inner.buffer.pop_front() {
+                    Some(item) => {
+                        if let Some(retain) = inner.retain {
+                            inner.in_flight.push_back(retain(&item));
+                        }
+                        inner.sent += 1;
+
                         // send front of buffer to sink
-                        let _ = Pin::new(&mut inner.sink).start_send(item);
-                    } else {
-                        break;
+                        match Pin::new(&mut inner.sink).start_send(item) {
+                            Err(err) if inner.reconnect.is_some() => err,
+                            _ => continue,
+                        }
                     }
-                }
-                Poll::Ready(Err(_err)) => {
-                    break;
-                }
-                Poll::Pending => {
-                    break;
-                }
+                    None => break,
+                },
+                // without a reconnect factory, errors are reported by flush/close below
+                Poll::Ready(Err(err)) if inner.reconnect.is_some() => err,
+                Poll::Ready(Err(_)) | Poll::Pending => break,
+            };
+
+            if act.error(err, ctxt) == Running::Stop {
+                act.errored(ctxt);
+                return Poll::Ready(());
             }
+            inner.reconnect();
+
+            // a sink that keeps failing must not keep the arbiter busy
+            inner.task.replace(cx.waker().clone());
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
         }
 
         if !inner.closing_flag.contains(Flags::CLOSING) {
             match Pin::new(&mut inner.sink).poll_flush(cx) {
                 Poll::Ready(Err(e)) => {
                     if act.error(e, ctxt) == Running::Stop {
-                        act.finished(ctxt);
+                        act.errored(ctxt);
                         return Poll::Ready(());
                     }
+                    if inner.reconnect() {
+                        cx.waker().wake_by_ref();
+                    }
                 }
-                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Ok(())) => inner.flushed(),
                 Poll::Pending => {}
             }
         } else {
@@ -551,11 +1174,16 @@ where
             match Pin::new(&mut inner.sink).poll_close(cx) {
                 Poll::Ready(Err(e)) => {
                     if act.error(e, ctxt) == Running::Stop {
-                        act.finished(ctxt);
+                        act.errored(ctxt);
                         return Poll::Ready(());
                     }
+                    if inner.reconnect() {
+                        cx.waker().wake_by_ref();
+                    }
                 }
                 Poll::Ready(Ok(())) => {
+                    inner.flushed();
+
                     // ensure all items in buffer have been sent before closing
                     if inner.buffer.is_empty() {
                         inner.closing_flag |= Flags::CLOSED;
@@ -572,3 +1200,61 @@ where
         Poll::Pending
     }
 }
+
+impl<I: 'static, S: Sink<I>> Drop for SinkWriteFuture<I, S> {
+    fn drop(&mut self) {
+        // fails the pending `flush_and_wait` futures
+        self.inner.borrow_mut().flush_waiters = None;
+    }
+}
+
+/// Glue for actors that handle the frames decoded from an [`AsyncRead`].
+///
+/// Implemented for every actor with a [`StreamHandler`] for the decoder's frames.
+///
+/// ```
+/// # use std::io;
+/// use actix::{io::FramedReadActor, prelude::*};
+/// use tokio_util::codec::{LinesCodec, LinesCodecError};
+///
+/// struct Session;
+///
+/// impl Actor for Session {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Self::Context) {
+///         Self::add_framed_read(ctx, tokio::io::empty(), LinesCodec::new());
+///     }
+/// }
+///
+/// impl StreamHandler<Result<String, LinesCodecError>> for Session {
+///     fn handle(&mut self, line: Result<String, LinesCodecError>, _: &mut Self::Context) {
+///         println!("line: {:?}", line);
+///     }
+/// }
+/// ```
+pub trait FramedReadActor<D>
+where
+    Self: Actor + StreamHandler<Result<D::Item, D::Error>>,
+    Self::Context: AsyncContext<Self>,
+    D: Decoder + 'static,
+{
+    /// Reads frames from `io` with `decoder` and passes them to the actor's [`StreamHandler`].
+    ///
+    /// This is a shorthand for adding a [`FramedRead`] to the context. Returns the handle of the
+    /// stream, see [`AsyncContext::add_stream`].
+    fn add_framed_read<T>(ctx: &mut Self::Context, io: T, decoder: D) -> SpawnHandle
+    where
+        T: AsyncRead + 'static,
+    {
+        ctx.add_stream(FramedRead::new(io, decoder))
+    }
+}
+
+impl<A, D> FramedReadActor<D> for A
+where
+    A: Actor + StreamHandler<Result<D::Item, D::Error>>,
+    A::Context: AsyncContext<A>,
+    D: Decoder + 'static,
+{
+}
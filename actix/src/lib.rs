@@ -30,18 +30,24 @@ doc_comment::doctest!("../README.md");
 
 mod actor;
 mod address;
+mod behavior;
+mod builder;
 mod context;
 mod context_impl;
 mod context_items;
+mod extensions;
 mod handler;
 mod mailbox;
+mod pool;
 mod stream;
 mod supervisor;
 
 pub mod actors;
 pub mod clock;
+pub mod dead_letter;
 pub mod fut;
 pub mod io;
+pub mod lifecycle;
 pub mod registry;
 pub mod sync;
 pub mod utils;
@@ -60,19 +66,27 @@ pub mod __private {
 pub use crate::context::ContextFutureSpawner;
 pub use crate::{
     actor::{Actor, ActorContext, ActorState, AsyncContext, Running, SpawnHandle, Supervised},
-    address::{Addr, MailboxError, Recipient, WeakAddr, WeakRecipient},
-    context::Context,
+    address::{
+        Addr, AndSendTo, CancelToken, CancellableRequest, ForwardTo, MailboxError, MailboxReady,
+        PipeError, PipeToSink, Recipient, RecipientSet, ResponseStream, SelfNotifier, WeakAddr,
+        WeakRecipient,
+    },
+    behavior::{Behavior, BehaviorFn},
+    builder::ActorBuilder,
+    context::{Context, StartReady, StopToken},
+    extensions::Extensions,
     fut::{
         ActorFuture, ActorFutureExt, ActorStream, ActorStreamExt, ActorTryFuture,
         ActorTryFutureExt, WrapFuture, WrapStream,
     },
     handler::{
-        ActorResponse, AtomicResponse, Handler, Message, MessageResult, Response,
-        ResponseActFuture, ResponseFuture,
+        respond_blocking, ActorResponse, AtomicResponse, BlockingResponse, Handler, Message,
+        MessageResult, Response, ResponseActFuture, ResponseFuture, UnboxedResponse,
     },
-    registry::{ArbiterService, Registry, SystemRegistry, SystemService},
+    pool::ActorPool,
+    registry::{ArbiterService, Registry, SystemRegistry, SystemService, Tracked},
     stream::StreamHandler,
-    supervisor::Supervisor,
+    supervisor::{SupervisionStrategy, Supervisor, SupervisorTree},
     sync::{SyncArbiter, SyncContext},
 };
 
@@ -98,7 +112,10 @@ pub mod prelude {
     pub use crate::{
         actor::{Actor, ActorContext, ActorState, AsyncContext, Running, SpawnHandle, Supervised},
         actors,
-        address::{Addr, MailboxError, Recipient, RecipientRequest, Request, SendError},
+        address::{
+            Addr, CancelToken, CancellableRequest, MailboxError, Recipient, RecipientRequest,
+            RecipientSet, Request, SendError,
+        },
         context::{Context, ContextFutureSpawner},
         dev, fut,
         fut::{
@@ -107,7 +124,7 @@ pub mod prelude {
         },
         handler::{
             ActorResponse, AtomicResponse, Handler, Message, MessageResult, Response,
-            ResponseActFuture, ResponseFuture,
+            ResponseActFuture, ResponseFuture, UnboxedResponse,
         },
         io,
         registry::{ArbiterService, SystemService},
@@ -130,7 +147,7 @@ pub mod dev {
     //! ```
 
     pub use crate::{
-        address::{Envelope, EnvelopeProxy, RecipientRequest, Request, ToEnvelope},
+        address::{Envelope, EnvelopeProxy, MailboxReady, RecipientRequest, Request, ToEnvelope},
         prelude::*,
     };
     pub mod channel {
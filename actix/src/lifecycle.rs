@@ -0,0 +1,144 @@
+//! Observing actor lifecycle changes.
+//!
+//! [`lifecycle_events`] returns a stream of the [`LifecycleEvent`]s of every actor in the current
+//! system, e.g. for a monitoring actor to keep track of running sessions. Events only describe
+//! the actor, so subscribers never keep it alive. Actors running in a [`SyncContext`] do not
+//! report events.
+//!
+//! [`SyncContext`]: crate::SyncContext
+//!
+//! ```
+//! use actix::{lifecycle::{lifecycle_events, LifecycleEvent, LifecycleStage}, prelude::*};
+//!
+//! struct Monitor;
+//!
+//! impl Actor for Monitor {
+//!     type Context = Context<Self>;
+//!
+//!     fn started(&mut self, ctx: &mut Self::Context) {
+//!         ctx.add_stream(lifecycle_events());
+//!     }
+//! }
+//!
+//! impl StreamHandler<LifecycleEvent> for Monitor {
+//!     fn handle(&mut self, event: LifecycleEvent, _: &mut Self::Context) {
+//!         if event.stage == LifecycleStage::Stopped {
+//!             println!("{} stopped", event.actor_type);
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+    task::{Context, Poll},
+};
+
+use actix_rt::System;
+use futures_core::stream::Stream;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Stage of an actor's lifecycle reported by a [`LifecycleEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleStage {
+    /// [`Actor::started`](crate::Actor::started) was called.
+    Started,
+    /// [`Actor::stopping`](crate::Actor::stopping) is about to be called.
+    Stopping,
+    /// [`Actor::stopped`](crate::Actor::stopped) was called.
+    Stopped,
+    /// The actor was restarted by its [`Supervisor`](crate::Supervisor).
+    Restarted,
+}
+
+/// A change in the lifecycle of an actor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LifecycleEvent {
+    /// The stage the actor entered.
+    pub stage: LifecycleStage,
+    /// Type name of the actor.
+    pub actor_type: &'static str,
+    /// Name of the actor, if one was set with
+    /// [`Actor::start_named`](crate::Actor::start_named) or
+    /// [`Context::set_name`](crate::Context::set_name).
+    pub name: Option<Cow<'static, str>>,
+}
+
+/// Stream of lifecycle events returned by [`lifecycle_events`].
+#[derive(Debug)]
+pub struct LifecycleEvents {
+    rx: UnboundedReceiver<LifecycleEvent>,
+}
+
+impl Stream for LifecycleEvents {
+    type Item = LifecycleEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+type SubscriberMap = HashMap<usize, Vec<UnboundedSender<LifecycleEvent>>>;
+
+static SUBSCRIBERS: Lazy<Mutex<SubscriberMap>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// number of subscribers across all systems, to skip the lock when nobody listens
+static SUBSCRIBER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns a stream of the lifecycle events of all actors in the current system.
+///
+/// Only events occurring after the call are reported. Dropping the stream unsubscribes.
+///
+/// # Panics
+///
+/// Panics if called outside of a running system.
+pub fn lifecycle_events() -> LifecycleEvents {
+    let (tx, rx) = unbounded_channel();
+    let id = System::current().id();
+
+    SUBSCRIBERS.lock().entry(id).or_default().push(tx);
+    SUBSCRIBER_COUNT.fetch_add(1, Ordering::Relaxed);
+
+    LifecycleEvents { rx }
+}
+
+/// Sends an event to the subscribers of the current system.
+pub(crate) fn emit(
+    stage: LifecycleStage,
+    actor_type: &'static str,
+    name: Option<&Cow<'static, str>>,
+) {
+    if SUBSCRIBER_COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+
+    let sys = match System::try_current() {
+        Some(sys) => sys,
+        None => return,
+    };
+
+    let mut subscribers = SUBSCRIBERS.lock();
+    let senders = match subscribers.get_mut(&sys.id()) {
+        Some(senders) => senders,
+        None => return,
+    };
+
+    let event = LifecycleEvent {
+        stage,
+        actor_type,
+        name: name.cloned(),
+    };
+
+    let len = senders.len();
+    senders.retain(|tx| tx.send(event.clone()).is_ok());
+    SUBSCRIBER_COUNT.fetch_sub(len - senders.len(), Ordering::Relaxed);
+
+    if senders.is_empty() {
+        subscribers.remove(&sys.id());
+    }
+}
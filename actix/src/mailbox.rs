@@ -1,21 +1,35 @@
-use std::{fmt, pin::Pin, task, task::Poll};
+use std::{
+    any::{type_name, Any},
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    task,
+    task::Poll,
+    time::{Duration, Instant},
+};
 
 use futures_core::stream::Stream;
+use log::warn;
 
 use crate::{
-    actor::{Actor, AsyncContext},
-    address::{channel, Addr, AddressReceiver, AddressSenderProducer, EnvelopeProxy},
+    actor::{Actor, ActorContext, AsyncContext, Running},
+    address::{channel, Addr, AddressReceiver, AddressSenderProducer, Envelope, EnvelopeProxy},
 };
 
 /// Default address channel capacity
 pub const DEFAULT_CAPACITY: usize = 16;
 
+/// Default number of messages handled in a row before spawned futures get polled
+pub const DEFAULT_BUDGET: usize = 128;
+
 pub struct Mailbox<A>
 where
     A: Actor,
     A::Context: AsyncContext<A>,
 {
     msgs: AddressReceiver<A>,
+    budget: usize,
+    slow_handler: Option<Duration>,
 }
 
 impl<A> fmt::Debug for Mailbox<A>
@@ -26,6 +40,8 @@ where
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt.debug_struct("Mailbox")
             .field("capacity", &self.capacity())
+            .field("budget", &self.budget)
+            .field("slow_handler", &self.slow_handler)
             .finish()
     }
 }
@@ -38,7 +54,11 @@ where
     #[inline]
     fn default() -> Self {
         let (_, rx) = channel::channel(DEFAULT_CAPACITY);
-        Mailbox { msgs: rx }
+        Mailbox {
+            msgs: rx,
+            budget: DEFAULT_BUDGET,
+            slow_handler: None,
+        }
     }
 }
 
@@ -49,7 +69,11 @@ where
 {
     #[inline]
     pub fn new(msgs: AddressReceiver<A>) -> Self {
-        Self { msgs }
+        Self {
+            msgs,
+            budget: DEFAULT_BUDGET,
+            slow_handler: None,
+        }
     }
 
     pub fn capacity(&self) -> usize {
@@ -60,11 +84,26 @@ where
         self.msgs.set_capacity(cap);
     }
 
+    /// Sets the number of messages handled in a row before the mailbox yields.
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget.max(1);
+    }
+
+    /// Sets the handling time above which a warning is logged, `None` to disable the check.
+    pub fn set_slow_handler_threshold(&mut self, threshold: Option<Duration>) {
+        self.slow_handler = threshold;
+    }
+
     #[inline]
     pub fn connected(&self) -> bool {
         self.msgs.connected()
     }
 
+    /// Closes the mailbox and passes the messages still queued to `f`.
+    pub(crate) fn close_with(&mut self, f: impl FnMut(Envelope<A>)) {
+        self.msgs.close_with(f)
+    }
+
     pub fn address(&self) -> Addr<A> {
         Addr::new(self.msgs.sender())
     }
@@ -74,13 +113,66 @@ where
     }
 
     pub fn poll(&mut self, act: &mut A, ctx: &mut A::Context, task: &mut task::Context<'_>) {
+        self.poll_intercepted(act, ctx, task, |_, _, _| true)
+    }
+
+    /// Like [`Mailbox::poll`], but drops the messages for which `intercept` returns `false`
+    /// instead of handling them.
+    pub(crate) fn poll_intercepted<F>(
+        &mut self,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut task::Context<'_>,
+        mut intercept: F,
+    ) where
+        F: FnMut(&mut A, &mut A::Context, &dyn Any) -> bool,
+    {
         #[cfg(feature = "mailbox_assert")]
         let mut n_polls = 0u16;
 
+        let mut budget = self.budget;
+
         while !ctx.waiting() {
+            // let spawned futures make progress, a busy mailbox would starve them otherwise
+            if budget == 0 {
+                task.waker().wake_by_ref();
+                return;
+            }
+
             match Pin::new(&mut self.msgs).poll_next(task) {
                 Poll::Ready(Some(mut msg)) => {
-                    msg.handle(act, ctx);
+                    budget -= 1;
+                    let start = self.slow_handler.map(|_| Instant::now());
+                    let mut handle = |act: &mut A, ctx: &mut A::Context| {
+                        let pass = match msg.message() {
+                            Some(m) => intercept(act, ctx, m),
+                            None => true,
+                        };
+                        if pass {
+                            msg.handle(act, ctx);
+                        }
+                    };
+                    if act.catch_unwind() {
+                        let res = panic::catch_unwind(AssertUnwindSafe(|| handle(act, ctx)));
+                        if let Err(payload) = res {
+                            if act.on_panic(payload, ctx) == Running::Stop {
+                                ctx.stop();
+                            }
+                        }
+                    } else {
+                        handle(act, ctx);
+                    }
+                    if let (Some(threshold), Some(start)) = (self.slow_handler, start) {
+                        let elapsed = start.elapsed();
+                        if elapsed > threshold {
+                            warn!(
+                                "Slow handler: {} took {:?} to handle {}",
+                                type_name::<A>(),
+                                elapsed,
+                                msg.message_type()
+                            );
+                        }
+                    }
                     #[cfg(feature = "mailbox_assert")]
                     {
                         n_polls += 1;
@@ -0,0 +1,139 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{
+    actor::Actor,
+    address::{Addr, Request, ToEnvelope},
+    context::Context,
+    handler::{Handler, Message},
+};
+
+/// A pool of asynchronous actors of the same type, dispatching each message to the least loaded
+/// one.
+///
+/// Unlike a [`SyncArbiter`](crate::SyncArbiter), which hands messages to its threads in turn, a
+/// pool picks the actor with the fewest messages waiting in its mailbox, see
+/// [`Addr::mailbox_len`]. When handling times vary, this keeps messages from queueing up behind
+/// a slow one while other actors are idle. Ties are broken in turn.
+///
+/// Only queued messages count towards the load, so an actor busy with a message it is handling,
+/// e.g. while it [waits](crate::AsyncContext::wait) on a future, looks idle until messages queue
+/// up behind it.
+///
+/// ```
+/// use actix::prelude::*;
+/// use actix::ActorPool;
+///
+/// #[derive(Message)]
+/// #[rtype(result = "usize")]
+/// struct Resize(usize);
+///
+/// struct Resizer;
+///
+/// impl Actor for Resizer {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Handler<Resize> for Resizer {
+///     type Result = usize;
+///
+///     fn handle(&mut self, msg: Resize, _: &mut Self::Context) -> usize {
+///         msg.0 / 2
+///     }
+/// }
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let pool = ActorPool::new(4, || Resizer);
+/// assert_eq!(pool.send(Resize(1024)).await.unwrap(), 512);
+/// # }
+/// ```
+pub struct ActorPool<A: Actor> {
+    workers: Vec<Addr<A>>,
+    // where the search for the least loaded actor starts, to break ties in turn
+    next: AtomicUsize,
+}
+
+impl<A> ActorPool<A>
+where
+    A: Actor<Context = Context<A>>,
+{
+    /// Starts `size` actors created by `factory` and returns the pool dispatching to them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is zero.
+    pub fn new<F>(size: usize, mut factory: F) -> Self
+    where
+        F: FnMut() -> A,
+    {
+        assert!(size > 0, "an actor pool needs at least one actor");
+        ActorPool::from_addrs((0..size).map(|_| factory().start()).collect())
+    }
+}
+
+impl<A: Actor> ActorPool<A> {
+    /// Creates a pool dispatching to already running actors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `workers` is empty.
+    pub fn from_addrs(workers: Vec<Addr<A>>) -> Self {
+        assert!(
+            !workers.is_empty(),
+            "an actor pool needs at least one actor"
+        );
+        ActorPool {
+            workers,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the addresses of the actors in the pool.
+    pub fn workers(&self) -> &[Addr<A>] {
+        &self.workers
+    }
+
+    /// Returns the address of the actor with the fewest messages in its mailbox.
+    pub fn least_loaded(&self) -> &Addr<A> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len();
+        self.workers[start..]
+            .iter()
+            .chain(&self.workers[..start])
+            .min_by_key(|addr| addr.mailbox_len())
+            .unwrap()
+    }
+
+    /// Sends a message to the least loaded actor, like [`Addr::do_send`].
+    pub fn do_send<M>(&self, msg: M)
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+    {
+        self.least_loaded().do_send(msg)
+    }
+
+    /// Sends a message to the least loaded actor and waits for the response, like
+    /// [`Addr::send`].
+    pub fn send<M>(&self, msg: M) -> Request<A, M>
+    where
+        M: Message + Send + 'static,
+        M::Result: Send,
+        A: Handler<M>,
+        A::Context: ToEnvelope<A, M>,
+    {
+        self.least_loaded().send(msg)
+    }
+}
+
+impl<A: Actor> fmt::Debug for ActorPool<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ActorPool")
+            .field("size", &self.workers.len())
+            .finish()
+    }
+}
@@ -3,6 +3,9 @@
 //! An Actor can register itself as a service. A Service can be defined as an
 //! `ArbiterService`, which is unique per arbiter, or a `SystemService`, which
 //! is unique per system.
+//!
+//! Actors implementing [`Tracked`] can also join a per-system list of the live instances of
+//! their type, see [`SystemRegistry::instances`].
 use std::{
     any::{Any, TypeId},
     cell::RefCell,
@@ -15,8 +18,8 @@ use once_cell::sync::Lazy;
 use parking_lot::Mutex;
 
 use crate::{
-    actor::{Actor, Supervised},
-    address::Addr,
+    actor::{Actor, AsyncContext, Supervised},
+    address::{Addr, WeakAddr},
     context::Context,
     supervisor::Supervisor,
 };
@@ -246,6 +249,8 @@ impl Registry {
 pub struct SystemRegistry {
     system: ArbiterHandle,
     registry: HashMap<TypeId, Box<dyn Any + Send>>,
+    // `Vec<WeakAddr<A>>` of the tracked actors of each type
+    tracked: HashMap<TypeId, Box<dyn Any + Send>>,
 }
 
 static SREG: Lazy<Mutex<HashMap<usize, SystemRegistry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
@@ -292,6 +297,7 @@ impl SystemRegistry {
         Self {
             system,
             registry: HashMap::default(),
+            tracked: HashMap::default(),
         }
     }
 
@@ -340,4 +346,83 @@ impl SystemRegistry {
 
         reg.registry.insert(TypeId::of::<A>(), Box::new(addr));
     }
+
+    /// Returns the addresses of the live [`Tracked`] actors of type `A` in the current system.
+    ///
+    /// Actors that have stopped since they were tracked are left out and forgotten.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no system is running on the current thread.
+    pub fn instances<A: Tracked>() -> Vec<WeakAddr<A>> {
+        let sys = System::current();
+
+        let mut sreg = SREG.lock();
+        match sreg.get_mut(&sys.id()) {
+            Some(reg) => {
+                let tracked = reg.tracked_mut::<A>();
+                tracked.retain(|addr| addr.upgrade().is_some());
+                tracked.clone()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn tracked_mut<A: Tracked>(&mut self) -> &mut Vec<WeakAddr<A>> {
+        self.tracked
+            .entry(TypeId::of::<A>())
+            .or_insert_with(|| Box::new(Vec::<WeakAddr<A>>::new()))
+            .downcast_mut()
+            .unwrap()
+    }
+}
+
+/// Marks actors that can be listed with [`SystemRegistry::instances`].
+///
+/// Tracking is opt-in: an actor joins the list of its type by calling [`Tracked::track`],
+/// usually from [`Actor::started`]. Each call takes a lock shared by all systems and keeps a weak
+/// address per actor until a later call finds it stopped, so it suits actors that are
+/// enumerated for administration rather than ones started in large numbers per second.
+///
+/// ```
+/// use actix::{prelude::*, SystemRegistry, Tracked};
+///
+/// struct Session;
+///
+/// impl Actor for Session {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, ctx: &mut Self::Context) {
+///         Self::track(ctx);
+///     }
+/// }
+///
+/// impl Tracked for Session {}
+///
+/// # #[actix::main]
+/// # async fn main() {
+/// let _sessions = [Session.start(), Session.start()];
+/// # actix_rt::task::yield_now().await;
+/// assert_eq!(SystemRegistry::instances::<Session>().len(), 2);
+/// # }
+/// ```
+pub trait Tracked: Actor<Context = Context<Self>> {
+    /// Adds the actor to the instances of its type in the system it was started in.
+    ///
+    /// Tracking an actor again, e.g. after a [supervised](Supervised) restart, has no effect.
+    fn track(ctx: &mut Context<Self>) {
+        let sys = ctx.system();
+        let addr = ctx.address().downgrade();
+
+        let mut sreg = SREG.lock();
+        let tracked = sreg
+            .entry(sys.id())
+            .or_insert_with(|| SystemRegistry::new(sys.arbiter().clone()))
+            .tracked_mut::<Self>();
+
+        tracked.retain(|addr| addr.upgrade().is_some());
+        if !tracked.contains(&addr) {
+            tracked.push(addr);
+        }
+    }
 }
@@ -1,14 +1,17 @@
 use std::{
     pin::Pin,
     task::{Context, Poll},
+    time::Duration,
 };
 
 use futures_core::{ready, stream::Stream};
 use log::error;
 use pin_project_lite::pin_project;
+use tokio::sync::mpsc;
 
 use crate::{
     actor::{Actor, ActorContext, ActorState, AsyncContext, SpawnHandle},
+    context_impl::AsyncContextParts,
     fut::ActorFuture,
 };
 
@@ -151,3 +154,156 @@ where
         Poll::Ready(())
     }
 }
+
+pin_project! {
+    /// Like [`ActorStream`], but leaves the stream alone while the actor's mailbox holds at least
+    /// `watermark` messages.
+    pub(crate) struct BackpressureStream<S> {
+        #[pin]
+        stream: S,
+        started: bool,
+        watermark: usize,
+    }
+}
+
+impl<S> BackpressureStream<S> {
+    pub fn new(stream: S, watermark: usize) -> Self {
+        Self {
+            stream,
+            started: false,
+            watermark: watermark.max(1),
+        }
+    }
+}
+
+impl<A, S> ActorFuture<A> for BackpressureStream<S>
+where
+    S: Stream,
+    A: Actor + StreamHandler<S::Item>,
+    A::Context: AsyncContextParts<A>,
+{
+    type Output = ();
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if !*this.started {
+            *this.started = true;
+            <A as StreamHandler<S::Item>>::started(act, ctx);
+        }
+
+        let mut polled = 0;
+
+        loop {
+            // the context polls its items again after handling mailbox messages, so there is no
+            // need to register for a wake up here
+            if ctx.parts().mailbox_len() >= *this.watermark {
+                return Poll::Pending;
+            }
+
+            let msg = match ready!(this.stream.as_mut().poll_next(task)) {
+                Some(msg) => msg,
+                None => break,
+            };
+            A::handle(act, msg, ctx);
+
+            polled += 1;
+
+            if ctx.waiting() {
+                return Poll::Pending;
+            } else if polled == 16 {
+                task.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+
+        A::finished(act, ctx);
+        Poll::Ready(())
+    }
+}
+
+pin_project! {
+    /// Like [`ActorStream`], but stops the actor from waiting on futures a stream item started
+    /// once `timeout` has passed.
+    pub(crate) struct ItemTimeoutStream<S> {
+        #[pin]
+        stream: S,
+        started: bool,
+        timeout: Duration,
+    }
+}
+
+impl<S> ItemTimeoutStream<S> {
+    pub fn new(stream: S, timeout: Duration) -> Self {
+        Self {
+            stream,
+            started: false,
+            timeout,
+        }
+    }
+}
+
+impl<A, S> ActorFuture<A> for ItemTimeoutStream<S>
+where
+    S: Stream,
+    A: Actor + StreamHandler<S::Item>,
+    A::Context: AsyncContextParts<A>,
+{
+    type Output = ();
+
+    fn poll(
+        self: Pin<&mut Self>,
+        act: &mut A,
+        ctx: &mut A::Context,
+        task: &mut Context<'_>,
+    ) -> Poll<Self::Output> {
+        let mut this = self.project();
+
+        if !*this.started {
+            *this.started = true;
+            <A as StreamHandler<S::Item>>::started(act, ctx);
+        }
+
+        let mut polled = 0;
+
+        while let Some(msg) = ready!(this.stream.as_mut().poll_next(task)) {
+            A::handle(act, msg, ctx);
+
+            polled += 1;
+
+            if ctx.waiting() {
+                ctx.parts().detach_wait_after(*this.timeout);
+                return Poll::Pending;
+            } else if polled == 16 {
+                task.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+        }
+
+        A::finished(act, ctx);
+        Poll::Ready(())
+    }
+}
+
+/// A [`Stream`] of the messages received by a tokio [`mpsc::Receiver`], ending once all senders
+/// are dropped and the buffered messages are taken.
+pub(crate) struct ReceiverStream<M>(mpsc::Receiver<M>);
+
+impl<M> ReceiverStream<M> {
+    pub fn new(rx: mpsc::Receiver<M>) -> Self {
+        Self(rx)
+    }
+}
+
+impl<M> Stream for ReceiverStream<M> {
+    type Item = M;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<M>> {
+        self.0.poll_recv(cx)
+    }
+}
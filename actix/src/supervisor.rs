@@ -1,15 +1,22 @@
 use std::{
+    any::type_name,
+    cell::RefCell,
     future::Future,
+    mem,
     pin::Pin,
-    task::{self, Poll},
+    rc::Rc,
+    task::{self, Poll, Waker},
+    time::Duration,
 };
 
 use actix_rt::ArbiterHandle;
+use log::warn;
 use pin_project_lite::pin_project;
 
 use crate::{
-    actor::{Actor, AsyncContext, Supervised},
+    actor::{Actor, ActorContext, AsyncContext, Supervised},
     address::{channel, Addr},
+    clock::{sleep, Sleep},
     context::Context,
     context_impl::ContextFut,
     mailbox::DEFAULT_CAPACITY,
@@ -78,6 +85,9 @@ pin_project! {
     {
         #[pin]
         fut: ContextFut<A, Context<A>>,
+        start_timeout: Option<Duration>,
+        #[pin]
+        timer: Option<Sleep>,
     }
 }
 
@@ -119,7 +129,29 @@ where
         let fut = ctx.into_future(act);
 
         // create supervisor
-        actix_rt::spawn(Self { fut });
+        actix_rt::spawn(Self::new(fut, None));
+
+        addr
+    }
+
+    /// Start new supervised actor in current tokio runtime, restarting it if it does not get
+    /// ready within `timeout`.
+    ///
+    /// The actor is ready once [`Actor::started`] has run and the futures it passed to
+    /// [`AsyncContext::wait`] have completed. An actor that is not ready in time is restarted
+    /// like an actor that stopped, except that [`Actor::stopping`] and [`Actor::stopped`] are not
+    /// called. The timeout applies again after every restart.
+    pub fn start_with_timeout<F>(timeout: Duration, f: F) -> Addr<A>
+    where
+        F: FnOnce(&mut A::Context) -> A + 'static,
+        A: Actor<Context = Context<A>>,
+    {
+        let mut ctx = Context::new();
+        let act = f(&mut ctx);
+        let addr = ctx.address();
+        let fut = ctx.into_future(act);
+
+        actix_rt::spawn(Self::new(fut, Some(timeout)));
 
         addr
     }
@@ -137,11 +169,19 @@ where
             let act = f(&mut ctx);
             let fut = ctx.into_future(act);
 
-            actix_rt::spawn(Self { fut });
+            actix_rt::spawn(Self::new(fut, None));
         });
 
         Addr::new(tx)
     }
+
+    fn new(fut: ContextFut<A, Context<A>>, start_timeout: Option<Duration>) -> Self {
+        Self {
+            fut,
+            start_timeout,
+            timer: start_timeout.map(sleep),
+        }
+    }
 }
 
 #[doc(hidden)]
@@ -155,12 +195,261 @@ where
         let mut this = self.project();
         loop {
             match this.fut.as_mut().poll(cx) {
-                Poll::Pending => return Poll::Pending,
+                Poll::Pending => {
+                    let timer = match this.timer.as_mut().as_pin_mut() {
+                        Some(timer) => timer,
+                        None => return Poll::Pending,
+                    };
+
+                    if this.fut.ready() {
+                        this.timer.set(None);
+                        return Poll::Pending;
+                    }
+                    if timer.poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+
+                    warn!(
+                        "Actor {} did not start within {:?}, restarting",
+                        type_name::<A>(),
+                        this.start_timeout.unwrap_or_default()
+                    );
+                    if !this.fut.restart() {
+                        return Poll::Ready(());
+                    }
+                }
+                Poll::Ready(_) => {
+                    // stop if context's address is not connected
+                    if !this.fut.restart() {
+                        return Poll::Ready(());
+                    }
+                }
+            }
+            this.timer.set(this.start_timeout.map(sleep));
+        }
+    }
+}
+
+/// Strategy applied by a [`SupervisorTree`] when one of its children fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisionStrategy {
+    /// Restart only the failed child.
+    OneForOne,
+    /// Restart all children of the tree.
+    OneForAll,
+    /// Restart the failed child and all children started after it.
+    RestForOne,
+}
+
+/// A group of supervised actors restarted together according to a [`SupervisionStrategy`].
+///
+/// Children are started with [`SupervisorTree::start_child`] and are restarted the same way a
+/// [`Supervisor`] restarts its actor. A child fails when it stops while its address is still
+/// connected; the tree then restarts the failed child and, depending on the strategy, some of its
+/// siblings. Siblings are stopped as if [`ActorContext::stop`] was called on them, so a sibling
+/// refusing to stop in [`Actor::stopping`] keeps running. A child stopping after all its
+/// addresses are dropped is removed from the tree.
+///
+/// The tree is usually owned by a parent actor. Dropping the tree does not stop its children.
+///
+/// # Examples
+///
+/// ```
+/// # use actix::prelude::*;
+/// use actix::{SupervisionStrategy, SupervisorTree};
+///
+/// struct Worker;
+///
+/// impl Actor for Worker {
+///     type Context = Context<Self>;
+/// }
+///
+/// impl Supervised for Worker {}
+///
+/// struct Parent {
+///     children: SupervisorTree,
+///     workers: Vec<Addr<Worker>>,
+/// }
+///
+/// impl Actor for Parent {
+///     type Context = Context<Self>;
+///
+///     fn started(&mut self, _: &mut Context<Self>) {
+///         for _ in 0..3 {
+///             self.workers.push(self.children.start_child(|_| Worker));
+///         }
+/// #       System::current().stop();
+///     }
+/// }
+///
+/// # fn main() {
+/// let sys = System::new();
+/// sys.block_on(async {
+///     Parent {
+///         children: SupervisorTree::new(SupervisionStrategy::OneForAll),
+///         workers: Vec::new(),
+///     }
+///     .start();
+/// });
+/// # sys.run().unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SupervisorTree {
+    inner: Rc<RefCell<TreeInner>>,
+}
+
+impl SupervisorTree {
+    /// Creates an empty tree using the given strategy.
+    pub fn new(strategy: SupervisionStrategy) -> Self {
+        SupervisorTree {
+            inner: Rc::new(RefCell::new(TreeInner {
+                strategy,
+                next_id: 0,
+                children: Vec::new(),
+            })),
+        }
+    }
+
+    /// Returns the strategy of this tree.
+    pub fn strategy(&self) -> SupervisionStrategy {
+        self.inner.borrow().strategy
+    }
+
+    /// Starts a new supervised child in the current arbiter and registers it with the tree.
+    pub fn start_child<A, F>(&self, f: F) -> Addr<A>
+    where
+        A: Supervised + Actor<Context = Context<A>>,
+        F: FnOnce(&mut Context<A>) -> A + 'static,
+    {
+        let mut ctx = Context::new();
+        let act = f(&mut ctx);
+        let addr = ctx.address();
+        let fut = ctx.into_future(act);
+
+        let id = self.inner.borrow_mut().register();
+        actix_rt::spawn(SupervisedChild {
+            fut,
+            tree: Rc::clone(&self.inner),
+            id,
+            restarting: false,
+        });
+
+        addr
+    }
+}
+
+#[derive(Debug)]
+struct TreeInner {
+    strategy: SupervisionStrategy,
+    next_id: usize,
+    // children in start order
+    children: Vec<ChildEntry>,
+}
+
+#[derive(Debug)]
+struct ChildEntry {
+    id: usize,
+    restart: bool,
+    waker: Option<Waker>,
+}
+
+impl TreeInner {
+    fn register(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.children.push(ChildEntry {
+            id,
+            restart: false,
+            waker: None,
+        });
+        id
+    }
+
+    fn child_mut(&mut self, id: usize) -> Option<&mut ChildEntry> {
+        self.children.iter_mut().find(|child| child.id == id)
+    }
+
+    fn take_restart(&mut self, id: usize) -> bool {
+        self.child_mut(id)
+            .is_some_and(|child| mem::take(&mut child.restart))
+    }
+
+    fn set_waker(&mut self, id: usize, waker: &Waker) {
+        if let Some(child) = self.child_mut(id) {
+            child.waker = Some(waker.clone());
+        }
+    }
+
+    fn remove(&mut self, id: usize) {
+        self.children.retain(|child| child.id != id);
+    }
+
+    /// Marks siblings of a failed child for restart according to the strategy.
+    fn child_failed(&mut self, id: usize) {
+        let pos = match self.children.iter().position(|child| child.id == id) {
+            Some(pos) => pos,
+            None => return,
+        };
+        let siblings = match self.strategy {
+            SupervisionStrategy::OneForOne => return,
+            SupervisionStrategy::OneForAll => &mut self.children[..],
+            SupervisionStrategy::RestForOne => &mut self.children[pos..],
+        };
+
+        for child in siblings.iter_mut().filter(|child| child.id != id) {
+            child.restart = true;
+            if let Some(waker) = child.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+struct SupervisedChild<A>
+where
+    A: Supervised + Actor<Context = Context<A>>,
+{
+    fut: ContextFut<A, Context<A>>,
+    tree: Rc<RefCell<TreeInner>>,
+    id: usize,
+    // stop was requested by the tree because a sibling failed
+    restarting: bool,
+}
+
+impl<A> Future for SupervisedChild<A>
+where
+    A: Supervised + Actor<Context = Context<A>>,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        loop {
+            // the tree must not be borrowed while the actor runs, it may start new children
+            if this.tree.borrow_mut().take_restart(this.id) {
+                this.restarting = true;
+                this.fut.ctx().stop();
+            }
+
+            match Pin::new(&mut this.fut).poll(cx) {
+                Poll::Pending => {
+                    // actor refused to stop
+                    this.restarting = false;
+                    this.tree.borrow_mut().set_waker(this.id, cx.waker());
+                    return Poll::Pending;
+                }
                 Poll::Ready(_) => {
+                    let restarting = mem::take(&mut this.restarting);
+
                     // stop if context's address is not connected
                     if !this.fut.restart() {
+                        this.tree.borrow_mut().remove(this.id);
                         return Poll::Ready(());
                     }
+                    if !restarting {
+                        this.tree.borrow_mut().child_failed(this.id);
+                    }
                 }
             }
         }
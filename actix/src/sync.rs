@@ -6,20 +6,32 @@
 //! Actor type A and B, sharing the same thread pool. You need to create two
 //! [`SyncArbiter`]s and have A and B spawn on unique `SyncArbiter`s respectively.
 //! For more information and examples, see `SyncArbiter`
-use std::{future::Future, pin::Pin, sync::Arc, task, task::Poll, thread};
+use std::{
+    any::{Any, TypeId},
+    collections::{HashMap, VecDeque},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task,
+    task::Poll,
+    thread,
+};
 
 use actix_rt::System;
 use crossbeam_channel as cb_channel;
 use futures_core::stream::Stream;
 use log::warn;
+use parking_lot::Mutex;
 use tokio::sync::oneshot::Sender as SyncSender;
 
 use crate::{
     actor::{Actor, ActorContext, ActorState, Running},
     address::{
-        channel, Addr, AddressReceiver, AddressSenderProducer, Envelope, EnvelopeProxy, ToEnvelope,
+        channel::{self, AddressSender},
+        Addr, AddressReceiver, AddressSenderProducer, Envelope, EnvelopeProxy, ToEnvelope,
     },
     context::Context,
+    dead_letter,
     handler::{Handler, Message, MessageResponse},
 };
 
@@ -120,8 +132,57 @@ where
     /// used to communicate to the actor(s), and messages are handled by
     /// the next available Actor in the `SyncArbiter`.
     pub fn start_with_thread_builder<F, BF>(
+        threads: usize,
+        thread_builder_factory: BF,
+        factory: F,
+    ) -> Addr<A>
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+        BF: FnMut() -> thread::Builder,
+    {
+        Self::spawn(
+            threads,
+            thread_builder_factory,
+            ConcurrencyLimits::default(),
+            factory,
+        )
+    }
+
+    /// Start a new `SyncArbiter` with specified number of worker threads, limiting how many
+    /// of them handle certain message types at the same time.
+    ///
+    /// A message whose type is at its limit is set aside without occupying a worker, so other
+    /// messages keep being handled by the remaining workers. It is picked up by the next worker
+    /// finishing a message of the same type.
+    ///
+    /// ```
+    /// # use actix::{prelude::*, sync::ConcurrencyLimits};
+    /// # struct Heavy;
+    /// # impl Message for Heavy { type Result = (); }
+    /// # struct Worker;
+    /// # impl Actor for Worker { type Context = SyncContext<Self>; }
+    /// # impl Handler<Heavy> for Worker {
+    /// #     type Result = ();
+    /// #     fn handle(&mut self, _: Heavy, _: &mut Self::Context) {}
+    /// # }
+    /// # System::new().block_on(async {
+    /// // at most 2 of the 8 workers handle `Heavy` messages at a time
+    /// let limits = ConcurrencyLimits::new().limit::<Heavy>(2);
+    /// let addr = SyncArbiter::start_with_limits(8, limits, || Worker);
+    /// # addr.send(Heavy).await.unwrap();
+    /// # });
+    /// ```
+    pub fn start_with_limits<F>(threads: usize, limits: ConcurrencyLimits, factory: F) -> Addr<A>
+    where
+        F: Fn() -> A + Send + Sync + 'static,
+    {
+        Self::spawn(threads, thread::Builder::new, limits, factory)
+    }
+
+    fn spawn<F, BF>(
         threads: usize,
         mut thread_builder_factory: BF,
+        limits: ConcurrencyLimits,
         factory: F,
     ) -> Addr<A>
     where
@@ -129,6 +190,13 @@ where
         BF: FnMut() -> thread::Builder,
     {
         let factory = Arc::new(factory);
+        let gates = Arc::new(
+            limits
+                .limits
+                .into_iter()
+                .map(|(id, max)| (id, Gate::new(max)))
+                .collect::<HashMap<_, _>>(),
+        );
         let (sender, receiver) = cb_channel::unbounded();
         let (tx, rx) = channel::channel(0);
 
@@ -137,11 +205,12 @@ where
             let sys = System::current();
             let actor_queue = receiver.clone();
             let inner_rx = rx.sender_producer();
+            let gates = Arc::clone(&gates);
 
             thread_builder_factory()
                 .spawn(move || {
                     System::set_current(sys);
-                    SyncContext::new(f, actor_queue, inner_rx).run();
+                    SyncContext::new(f, actor_queue, inner_rx, gates).run();
                 })
                 .expect("failed to spawn thread");
         }
@@ -155,6 +224,140 @@ where
     }
 }
 
+/// Limits on how many workers of a [`SyncArbiter`] may handle a message type at the same time.
+///
+/// See [`SyncArbiter::start_with_limits`]. Message types without a limit can be handled by
+/// all workers at once.
+#[derive(Debug, Clone, Default)]
+pub struct ConcurrencyLimits {
+    limits: HashMap<TypeId, usize>,
+}
+
+impl ConcurrencyLimits {
+    /// Creates an empty set of limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lets at most `max` workers handle messages of type `M` at the same time.
+    ///
+    /// A limit of 0 is treated as 1.
+    pub fn limit<M: Message + 'static>(mut self, max: usize) -> Self {
+        self.limits.insert(TypeId::of::<M>(), max.max(1));
+        self
+    }
+}
+
+/// Tracks the workers handling a limited message type and the messages set aside for it.
+struct Gate<A: Actor> {
+    max: usize,
+    state: Mutex<(usize, VecDeque<Envelope<A>>)>,
+}
+
+impl<A: Actor> Gate<A> {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            state: Mutex::new((0, VecDeque::new())),
+        }
+    }
+
+    /// Returns the message the calling worker may handle next, or sets `env` aside if all
+    /// slots are taken.
+    fn admit(&self, env: Envelope<A>) -> Option<Envelope<A>> {
+        let mut state = self.state.lock();
+        state.1.push_back(env);
+        if state.0 < self.max {
+            state.0 += 1;
+            state.1.pop_front()
+        } else {
+            None
+        }
+    }
+
+    /// Hands the next set aside message to a worker that finished one, or frees its slot.
+    fn next(&self) -> Option<Envelope<A>> {
+        let mut state = self.state.lock();
+        let env = state.1.pop_front();
+        if env.is_none() {
+            state.0 -= 1;
+        }
+        env
+    }
+}
+
+/// Holds a slot of a gate while a worker handles its messages.
+///
+/// A panicking handler takes its worker thread down, so the slot is handed to another worker
+/// together with the next set aside message, or freed if there is none.
+struct GateSlot<'a, A>
+where
+    A: Actor<Context = SyncContext<A>>,
+{
+    gate: &'a Gate<A>,
+    id: TypeId,
+    address: AddressSender<A>,
+}
+
+impl<A> Drop for GateSlot<'_, A>
+where
+    A: Actor<Context = SyncContext<A>>,
+{
+    fn drop(&mut self) {
+        if thread::panicking() {
+            if let Some(env) = self.gate.next() {
+                let handoff = GateHandoff {
+                    id: self.id,
+                    env: Some(env),
+                };
+                // if the arbiter has stopped, dropping the message closes its response channel
+                let _ = self
+                    .address
+                    .requeue(Envelope::with_proxy(Box::new(handoff)));
+            }
+        }
+    }
+}
+
+/// A set aside message passed on with the slot of its gate.
+struct GateHandoff<A: Actor> {
+    id: TypeId,
+    env: Option<Envelope<A>>,
+}
+
+impl<A> EnvelopeProxy<A> for GateHandoff<A>
+where
+    A: Actor<Context = SyncContext<A>>,
+{
+    fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        if let Some(env) = self.env.take() {
+            let gates = Arc::clone(&ctx.gates);
+            run_gated(&gates[&self.id], self.id, env, act, ctx);
+        }
+    }
+}
+
+/// Handles `env` and then the messages set aside for its gate, while holding one of its slots.
+fn run_gated<A>(
+    gate: &Gate<A>,
+    id: TypeId,
+    mut env: Envelope<A>,
+    act: &mut A,
+    ctx: &mut SyncContext<A>,
+) where
+    A: Actor<Context = SyncContext<A>>,
+{
+    let _slot = GateSlot {
+        gate,
+        id,
+        address: ctx.address.sender(),
+    };
+    env.handle(act, ctx);
+    while let Some(mut env) = gate.next() {
+        env.handle(act, ctx);
+    }
+}
+
 impl<A> Actor for SyncArbiter<A>
 where
     A: Actor<Context = SyncContext<A>>,
@@ -203,6 +406,10 @@ where
     fn pack(msg: M, tx: Option<SyncSender<M::Result>>) -> Envelope<A> {
         Envelope::with_proxy(Box::new(SyncContextEnvelope::new(msg, tx)))
     }
+
+    fn dead_letter(msg: M) {
+        dead_letter::deliver(msg);
+    }
 }
 
 /// Sync actor execution context. This is used instead of impl Actor for your Actor
@@ -244,6 +451,7 @@ where
     state: ActorState,
     factory: Arc<dyn Fn() -> A>,
     address: AddressSenderProducer<A>,
+    gates: Arc<HashMap<TypeId, Gate<A>>>,
 }
 
 impl<A> SyncContext<A>
@@ -254,6 +462,7 @@ where
         factory: Arc<dyn Fn() -> A>,
         queue: cb_channel::Receiver<Envelope<A>>,
         address: AddressSenderProducer<A>,
+        gates: Arc<HashMap<TypeId, Gate<A>>>,
     ) -> Self {
         let act = factory();
         Self {
@@ -263,6 +472,7 @@ where
             stopping: false,
             state: ActorState::Started,
             address,
+            gates,
         }
     }
 
@@ -341,6 +551,8 @@ where
 {
     msg: Option<M>,
     tx: Option<SyncSender<M::Result>>,
+    // a slot of the message type's gate is already held for this message
+    admitted: bool,
 }
 
 impl<M> SyncContextEnvelope<M>
@@ -349,7 +561,16 @@ where
     M::Result: Send,
 {
     pub fn new(msg: M, tx: Option<SyncSender<M::Result>>) -> Self {
-        Self { tx, msg: Some(msg) }
+        Self {
+            tx,
+            msg: Some(msg),
+            admitted: false,
+        }
+    }
+
+    /// Takes the message out if it has not been handled yet.
+    pub(crate) fn take_message(&mut self) -> Option<M> {
+        self.msg.take()
     }
 }
 
@@ -360,6 +581,22 @@ where
     A: Actor<Context = SyncContext<A>> + Handler<M>,
 {
     fn handle(&mut self, act: &mut A, ctx: &mut A::Context) {
+        if !self.admitted {
+            let gates = Arc::clone(&ctx.gates);
+            if let Some(gate) = gates.get(&TypeId::of::<M>()) {
+                let env = Envelope::with_proxy(Box::new(SyncContextEnvelope {
+                    msg: self.msg.take(),
+                    tx: self.tx.take(),
+                    admitted: true,
+                }));
+
+                if let Some(env) = gate.admit(env) {
+                    run_gated(gate, TypeId::of::<M>(), env, act, ctx);
+                }
+                return;
+            }
+        }
+
         let tx = self.tx.take();
         if tx.is_some() && tx.as_ref().unwrap().is_closed() {
             return;
@@ -369,6 +606,10 @@ where
             <A as Handler<M>>::handle(act, msg, ctx).handle(ctx, tx)
         }
     }
+
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
 }
 
 #[cfg(test)]
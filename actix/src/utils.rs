@@ -9,7 +9,7 @@ use futures_core::ready;
 use pin_project_lite::pin_project;
 use tokio::{
     sync::oneshot,
-    time::{sleep_until, Instant},
+    time::{sleep_until, Instant, MissedTickBehavior},
 };
 
 use crate::{
@@ -179,6 +179,7 @@ pin_project! {
     pub struct IntervalFunc<A: Actor> {
         f: Box<dyn FnMut(&mut A, &mut A::Context)>,
         interval: Duration,
+        missed_tick_behavior: MissedTickBehavior,
         #[pin]
         timer: Sleep,
     }
@@ -193,6 +194,7 @@ impl<A: Actor> IntervalFunc<A> {
         Self {
             f: Box::new(task),
             interval,
+            missed_tick_behavior: MissedTickBehavior::Burst,
             timer: sleep(interval),
         }
     }
@@ -206,9 +208,38 @@ impl<A: Actor> IntervalFunc<A> {
         Self {
             f: Box::new(task),
             interval,
+            missed_tick_behavior: MissedTickBehavior::Burst,
             timer: sleep_until(start),
         }
     }
+
+    /// Sets how ticks missed because the actor was busy are handled.
+    ///
+    /// Defaults to [`MissedTickBehavior::Burst`], which runs the missed ticks back to back to
+    /// catch up with the schedule. See [`MissedTickBehavior`] for the alternatives.
+    pub fn missed_tick_behavior(mut self, behavior: MissedTickBehavior) -> Self {
+        self.missed_tick_behavior = behavior;
+        self
+    }
+}
+
+/// Returns the deadline of the tick following the one due at `deadline`.
+fn next_tick(deadline: Instant, interval: Duration, behavior: MissedTickBehavior) -> Instant {
+    let now = Instant::now();
+
+    // same tolerance as `tokio::time::Interval` before treating a tick as missed
+    if now <= deadline + Duration::from_millis(5) {
+        return deadline + interval;
+    }
+
+    match behavior {
+        MissedTickBehavior::Burst => deadline + interval,
+        MissedTickBehavior::Delay => now + interval,
+        MissedTickBehavior::Skip => {
+            let behind = (now - deadline).as_nanos() % interval.as_nanos().max(1);
+            now + interval - Duration::from_nanos(behind as u64)
+        }
+    }
 }
 
 impl<A: Actor> ActorStream<A> for IntervalFunc<A> {
@@ -223,8 +254,9 @@ impl<A: Actor> ActorStream<A> for IntervalFunc<A> {
         let mut this = self.project();
         loop {
             ready!(this.timer.as_mut().poll(task));
-            let now = this.timer.deadline();
-            this.timer.as_mut().reset(now + *this.interval);
+            let deadline = this.timer.deadline();
+            let next = next_tick(deadline, *this.interval, *this.missed_tick_behavior);
+            this.timer.as_mut().reset(next);
             (this.f)(act, ctx);
         }
     }
@@ -319,3 +319,223 @@ fn test_run_interval_at() {
     // we wait the initial 1s delay and then 10 intervals of ~100ms
     assert_eq!(result.elapsed().as_secs(), 2);
 }
+
+struct Named;
+
+impl Actor for Named {
+    type Context = Context<Self>;
+}
+
+#[derive(Message)]
+#[rtype(result = "String")]
+struct GetName;
+
+impl Handler<GetName> for Named {
+    type Result = String;
+
+    fn handle(&mut self, _: GetName, ctx: &mut Self::Context) -> String {
+        ctx.name().to_owned()
+    }
+}
+
+#[actix::test]
+async fn test_actor_name() {
+    let named = Named.start_named("session-42");
+    assert_eq!(named.send(GetName).await.unwrap(), "session-42");
+
+    let unnamed = Named.start();
+    assert_eq!(
+        unnamed.send(GetName).await.unwrap(),
+        std::any::type_name::<Named>()
+    );
+}
+
+#[derive(Default)]
+struct Resilient {
+    panics: usize,
+}
+
+impl Actor for Resilient {
+    type Context = Context<Self>;
+
+    fn catch_unwind(&self) -> bool {
+        true
+    }
+
+    fn on_panic(
+        &mut self,
+        payload: Box<dyn std::any::Any + Send>,
+        _: &mut Self::Context,
+    ) -> Running {
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"boom"));
+        self.panics += 1;
+        Running::Continue
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Boom;
+
+#[derive(Message)]
+#[rtype(result = "usize")]
+struct Panics;
+
+impl Handler<Boom> for Resilient {
+    type Result = ();
+
+    fn handle(&mut self, _: Boom, _: &mut Self::Context) {
+        panic!("boom");
+    }
+}
+
+impl Handler<Panics> for Resilient {
+    type Result = usize;
+
+    fn handle(&mut self, _: Panics, _: &mut Self::Context) -> usize {
+        self.panics
+    }
+}
+
+#[actix::test]
+async fn test_actor_survives_handler_panic() {
+    let addr = Resilient::default().start();
+
+    assert_eq!(addr.send(Boom).await, Err(MailboxError::Closed));
+    addr.do_send(Boom);
+
+    assert_eq!(addr.send(Panics).await.unwrap(), 2);
+    assert!(addr.connected());
+}
+
+struct Flusher {
+    flushed: Arc<AtomicBool>,
+    events: Arc<AtomicUsize>,
+    done: Option<mpsc::Sender<bool>>,
+}
+
+impl Actor for Flusher {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+
+    fn finalize(&mut self, _: &mut Self::Context) -> Option<ResponseActFuture<Self, ()>> {
+        self.events.fetch_add(1, Ordering::SeqCst);
+        let flushed = Arc::clone(&self.flushed);
+        Some(Box::pin(fut::wrap_future(async move {
+            sleep(Duration::from_millis(20)).await;
+            flushed.store(true, Ordering::SeqCst);
+        })))
+    }
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        self.events.fetch_add(1, Ordering::SeqCst);
+        let flushed = self.flushed.load(Ordering::SeqCst);
+        self.done.take().unwrap().send(flushed).unwrap();
+    }
+}
+
+#[actix::test]
+async fn test_actor_finalize() {
+    let (tx, rx) = mpsc::channel();
+    let flushed = Arc::new(AtomicBool::new(false));
+    let events = Arc::new(AtomicUsize::new(0));
+
+    Flusher {
+        flushed: Arc::clone(&flushed),
+        events: Arc::clone(&events),
+        done: Some(tx),
+    }
+    .start();
+
+    sleep(Duration::from_millis(100)).await;
+
+    assert!(rx.try_recv().unwrap());
+    assert_eq!(events.load(Ordering::SeqCst), 2);
+}
+
+struct SlowInit {
+    ready: Arc<AtomicBool>,
+}
+
+impl Actor for SlowInit {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        sleep(Duration::from_millis(20))
+            .into_actor(self)
+            .map(|_, act, _| act.ready.store(true, Ordering::SeqCst))
+            .wait(ctx);
+    }
+}
+
+struct IsReady;
+
+impl Message for IsReady {
+    type Result = bool;
+}
+
+impl Handler<IsReady> for SlowInit {
+    type Result = bool;
+
+    fn handle(&mut self, _: IsReady, _: &mut Self::Context) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
+}
+
+#[actix::test]
+async fn test_start_ready() {
+    let ready = Arc::new(AtomicBool::new(false));
+
+    let addr = SlowInit {
+        ready: Arc::clone(&ready),
+    }
+    .start_ready()
+    .await;
+
+    assert!(ready.load(Ordering::SeqCst));
+    assert!(addr.send(IsReady).await.unwrap());
+}
+
+struct Tally(Arc<AtomicUsize>);
+
+impl Actor for Tally {
+    type Context = Context<Self>;
+}
+
+impl Handler<Num> for Tally {
+    type Result = ();
+
+    fn handle(&mut self, msg: Num, _: &mut Self::Context) {
+        self.0.fetch_add(msg.0, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn test_into_future_polled_manually() {
+    use std::{future::Future, pin::Pin, task::Context as TaskContext};
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let (addr, mut fut) = Tally::into_future(|_| Tally(Arc::clone(&count)));
+
+    let waker = futures_util::task::noop_waker();
+    let mut cx = TaskContext::from_waker(&waker);
+
+    // nothing is handled until the future is polled
+    addr.do_send(Num(1));
+    addr.do_send(Num(2));
+    assert_eq!(count.load(Ordering::SeqCst), 0);
+
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    assert_eq!(count.load(Ordering::SeqCst), 3);
+
+    addr.do_send(Num(4));
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    assert_eq!(count.load(Ordering::SeqCst), 7);
+
+    // the actor stops once its last address is gone
+    drop(addr);
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_ready());
+}
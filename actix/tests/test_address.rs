@@ -452,6 +452,8 @@ fn test_address_eq() {
 }
 
 #[test]
+// `Addr` hashes only the pointer of its channel, the counters behind it do not affect the key
+#[allow(clippy::mutable_key_type)]
 fn test_address_hash() {
     let count0 = Arc::new(AtomicUsize::new(0));
     let count1 = Arc::clone(&count0);
@@ -542,3 +544,1189 @@ fn test_recipient_hash() {
         System::current().stop();
     });
 }
+
+#[test]
+fn test_recipients_macro() {
+    System::new().block_on(async {
+        let addr = PingCounterActor::default().start();
+        let (ping, count) = actix::recipients!(addr => [Ping, CountPings]);
+
+        ping.do_send(Ping);
+        ping.send(Ping).await.unwrap();
+        assert_eq!(count.send(CountPings).await.unwrap(), 2);
+    });
+}
+
+struct Block(Duration);
+
+impl Message for Block {
+    type Result = ();
+}
+
+impl actix::Handler<Block> for PingCounterActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Block, _: &mut Self::Context) {
+        std::thread::sleep(msg.0);
+    }
+}
+
+impl Handler<actix::dead_letter::DeadLetter<Ping>> for PingCounterActor {
+    type Result = ();
+
+    fn handle(&mut self, _: actix::dead_letter::DeadLetter<Ping>, _: &mut Self::Context) {
+        self.ping_count.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_send_with_ttl() {
+    System::new().block_on(async {
+        let addr = PingCounterActor::default().start();
+        let dead_letters = PingCounterActor::default().start();
+        actix::dead_letter::DeadLetters::set_handler_for::<Ping>(dead_letters.clone().recipient());
+
+        addr.do_send(Block(Duration::from_millis(30)));
+        let expired = addr.send_with_ttl(Ping, Duration::from_millis(5));
+        let fresh = addr.send_with_ttl(Ping, Duration::from_secs(10));
+
+        assert_eq!(expired.await, Err(MailboxError::Timeout));
+        assert_eq!(fresh.await, Ok(()));
+        assert_eq!(addr.send(CountPings).await.unwrap(), 1);
+
+        // the expired message was passed to the dead-letter handler
+        assert_eq!(dead_letters.send(CountPings).await.unwrap(), 1);
+    });
+}
+
+#[test]
+fn test_do_send_bounded() {
+    System::new().block_on(async {
+        let addr = PingCounterActor::create(|ctx| {
+            ctx.set_mailbox_capacity(1);
+            PingCounterActor::default()
+        });
+        let overflowed = Arc::new(AtomicUsize::new(0));
+
+        // the actor has not run yet, so the first message fills the mailbox
+        for _ in 0..3 {
+            let overflowed = overflowed.clone();
+            addr.do_send_bounded(Ping, move |_| {
+                overflowed.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        assert_eq!(overflowed.load(Ordering::SeqCst), 2);
+        assert_eq!(addr.send(CountPings).await.unwrap(), 1);
+
+        // once the mailbox is drained, messages are accepted again
+        addr.do_send_bounded(Ping, |_| panic!("mailbox should have capacity"));
+        assert_eq!(addr.send(CountPings).await.unwrap(), 2);
+    });
+}
+
+#[test]
+fn test_do_send_checked() {
+    System::new().block_on(async {
+        let addr = PingCounterActor::create(|ctx| {
+            ctx.set_mailbox_capacity(1);
+            PingCounterActor::default()
+        });
+
+        // a full mailbox still accepts the messages
+        for _ in 0..3 {
+            assert!(addr.do_send_checked(Ping).is_ok());
+        }
+        assert_eq!(addr.send(CountPings).await.unwrap(), 3);
+
+        let stopped = PingCounterActor::create(|ctx| {
+            ctx.stop();
+            PingCounterActor::default()
+        });
+        sleep(Duration::from_millis(10)).await;
+
+        match stopped.do_send_checked(Ping) {
+            Err(SendError::Closed(Ping)) => {}
+            _ => panic!("message should be handed back"),
+        }
+    });
+}
+
+mod reply {
+    use actix::dead_letter::{DeadLetter, DeadLetters};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    struct Double(u32);
+
+    impl Message for Double {
+        type Result = Doubled;
+    }
+
+    struct Doubled(u32);
+
+    impl Message for Doubled {
+        type Result = ();
+    }
+
+    struct Doubler;
+
+    impl Actor for Doubler {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Double> for Doubler {
+        type Result = MessageResult<Double>;
+
+        fn handle(&mut self, msg: Double, _: &mut Self::Context) -> Self::Result {
+            MessageResult(Doubled(msg.0 * 2))
+        }
+    }
+
+    struct Collector(mpsc::UnboundedSender<u32>);
+
+    impl Actor for Collector {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Doubled> for Collector {
+        type Result = ();
+
+        fn handle(&mut self, msg: Doubled, _: &mut Self::Context) {
+            let _ = self.0.send(msg.0);
+        }
+    }
+
+    impl Handler<DeadLetter<Doubled>> for Collector {
+        type Result = ();
+
+        fn handle(&mut self, msg: DeadLetter<Doubled>, _: &mut Self::Context) {
+            let _ = self.0.send((msg.0).0);
+        }
+    }
+
+    /// Doubles after a delay, stopping the doubler right away.
+    struct DoubleLater(u32);
+
+    impl Message for DoubleLater {
+        type Result = Doubled;
+    }
+
+    impl Handler<DoubleLater> for Doubler {
+        type Result = ResponseFuture<Doubled>;
+
+        fn handle(&mut self, msg: DoubleLater, ctx: &mut Self::Context) -> Self::Result {
+            ctx.stop();
+            Box::pin(async move {
+                sleep(Duration::from_millis(10)).await;
+                Doubled(msg.0 * 2)
+            })
+        }
+    }
+
+    #[test]
+    fn result_is_sent_to_reply_recipient() {
+        System::new().block_on(async {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            let collector = Collector(tx).start().recipient();
+            let doubler = Doubler.start();
+
+            doubler.do_send_with_reply(Double(1), collector.clone());
+            doubler.do_send_with_reply(Double(2), collector);
+
+            assert_eq!(rx.recv().await, Some(2));
+            assert_eq!(rx.recv().await, Some(4));
+        });
+    }
+
+    #[test]
+    fn result_for_stopped_recipient_is_dead_letter() {
+        System::new().block_on(async {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            let collector = Collector(tx).start();
+            DeadLetters::set_handler_for::<Doubled>(collector.clone().recipient());
+
+            // the context is never run, so its mailbox closes once it is dropped
+            let ctx = Context::<Collector>::new();
+            let reply = ctx.address().recipient();
+            drop(ctx);
+            assert!(!reply.connected());
+
+            let doubler = Doubler.start();
+            doubler.do_send_with_reply(Double(3), reply);
+            assert_eq!(rx.recv().await, Some(6));
+        });
+    }
+
+    #[test]
+    fn result_arrives_after_handling_actor_stops() {
+        System::new().block_on(async {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            let collector = Collector(tx).start().recipient();
+            let doubler = Doubler.start();
+
+            doubler.do_send_with_reply(DoubleLater(5), collector);
+            assert_eq!(rx.recv().await, Some(10));
+            assert!(!doubler.connected());
+        });
+    }
+}
+
+mod cancel {
+    use super::*;
+
+    struct Work(u32);
+
+    impl Message for Work {
+        type Result = u32;
+    }
+
+    #[derive(Default)]
+    struct Worker {
+        started: Arc<AtomicUsize>,
+        aborted: Arc<AtomicUsize>,
+    }
+
+    impl Actor for Worker {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Work> for Worker {
+        type Result = ResponseActFuture<Self, u32>;
+
+        fn handle(&mut self, msg: Work, ctx: &mut Self::Context) -> Self::Result {
+            self.started.fetch_add(1, Ordering::SeqCst);
+            let token = ctx.cancel_token().unwrap();
+            let check = token.clone();
+
+            Box::pin(
+                async move {
+                    for _ in 0..msg.0 {
+                        if check.is_cancelled() {
+                            break;
+                        }
+                        sleep(Duration::from_millis(1)).await;
+                    }
+                    msg.0
+                }
+                .into_actor(self)
+                .map(move |res, act, ctx| {
+                    if ctx.is_cancelled(&token) {
+                        act.aborted.fetch_add(1, Ordering::SeqCst);
+                    }
+                    res
+                }),
+            )
+        }
+    }
+
+    #[test]
+    fn uncancelled_request_completes() {
+        System::new().block_on(async {
+            let addr = Worker::default().start();
+            let (req, _token) = addr.send_cancellable(Work(2));
+            assert_eq!(req.await, Ok(2));
+        });
+    }
+
+    #[test]
+    fn cancel_sleeping_handler() {
+        System::new().block_on(async {
+            let worker = Worker::default();
+            let started = worker.started.clone();
+            let aborted = worker.aborted.clone();
+            let addr = worker.start();
+
+            let (req, token) = addr.send_cancellable(Work(10_000));
+            let canceller = async move {
+                while started.load(Ordering::SeqCst) == 0 {
+                    sleep(Duration::from_millis(1)).await;
+                }
+                token.cancel();
+            };
+            let (res, _) = futures_util::future::join(req, canceller).await;
+            assert_eq!(res, Err(MailboxError::Closed));
+
+            while aborted.load(Ordering::SeqCst) == 0 {
+                sleep(Duration::from_millis(1)).await;
+            }
+        });
+    }
+
+    #[test]
+    fn cancelled_queued_message_is_not_handled() {
+        System::new().block_on(async {
+            let worker = Worker::default();
+            let started = worker.started.clone();
+            let addr = worker.start();
+
+            let (req, token) = addr.send_cancellable(Work(1));
+            token.cancel();
+            assert_eq!(req.await, Err(MailboxError::Closed));
+
+            assert_eq!(addr.send_cancellable(Work(1)).0.await, Ok(1));
+            assert_eq!(started.load(Ordering::SeqCst), 1);
+        });
+    }
+}
+
+mod recipient_set {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Event(u32);
+
+    impl Message for Event {
+        type Result = ();
+    }
+
+    struct Listener(mpsc::UnboundedSender<u32>);
+
+    impl Actor for Listener {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Event> for Listener {
+        type Result = ();
+
+        fn handle(&mut self, msg: Event, _: &mut Self::Context) {
+            let _ = self.0.send(msg.0);
+        }
+    }
+
+    fn dead_recipient() -> Recipient<Event> {
+        // the context is never run, so its mailbox closes once it is dropped
+        let ctx = Context::<Listener>::new();
+        ctx.address().recipient()
+    }
+
+    #[test]
+    fn explicit_prune() {
+        System::new().block_on(async {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            let live = Listener(tx).start().recipient();
+
+            let mut set = RecipientSet::new();
+            assert!(set.insert(live.clone()));
+            assert!(!set.insert(live));
+            set.insert(dead_recipient());
+            set.insert(dead_recipient());
+            assert_eq!(set.len(), 3);
+
+            assert_eq!(set.broadcast(Event(1)), 0);
+            assert_eq!(set.len(), 3);
+            assert_eq!(rx.recv().await, Some(1));
+
+            assert_eq!(set.prune(), 2);
+            assert_eq!(set.prune(), 0);
+            assert_eq!(set.len(), 1);
+        });
+    }
+
+    #[test]
+    fn prune_on_send() {
+        System::new().block_on(async {
+            let (tx, mut rx) = mpsc::unbounded_channel();
+
+            let mut set = RecipientSet::new();
+            set.set_prune_on_send(true);
+            set.insert(Listener(tx.clone()).start().recipient());
+            set.insert(Listener(tx).start().recipient());
+            set.insert(dead_recipient());
+
+            assert_eq!(set.broadcast(Event(7)), 1);
+            assert_eq!(set.len(), 2);
+            assert_eq!(rx.recv().await, Some(7));
+            assert_eq!(rx.recv().await, Some(7));
+
+            assert_eq!(set.broadcast(Event(8)), 0);
+        });
+    }
+}
+
+mod ordering {
+    use std::{collections::HashMap, thread};
+
+    use super::*;
+
+    const SENDERS: usize = 16;
+    const MESSAGES: usize = 2_000;
+
+    struct Seq {
+        sender: usize,
+        seq: usize,
+    }
+
+    impl Message for Seq {
+        type Result = ();
+    }
+
+    struct Done;
+
+    impl Message for Done {
+        type Result = usize;
+    }
+
+    #[derive(Default)]
+    struct Checker {
+        last: HashMap<usize, usize>,
+        received: usize,
+    }
+
+    impl Actor for Checker {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Seq> for Checker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Seq, _: &mut Self::Context) {
+            if let Some(last) = self.last.insert(msg.sender, msg.seq) {
+                assert!(
+                    last < msg.seq,
+                    "sender {} delivered {} after {}",
+                    msg.sender,
+                    msg.seq,
+                    last
+                );
+            }
+            self.received += 1;
+        }
+    }
+
+    impl Handler<Done> for Checker {
+        type Result = usize;
+
+        fn handle(&mut self, _: Done, _: &mut Self::Context) -> usize {
+            self.received
+        }
+    }
+
+    #[test]
+    fn per_sender_fifo_under_concurrent_senders() {
+        System::new().block_on(async {
+            let addr = Checker::default().start();
+
+            let threads: Vec<_> = (0..SENDERS)
+                .map(|sender| {
+                    let addr = addr.clone();
+                    thread::spawn(move || {
+                        for seq in 0..MESSAGES {
+                            addr.do_send(Seq { sender, seq });
+                            if seq % 64 == 0 {
+                                thread::yield_now();
+                            }
+                        }
+                    })
+                })
+                .collect();
+
+            // interleave with senders on the actor's own arbiter
+            for sender in SENDERS..SENDERS * 2 {
+                let addr = addr.clone();
+                actix_rt::spawn(async move {
+                    for seq in 0..MESSAGES {
+                        addr.send(Seq { sender, seq }).await.unwrap();
+                    }
+                });
+            }
+
+            for thread in threads {
+                thread.join().unwrap();
+            }
+
+            let total = SENDERS * 2 * MESSAGES;
+            while addr.send(Done).await.unwrap() < total {
+                sleep(Duration::from_millis(1)).await;
+            }
+            assert_eq!(addr.send(Done).await.unwrap(), total);
+        });
+    }
+}
+
+mod pipe_to_sink {
+    use std::{
+        pin::Pin,
+        sync::Mutex,
+        task::{Context as StdContext, Poll},
+    };
+
+    use actix::PipeError;
+    use futures_util::stream;
+
+    use super::*;
+
+    struct Square(usize);
+
+    impl Message for Square {
+        type Result = usize;
+    }
+
+    struct Squarer;
+
+    impl Actor for Squarer {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Square> for Squarer {
+        type Result = usize;
+
+        fn handle(&mut self, msg: Square, ctx: &mut Self::Context) -> usize {
+            if msg.0 == 0 {
+                ctx.stop();
+            }
+            msg.0 * msg.0
+        }
+    }
+
+    /// Sink collecting its items that fails once it holds `capacity` items.
+    #[derive(Clone, Default)]
+    struct Collect {
+        items: Arc<Mutex<Vec<usize>>>,
+        capacity: Option<usize>,
+        closed: Arc<AtomicUsize>,
+    }
+
+    impl futures_sink::Sink<usize> for Collect {
+        type Error = &'static str;
+
+        fn poll_ready(
+            self: Pin<&mut Self>,
+            _: &mut StdContext<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            match self.capacity {
+                Some(cap) if self.items.lock().unwrap().len() >= cap => Poll::Ready(Err("full")),
+                _ => Poll::Ready(Ok(())),
+            }
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: usize) -> Result<(), Self::Error> {
+            self.items.lock().unwrap().push(item);
+            Ok(())
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _: &mut StdContext<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            _: &mut StdContext<'_>,
+        ) -> Poll<Result<(), Self::Error>> {
+            self.closed.fetch_add(1, Ordering::SeqCst);
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn squares(nums: Vec<usize>) -> impl futures_core::Stream<Item = Square> {
+        stream::iter(nums.into_iter().map(Square))
+    }
+
+    #[test]
+    fn forwards_responses_in_order() {
+        System::new().block_on(async {
+            let addr = Squarer.start();
+            let sink = Collect::default();
+
+            let res = addr
+                .pipe_to_sink(squares(vec![1, 2, 3]), sink.clone())
+                .await;
+
+            assert_eq!(res, Ok(()));
+            assert_eq!(*sink.items.lock().unwrap(), [1, 4, 9]);
+            assert_eq!(sink.closed.load(Ordering::SeqCst), 1);
+        });
+    }
+
+    #[test]
+    fn sink_error_stops_pipe_and_keeps_actor() {
+        System::new().block_on(async {
+            let addr = Squarer.start();
+            let sink = Collect {
+                capacity: Some(2),
+                ..Collect::default()
+            };
+
+            let res = addr
+                .pipe_to_sink(squares(vec![1, 2, 3, 4]), sink.clone())
+                .await;
+
+            assert_eq!(res, Err(PipeError::Sink("full")));
+            assert_eq!(*sink.items.lock().unwrap(), [1, 4]);
+            assert_eq!(sink.closed.load(Ordering::SeqCst), 0);
+            assert_eq!(addr.send(Square(5)).await.unwrap(), 25);
+        });
+    }
+
+    #[test]
+    fn actor_stop_stops_pipe() {
+        System::new().block_on(async {
+            let addr = Squarer.start();
+            let sink = Collect::default();
+
+            // `0` makes the actor stop after responding
+            let res = addr
+                .pipe_to_sink(squares(vec![2, 0, 3]), sink.clone())
+                .await;
+
+            assert_eq!(res, Err(PipeError::Mailbox(MailboxError::Closed)));
+            assert_eq!(*sink.items.lock().unwrap(), [4, 0]);
+            assert_eq!(sink.closed.load(Ordering::SeqCst), 0);
+        });
+    }
+}
+
+mod mailbox_error {
+    use std::error::Error;
+
+    use super::*;
+
+    struct Stall;
+
+    impl Message for Stall {
+        type Result = ();
+    }
+
+    struct Staller;
+
+    impl Actor for Staller {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Stall> for Staller {
+        type Result = ResponseFuture<()>;
+
+        fn handle(&mut self, _: Stall, _: &mut Self::Context) -> Self::Result {
+            Box::pin(sleep(Duration::from_secs(10)))
+        }
+    }
+
+    fn assert_send_sync_static<E: Send + Sync + 'static>(_: &E) {}
+
+    #[test]
+    fn display() {
+        assert_eq!(MailboxError::Closed.to_string(), "Mailbox has closed");
+        assert_eq!(
+            MailboxError::Timeout.to_string(),
+            "Message delivery timed out"
+        );
+        assert_eq!(
+            format!("{:?}", MailboxError::Closed),
+            "MailboxError(Mailbox has closed)"
+        );
+    }
+
+    #[test]
+    fn downcast_from_boxed_error() {
+        System::new().block_on(async {
+            let addr = Staller.start();
+
+            let err = addr
+                .send(Stall)
+                .timeout(Duration::from_millis(10))
+                .await
+                .unwrap_err();
+            assert_send_sync_static(&err);
+
+            let boxed: Box<dyn Error + Send + Sync> = Box::new(err);
+            assert!(boxed.source().is_none());
+            assert_eq!(
+                boxed.downcast_ref::<MailboxError>(),
+                Some(&MailboxError::Timeout)
+            );
+        });
+    }
+}
+
+mod foreign_thread {
+    use std::{sync::mpsc, thread};
+
+    use super::*;
+
+    struct Signal(usize);
+
+    impl Message for Signal {
+        type Result = ();
+    }
+
+    struct Listener(mpsc::Sender<usize>);
+
+    impl Actor for Listener {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Signal> for Listener {
+        type Result = ();
+
+        fn handle(&mut self, msg: Signal, _: &mut Self::Context) {
+            self.0.send(msg.0).unwrap();
+        }
+    }
+
+    #[test]
+    fn do_send_without_runtime() {
+        let (addr_tx, addr_rx) = mpsc::channel();
+        let (handled_tx, handled_rx) = mpsc::channel();
+
+        let system = thread::spawn(move || {
+            let sys = System::new();
+            sys.block_on(async move {
+                let addr = Listener(handled_tx).start();
+                addr_tx.send((addr, System::current())).unwrap();
+            });
+            sys.run().unwrap();
+        });
+        let (addr, sys) = addr_rx.recv().unwrap();
+
+        thread::spawn(move || {
+            assert!(tokio::runtime::Handle::try_current().is_err());
+            assert!(System::try_current().is_none());
+
+            for i in 0..3 {
+                addr.do_send(Signal(i));
+            }
+        })
+        .join()
+        .unwrap();
+
+        for i in 0..3 {
+            assert_eq!(handled_rx.recv_timeout(Duration::from_secs(5)), Ok(i));
+        }
+
+        sys.stop();
+        system.join().unwrap();
+    }
+}
+
+mod and_send_to {
+    use super::*;
+
+    struct Parse(&'static str);
+
+    impl Message for Parse {
+        type Result = Double;
+    }
+
+    struct Double(usize);
+
+    impl Message for Double {
+        type Result = usize;
+    }
+
+    struct Parser;
+
+    impl Actor for Parser {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Parse> for Parser {
+        type Result = MessageResult<Parse>;
+
+        fn handle(&mut self, msg: Parse, _: &mut Self::Context) -> Self::Result {
+            MessageResult(Double(msg.0.parse().unwrap()))
+        }
+    }
+
+    struct Doubler;
+
+    impl Actor for Doubler {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Double> for Doubler {
+        type Result = usize;
+
+        fn handle(&mut self, msg: Double, _: &mut Self::Context) -> usize {
+            msg.0 * 2
+        }
+    }
+
+    #[test]
+    fn two_stage_pipeline() {
+        System::new().block_on(async {
+            let parser = Parser.start();
+            let doubler = Doubler.start();
+
+            let res = parser.send(Parse("21")).and_send_to(doubler.clone()).await;
+            assert_eq!(res, Ok(42));
+
+            let res = parser
+                .recipient()
+                .send(Parse("4"))
+                .and_send_to(doubler)
+                .await;
+            assert_eq!(res, Ok(8));
+        });
+    }
+
+    #[test]
+    fn stopped_stage_fails() {
+        System::new().block_on(async {
+            let parser = Parser.start();
+            let doubler = Doubler.start();
+
+            let stopped = Doubler::create(|ctx| {
+                ctx.stop();
+                Doubler
+            });
+            sleep(Duration::from_millis(10)).await;
+            let res = parser.send(Parse("1")).and_send_to(stopped).await;
+            assert_eq!(res, Err(MailboxError::Closed));
+
+            let stopped = Parser::create(|ctx| {
+                ctx.stop();
+                Parser
+            });
+            sleep(Duration::from_millis(10)).await;
+            let res = stopped.send(Parse("1")).and_send_to(doubler).await;
+            assert_eq!(res, Err(MailboxError::Closed));
+        });
+    }
+}
+
+mod mapped_recipient {
+    use super::*;
+
+    struct Add(usize);
+
+    impl Message for Add {
+        type Result = usize;
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Increment;
+
+    impl Message for Increment {
+        type Result = usize;
+    }
+
+    struct Counter(usize);
+
+    impl Actor for Counter {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Add> for Counter {
+        type Result = usize;
+
+        fn handle(&mut self, msg: Add, _: &mut Self::Context) -> usize {
+            self.0 += msg.0;
+            self.0
+        }
+    }
+
+    #[test]
+    fn converts_messages() {
+        System::new().block_on(async {
+            let add = Counter(0).start().recipient::<Add>();
+            let increment: Recipient<Increment> = add.clone().map(|Increment| Add(1));
+
+            increment.do_send(Increment);
+            assert_eq!(increment.send(Increment).await, Ok(2));
+            assert_eq!(add.send(Add(10)).await, Ok(12));
+
+            let weak = increment.downgrade();
+            assert_eq!(weak.upgrade().unwrap().send(Increment).await, Ok(13));
+            assert_eq!(increment, increment.clone().map(|msg| msg));
+        });
+    }
+
+    #[test]
+    fn refused_message_is_returned_unconverted() {
+        System::new().block_on(async {
+            let stopped = Counter::create(|ctx| {
+                ctx.stop();
+                Counter(0)
+            });
+            sleep(Duration::from_millis(10)).await;
+
+            let increment = stopped
+                .recipient::<Add>()
+                .map(|Increment| -> Add { panic!("refused message got converted") });
+            assert!(!increment.connected());
+            match increment.try_send(Increment) {
+                Err(SendError::Closed(msg)) => assert_eq!(msg, Increment),
+                _ => panic!("message should be refused"),
+            }
+            assert_eq!(increment.send(Increment).await, Err(MailboxError::Closed));
+        });
+    }
+}
+
+mod into_stream {
+    use futures_util::stream::StreamExt as _;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Next;
+
+    impl Message for Next {
+        type Result = usize;
+    }
+
+    struct Counter {
+        count: usize,
+        stop_at: usize,
+    }
+
+    impl Actor for Counter {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Next> for Counter {
+        type Result = usize;
+
+        fn handle(&mut self, _: Next, ctx: &mut Self::Context) -> usize {
+            self.count += 1;
+            if self.count == self.stop_at {
+                ctx.stop();
+            }
+            self.count
+        }
+    }
+
+    #[test]
+    fn yields_responses_until_actor_stops() {
+        System::new().block_on(async {
+            let addr = Counter {
+                count: 0,
+                stop_at: 3,
+            }
+            .start();
+
+            let counts: Vec<_> = addr.into_stream(Next).collect().await;
+            assert_eq!(counts, [Ok(1), Ok(2), Ok(3)]);
+        });
+    }
+
+    #[test]
+    fn sends_one_request_per_item() {
+        System::new().block_on(async {
+            let addr = Counter {
+                count: 0,
+                stop_at: usize::MAX,
+            }
+            .start();
+
+            let mut counts = std::pin::pin!(addr.clone().into_stream(Next));
+            assert_eq!(counts.next().await, Some(Ok(1)));
+            assert_eq!(counts.next().await, Some(Ok(2)));
+            sleep(Duration::from_millis(10)).await;
+            assert_eq!(addr.send(Next).await, Ok(3));
+        });
+    }
+}
+
+mod forward_to {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Clone)]
+    struct Produce;
+
+    impl Message for Produce {
+        type Result = Item;
+    }
+
+    struct Item(usize);
+
+    impl Message for Item {
+        type Result = ();
+    }
+
+    struct Producer {
+        produced: Arc<AtomicUsize>,
+        stop_at: usize,
+    }
+
+    impl Actor for Producer {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Produce> for Producer {
+        type Result = MessageResult<Produce>;
+
+        fn handle(&mut self, _: Produce, ctx: &mut Self::Context) -> Self::Result {
+            let n = self.produced.fetch_add(1, Ordering::SeqCst) + 1;
+            if n == self.stop_at {
+                ctx.stop();
+            }
+            MessageResult(Item(n))
+        }
+    }
+
+    struct SlowConsumer(Arc<Mutex<Vec<usize>>>);
+
+    impl Actor for SlowConsumer {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Item> for SlowConsumer {
+        type Result = ResponseFuture<()>;
+
+        fn handle(&mut self, msg: Item, _: &mut Self::Context) -> Self::Result {
+            let received = Arc::clone(&self.0);
+            Box::pin(async move {
+                sleep(Duration::from_millis(10)).await;
+                received.lock().unwrap().push(msg.0);
+            })
+        }
+    }
+
+    type Received = Arc<Mutex<Vec<usize>>>;
+
+    fn start(
+        stop_at: usize,
+    ) -> (
+        Addr<Producer>,
+        Arc<AtomicUsize>,
+        Addr<SlowConsumer>,
+        Received,
+    ) {
+        let produced = Arc::new(AtomicUsize::new(0));
+        let producer = Producer {
+            produced: Arc::clone(&produced),
+            stop_at,
+        }
+        .start();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let consumer = SlowConsumer(Arc::clone(&received)).start();
+        (producer, produced, consumer, received)
+    }
+
+    #[actix::test]
+    async fn slow_consumer_throttles_producer() {
+        let (producer, produced, consumer, received) = start(usize::MAX);
+
+        let forward = actix_rt::spawn(producer.forward_to(Produce, consumer));
+        sleep(Duration::from_millis(55)).await;
+
+        // at most the response being forwarded is ahead of the consumer
+        let produced = produced.load(Ordering::SeqCst);
+        let received = received.lock().unwrap().len();
+        assert!(produced <= received + 1, "{} > {} + 1", produced, received);
+        assert!(produced <= 6, "{}", produced);
+
+        forward.abort();
+    }
+
+    #[actix::test]
+    async fn resolves_once_producer_stops() {
+        let (producer, _, consumer, received) = start(3);
+
+        assert_eq!(producer.forward_to(Produce, consumer).await, 3);
+        assert_eq!(*received.lock().unwrap(), [1, 2, 3]);
+    }
+}
+
+mod ready {
+    use futures_util::FutureExt as _;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    struct Hold(oneshot::Receiver<()>);
+
+    impl Message for Hold {
+        type Result = ();
+    }
+
+    struct Job;
+
+    impl Message for Job {
+        type Result = ();
+    }
+
+    struct Worker(Arc<AtomicUsize>);
+
+    impl Actor for Worker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.set_mailbox_capacity(1);
+        }
+    }
+
+    impl Handler<Hold> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Hold, ctx: &mut Self::Context) {
+            // stop taking messages from the mailbox until released, or stop if dropped
+            ctx.wait(msg.0.into_actor(self).map(|res, _, ctx| {
+                if res.is_err() {
+                    ctx.stop();
+                }
+            }));
+        }
+    }
+
+    impl Handler<Job> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, _: Job, _: &mut Self::Context) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    async fn held_worker() -> (Addr<Worker>, Arc<AtomicUsize>, oneshot::Sender<()>) {
+        let jobs = Arc::new(AtomicUsize::new(0));
+        let addr = Worker(Arc::clone(&jobs)).start();
+        let (release, rx) = oneshot::channel();
+        addr.send(Hold(rx)).await.unwrap();
+        (addr, jobs, release)
+    }
+
+    #[actix::test]
+    async fn waits_for_mailbox_to_drain() {
+        let (addr, jobs, release) = held_worker().await;
+        assert_eq!(addr.ready().await, Ok(()));
+
+        addr.try_send(Job).unwrap();
+        assert!(matches!(addr.try_send(Job), Err(SendError::Full(_))));
+
+        // the future type can be named, e.g. to store it
+        let ready: actix::MailboxReady<'_, Worker> = addr.ready();
+        let mut ready = std::pin::pin!(ready);
+        assert!(ready.as_mut().now_or_never().is_none());
+
+        release.send(()).unwrap();
+        assert_eq!(ready.await, Ok(()));
+        assert_eq!(jobs.load(Ordering::SeqCst), 1);
+        addr.try_send(Job).unwrap();
+    }
+
+    #[actix::test]
+    async fn waits_when_another_address_filled_mailbox() {
+        let (addr, jobs, release) = held_worker().await;
+        let other = addr.clone();
+        other.do_send(Job);
+
+        let mut ready = std::pin::pin!(addr.ready());
+        assert!(ready.as_mut().now_or_never().is_none());
+
+        release.send(()).unwrap();
+        assert_eq!(ready.await, Ok(()));
+        assert_eq!(jobs.load(Ordering::SeqCst), 1);
+    }
+
+    #[actix::test]
+    async fn fails_once_actor_stops() {
+        let (addr, jobs, release) = held_worker().await;
+        addr.try_send(Job).unwrap();
+
+        let mut ready = std::pin::pin!(addr.ready());
+        assert!(ready.as_mut().now_or_never().is_none());
+
+        drop(release);
+        assert_eq!(ready.await, Err(MailboxError::Closed));
+        assert_eq!(addr.ready().await, Err(MailboxError::Closed));
+        assert_eq!(jobs.load(Ordering::SeqCst), 0);
+    }
+}
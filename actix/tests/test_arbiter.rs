@@ -1,6 +1,9 @@
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread::{self, ThreadId},
 };
 
 use actix::prelude::*;
@@ -59,3 +62,119 @@ fn test_start_actor_message() {
 
     assert_eq!(count.load(Ordering::Relaxed), 1);
 }
+
+struct ThreadActor;
+
+impl Actor for ThreadActor {
+    type Context = Context<Self>;
+}
+
+struct CurrentThread;
+
+impl Message for CurrentThread {
+    type Result = ThreadId;
+}
+
+impl Handler<CurrentThread> for ThreadActor {
+    type Result = MessageResult<CurrentThread>;
+
+    fn handle(&mut self, _: CurrentThread, _: &mut Self::Context) -> Self::Result {
+        MessageResult(thread::current().id())
+    }
+}
+
+async fn arbiter_thread(arbiter: &Arbiter) -> ThreadId {
+    let (tx, rx) = oneshot::channel();
+    arbiter.spawn_fn(move || {
+        tx.send(thread::current().id()).unwrap();
+    });
+    rx.await.unwrap()
+}
+
+#[test]
+fn test_start_in_arbiter_thread() {
+    let sys = System::new();
+
+    sys.block_on(async {
+        let arbiter1 = Arbiter::new();
+        let arbiter2 = Arbiter::new();
+        let thread1 = arbiter_thread(&arbiter1).await;
+        let thread2 = arbiter_thread(&arbiter2).await;
+        assert_ne!(thread1, thread2);
+
+        let addr1 = ThreadActor::start_in_arbiter(&arbiter1.handle(), |_| ThreadActor);
+        let addr2 = ThreadActor::start_in_arbiter(&arbiter2.handle(), |_| ThreadActor);
+
+        // addresses are `Send` and can be used from any thread
+        let addr1 = thread::spawn(move || addr1).join().unwrap();
+
+        assert_eq!(addr1.send(CurrentThread).await.unwrap(), thread1);
+        assert_eq!(addr2.send(CurrentThread).await.unwrap(), thread2);
+        assert_ne!(thread1, thread::current().id());
+
+        arbiter1.stop();
+        arbiter2.stop();
+    });
+}
+
+struct SystemActor;
+
+impl Actor for SystemActor {
+    type Context = Context<Self>;
+}
+
+/// Returns the IDs of the actor's system and of the thread's current system, after replacing
+/// the thread's current system with the given one.
+struct SystemIds(Option<System>);
+
+impl Message for SystemIds {
+    type Result = (usize, usize);
+}
+
+impl Handler<SystemIds> for SystemActor {
+    type Result = MessageResult<SystemIds>;
+
+    fn handle(&mut self, msg: SystemIds, ctx: &mut Self::Context) -> Self::Result {
+        let current = System::current();
+        if let Some(other) = msg.0 {
+            System::set_current(other);
+        }
+        let ids = (ctx.system().id(), System::current().id());
+        System::set_current(current);
+        MessageResult(ids)
+    }
+}
+
+#[test]
+fn test_context_system_on_arbiter() {
+    // a system handle from another thread, to stand in for stale thread-local state
+    let other = thread::spawn(|| {
+        let sys = System::new();
+        sys.block_on(async { System::current() })
+    })
+    .join()
+    .unwrap();
+
+    let sys = System::new();
+
+    sys.block_on(async move {
+        let main_id = System::current().id();
+        assert_ne!(other.id(), main_id);
+
+        let arbiter = Arbiter::new();
+        let addr = SystemActor::start_in_arbiter(&arbiter.handle(), |_| SystemActor);
+
+        assert_eq!(
+            addr.send(SystemIds(None)).await.unwrap(),
+            (main_id, main_id)
+        );
+
+        let other_id = other.id();
+        assert_eq!(
+            addr.send(SystemIds(Some(other))).await.unwrap(),
+            (main_id, other_id)
+        );
+
+        arbiter.stop();
+    });
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "macros")]
+
+use std::time::Duration;
+
+use actix::{
+    actors::collector::{Batch, Collector, Drain, Subscribe},
+    prelude::*,
+};
+use actix_rt::time::sleep;
+use tokio::sync::mpsc;
+
+#[derive(Debug, PartialEq, Message)]
+#[rtype(result = "()")]
+struct Metric(u32);
+
+struct Producer(Addr<Collector<Metric>>, u32);
+
+impl Actor for Producer {
+    type Context = Context<Self>;
+
+    fn started(&mut self, _: &mut Self::Context) {
+        for _ in 0..3 {
+            self.0.do_send(Metric(self.1));
+        }
+    }
+}
+
+struct Consumer(mpsc::UnboundedSender<Vec<Metric>>);
+
+impl Actor for Consumer {
+    type Context = Context<Self>;
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Stop;
+
+impl Handler<Stop> for Consumer {
+    type Result = ();
+
+    fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+impl Handler<Batch<Metric>> for Consumer {
+    type Result = ();
+
+    fn handle(&mut self, msg: Batch<Metric>, _: &mut Self::Context) {
+        let _ = self.0.send(msg.0);
+    }
+}
+
+#[actix::test]
+async fn buffers_from_many_producers() {
+    let collector = Collector::<Metric>::new().start();
+
+    for id in 0..4 {
+        Producer(collector.clone(), id).start();
+    }
+    sleep(Duration::from_millis(20)).await;
+
+    let mut metrics = collector.send(Drain::new()).await.unwrap();
+    metrics.sort_by_key(|metric| metric.0);
+
+    assert_eq!(metrics.len(), 12);
+    assert_eq!(metrics[..3], [Metric(0), Metric(0), Metric(0)]);
+}
+
+#[actix::test]
+async fn drain_clears_buffer() {
+    let collector = Collector::<Metric>::new().start();
+
+    collector.do_send(Metric(1));
+    collector.do_send(Metric(2));
+    assert_eq!(
+        collector.send(Drain::new()).await.unwrap(),
+        [Metric(1), Metric(2)]
+    );
+    assert!(collector.send(Drain::new()).await.unwrap().is_empty());
+
+    collector.do_send(Metric(3));
+    assert_eq!(collector.send(Drain::new()).await.unwrap(), [Metric(3)]);
+}
+
+#[actix::test]
+async fn subscriber_receives_batches() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let collector = Collector::<Metric>::new().start();
+    let consumer = Consumer(tx).start();
+
+    collector
+        .send(Subscribe(consumer.recipient(), Duration::from_millis(20)))
+        .await
+        .unwrap();
+
+    collector.do_send(Metric(1));
+    collector.do_send(Metric(2));
+    assert_eq!(rx.recv().await.unwrap(), [Metric(1), Metric(2)]);
+
+    // empty intervals are skipped, so the next batch only holds the next metric
+    sleep(Duration::from_millis(50)).await;
+    collector.do_send(Metric(3));
+    assert_eq!(rx.recv().await.unwrap(), [Metric(3)]);
+    assert!(collector.send(Drain::new()).await.unwrap().is_empty());
+}
+
+#[actix::test]
+async fn buffers_again_after_subscriber_stops() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let collector = Collector::<Metric>::new().start();
+    let consumer = Consumer(tx).start();
+
+    collector
+        .send(Subscribe(
+            consumer.clone().recipient(),
+            Duration::from_millis(10),
+        ))
+        .await
+        .unwrap();
+
+    // the stopped consumer drops its sender
+    consumer.do_send(Stop);
+    assert!(rx.recv().await.is_none());
+
+    collector.do_send(Metric(4));
+    sleep(Duration::from_millis(30)).await;
+    assert_eq!(collector.send(Drain::new()).await.unwrap(), [Metric(4)]);
+}
@@ -656,3 +656,1975 @@ mod restart {
         }));
     }
 }
+
+mod cancel_all_futures {
+    use std::{
+        future::{pending, Future},
+        rc::Rc,
+    };
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    struct DropGuard(Rc<AtomicUsize>);
+
+    impl Drop for DropGuard {
+        fn drop(&mut self) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn guarded_pending(dropped: Rc<AtomicUsize>) -> impl Future<Output = ()> {
+        let guard = DropGuard(dropped);
+        async move {
+            let _guard = guard;
+            pending::<()>().await
+        }
+    }
+
+    struct TestActor {
+        dropped: Rc<AtomicUsize>,
+        spawned: Vec<SpawnHandle>,
+    }
+
+    impl Actor for TestActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            for _ in 0..3 {
+                let handle = ctx.spawn(guarded_pending(Rc::clone(&self.dropped)).into_actor(self));
+                self.spawned.push(handle);
+            }
+        }
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct CancelAll(oneshot::Sender<(usize, usize)>);
+
+    impl Handler<CancelAll> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, msg: CancelAll, ctx: &mut Self::Context) {
+            let cancelled = ctx.cancel_all_futures();
+
+            // futures spawned after the call keep running
+            ctx.run_later(Duration::from_millis(10), move |act, _| {
+                let _ = msg.0.send((cancelled, act.dropped.load(Ordering::Relaxed)));
+            });
+        }
+    }
+
+    #[test]
+    fn cancels_outstanding_futures() {
+        System::new().block_on(async {
+            let dropped = Rc::new(AtomicUsize::new(0));
+            let addr = TestActor {
+                dropped: Rc::clone(&dropped),
+                spawned: Vec::new(),
+            }
+            .start();
+
+            let (tx, rx) = oneshot::channel();
+            addr.send(CancelAll(tx)).await.unwrap();
+
+            assert_eq!(rx.await.unwrap(), (3, 3));
+        });
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct CancelOneThenAll(oneshot::Sender<(usize, usize, usize)>);
+
+    impl Handler<CancelOneThenAll> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, msg: CancelOneThenAll, ctx: &mut Self::Context) {
+            ctx.cancel_future(self.spawned[0]);
+            let first = ctx.cancel_all_futures();
+            let second = ctx.cancel_all_futures();
+
+            ctx.run_later(Duration::from_millis(10), move |act, _| {
+                let dropped = act.dropped.load(Ordering::Relaxed);
+                let _ = msg.0.send((first, second, dropped));
+            });
+        }
+    }
+
+    #[test]
+    fn counts_each_future_once() {
+        System::new().block_on(async {
+            let dropped = Rc::new(AtomicUsize::new(0));
+            let addr = TestActor {
+                dropped: Rc::clone(&dropped),
+                spawned: Vec::new(),
+            }
+            .start();
+
+            let (tx, rx) = oneshot::channel();
+            addr.send(CancelOneThenAll(tx)).await.unwrap();
+
+            // the future cancelled on its own is not counted again
+            assert_eq!(rx.await.unwrap(), (2, 0, 3));
+        });
+    }
+}
+
+mod drain_pending {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Job(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Other;
+
+    struct TestActor {
+        tx: Option<oneshot::Sender<Vec<u32>>>,
+    }
+
+    impl Actor for TestActor {
+        type Context = Context<Self>;
+
+        fn stopped(&mut self, ctx: &mut Self::Context) {
+            let jobs = ctx.drain_pending::<Job>().into_iter().map(|job| job.0);
+            let _ = self.tx.take().unwrap().send(jobs.collect());
+        }
+    }
+
+    impl Handler<Job> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, _: Job, ctx: &mut Self::Context) {
+            ctx.stop();
+        }
+    }
+
+    impl Handler<Other> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, _: Other, _: &mut Self::Context) {}
+    }
+
+    #[test]
+    fn returns_queued_messages_of_type() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            let addr = TestActor { tx: Some(tx) }.start();
+
+            addr.do_send(Job(1));
+            addr.do_send(Job(2));
+            addr.do_send(Other);
+            addr.do_send(Job(3));
+            let pending = addr.send(Job(4));
+
+            assert_eq!(rx.await.unwrap(), vec![2, 3, 4]);
+            assert_eq!(pending.await, Err(MailboxError::Closed));
+        });
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Seq(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "Vec<u32>")]
+    struct Handled;
+
+    #[derive(Default)]
+    struct Draining {
+        seqs: Vec<u32>,
+    }
+
+    impl Actor for Draining {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Seq> for Draining {
+        type Result = ();
+
+        fn handle(&mut self, msg: Seq, ctx: &mut Self::Context) {
+            self.seqs.push(msg.0);
+            // drain while the sender thread keeps pushing
+            ctx.drain_pending::<Job>();
+        }
+    }
+
+    impl Handler<Job> for Draining {
+        type Result = ();
+
+        fn handle(&mut self, _: Job, _: &mut Self::Context) {}
+    }
+
+    impl Handler<Handled> for Draining {
+        type Result = MessageResult<Handled>;
+
+        fn handle(&mut self, _: Handled, _: &mut Self::Context) -> Self::Result {
+            MessageResult(std::mem::take(&mut self.seqs))
+        }
+    }
+
+    #[test]
+    fn concurrent_senders_keep_their_order() {
+        System::new().block_on(async {
+            let addr = Draining::default().start();
+
+            let sender = std::thread::spawn({
+                let addr = addr.clone();
+                move || {
+                    for n in 0..5_000 {
+                        addr.do_send(Seq(n));
+                        if n % 3 == 0 {
+                            addr.do_send(Job(n));
+                        }
+                    }
+                }
+            });
+            while !sender.is_finished() {
+                actix_rt::task::yield_now().await;
+            }
+            sender.join().unwrap();
+
+            let seqs = addr.send(Handled).await.unwrap();
+            assert_eq!(seqs, (0..5_000).collect::<Vec<_>>());
+        });
+    }
+}
+
+mod pause {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Ping;
+
+    struct TestActor {
+        handled: Arc<AtomicUsize>,
+        resume: Option<oneshot::Receiver<()>>,
+    }
+
+    impl Actor for TestActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.pause();
+
+            let resume = self.resume.take().unwrap();
+            ctx.spawn(async move { resume.await.unwrap() }.into_actor(self).map(
+                |_, _, ctx: &mut Context<Self>| {
+                    assert!(ctx.paused());
+                    ctx.resume();
+                },
+            ));
+        }
+    }
+
+    impl Handler<Ping> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, _: Ping, _: &mut Self::Context) {
+            self.handled.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn messages_wait_until_resumed() {
+        System::new().block_on(async {
+            let handled = Arc::new(AtomicUsize::new(0));
+            let (tx, rx) = oneshot::channel();
+            let addr = TestActor {
+                handled: Arc::clone(&handled),
+                resume: Some(rx),
+            }
+            .start();
+
+            for _ in 0..3 {
+                addr.do_send(Ping);
+            }
+            sleep(Duration::from_millis(20)).await;
+            assert_eq!(handled.load(Ordering::SeqCst), 0);
+
+            tx.send(()).unwrap();
+            addr.send(Ping).await.unwrap();
+            assert_eq!(handled.load(Ordering::SeqCst), 4);
+        });
+    }
+}
+
+mod mailbox_budget {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Tick;
+
+    struct TestActor {
+        ticks: usize,
+        tx: Option<oneshot::Sender<usize>>,
+    }
+
+    impl Actor for TestActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.set_mailbox_budget(8);
+            ctx.run_later(Duration::from_millis(10), |act, ctx| {
+                let _ = act.tx.take().unwrap().send(act.ticks);
+                ctx.stop();
+            });
+            ctx.notify(Tick);
+        }
+    }
+
+    impl Handler<Tick> for TestActor {
+        type Result = ();
+
+        fn handle(&mut self, _: Tick, ctx: &mut Self::Context) {
+            self.ticks += 1;
+            // keeps the mailbox permanently non-empty
+            ctx.address().do_send(Tick);
+        }
+    }
+
+    #[test]
+    fn busy_mailbox_does_not_starve_spawned_futures() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            TestActor {
+                ticks: 0,
+                tx: Some(tx),
+            }
+            .start();
+
+            assert!(rx.await.unwrap() > 0);
+        });
+    }
+}
+
+mod correlation_id {
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Start(Option<u64>);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Hop;
+
+    struct Entry(Addr<Relay>);
+
+    impl Actor for Entry {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Start> for Entry {
+        type Result = ();
+
+        fn handle(&mut self, msg: Start, ctx: &mut Self::Context) {
+            ctx.set_correlation_id(msg.0);
+            self.0.do_send(Hop);
+        }
+    }
+
+    struct Relay(Recipient<Hop>, UnboundedSender<Option<u64>>);
+
+    impl Actor for Relay {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Hop> for Relay {
+        type Result = ();
+
+        fn handle(&mut self, msg: Hop, ctx: &mut Self::Context) {
+            let _ = self.1.send(ctx.correlation_id());
+            self.0.do_send(msg);
+        }
+    }
+
+    struct Sink(UnboundedSender<Option<u64>>);
+
+    impl Actor for Sink {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Hop> for Sink {
+        type Result = ();
+
+        fn handle(&mut self, _: Hop, ctx: &mut Self::Context) {
+            let _ = self.0.send(ctx.correlation_id());
+        }
+    }
+
+    #[actix::test]
+    async fn nested_sends_preserve_id() {
+        let (tx, mut rx) = unbounded_channel();
+        let sink = Sink(tx.clone()).start();
+        let relay = Relay(sink.recipient(), tx).start();
+        let entry = Entry(relay.clone()).start();
+
+        entry.do_send(Start(Some(42)));
+        assert_eq!(rx.recv().await, Some(Some(42)));
+        assert_eq!(rx.recv().await, Some(Some(42)));
+
+        // the relay's own ID is restored after handling
+        relay.do_send(Hop);
+        assert_eq!(rx.recv().await, Some(None));
+        assert_eq!(rx.recv().await, Some(None));
+
+        entry.do_send(Start(None));
+        assert_eq!(rx.recv().await, Some(None));
+        assert_eq!(rx.recv().await, Some(None));
+    }
+}
+
+mod spawn_future {
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    struct Spawner(UnboundedSender<&'static str>);
+
+    impl Actor for Spawner {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let tx = self.0.clone();
+            let cancelled = ctx.spawn_future(async move {
+                sleep(Duration::from_millis(10)).await;
+                tx.send("cancelled").unwrap();
+            });
+
+            let tx = self.0.clone();
+            ctx.spawn_future(async move {
+                sleep(Duration::from_millis(20)).await;
+                tx.send("completed").unwrap();
+            });
+
+            assert!(ctx.cancel_future(cancelled));
+        }
+    }
+
+    #[actix::test]
+    async fn spawn_and_cancel_plain_future() {
+        let (tx, mut rx) = unbounded_channel();
+        let _addr = Spawner(tx).start();
+
+        assert_eq!(rx.recv().await, Some("completed"));
+    }
+}
+
+mod slow_consumer {
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Work;
+
+    struct Backlogged(UnboundedSender<usize>);
+
+    impl Actor for Backlogged {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            // stop consuming so the mailbox backs up
+            ctx.pause();
+            ctx.set_slow_consumer_threshold(5, Duration::from_millis(20), |act, ctx, len| {
+                act.0.send(len).unwrap();
+                ctx.resume();
+            });
+        }
+    }
+
+    impl Handler<Work> for Backlogged {
+        type Result = ();
+
+        fn handle(&mut self, _: Work, _: &mut Self::Context) {}
+    }
+
+    #[actix::test]
+    async fn backed_up_mailbox_triggers_callback() {
+        let (tx, mut rx) = unbounded_channel();
+        let addr = Backlogged(tx).start();
+
+        for _ in 0..10 {
+            addr.do_send(Work);
+        }
+
+        assert_eq!(rx.recv().await, Some(10));
+
+        // the mailbox drains once the callback resumes the actor
+        addr.send(Work).await.unwrap();
+        assert!(rx.try_recv().is_err());
+    }
+}
+
+/// Captures warnings, so tests can check what was logged on their own thread.
+mod captured_logs {
+    use std::{
+        sync::{Mutex, Once},
+        thread::{self, ThreadId},
+    };
+
+    use log::{Level, LevelFilter, Log, Metadata, Record};
+
+    struct Capture(Mutex<Vec<(ThreadId, String)>>);
+
+    impl Log for Capture {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            metadata.level() <= Level::Warn
+        }
+
+        fn log(&self, record: &Record<'_>) {
+            if self.enabled(record.metadata()) {
+                let entry = (thread::current().id(), record.args().to_string());
+                self.0.lock().unwrap().push(entry);
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+    pub fn install() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&LOGGER).unwrap();
+            log::set_max_level(LevelFilter::Warn);
+        });
+    }
+
+    /// Warnings logged on the current thread so far.
+    pub fn current_thread() -> Vec<String> {
+        let id = thread::current().id();
+        LOGGER
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(thread, _)| *thread == id)
+            .map(|(_, msg)| msg.clone())
+            .collect()
+    }
+}
+
+mod slow_handler {
+    use std::thread;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Block(Duration);
+
+    struct Blocker;
+
+    impl Actor for Blocker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.set_slow_handler_threshold(Duration::from_millis(30));
+        }
+    }
+
+    impl Handler<Block> for Blocker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Block, _: &mut Self::Context) {
+            thread::sleep(msg.0);
+        }
+    }
+
+    #[actix::test]
+    async fn slow_handler_is_logged() {
+        captured_logs::install();
+
+        let addr = Blocker.start();
+
+        addr.send(Block(Duration::from_millis(1))).await.unwrap();
+        assert!(captured_logs::current_thread().is_empty());
+
+        addr.send(Block(Duration::from_millis(60))).await.unwrap();
+        let logs = captured_logs::current_thread();
+        assert_eq!(logs.len(), 1);
+        assert!(
+            logs[0].contains("slow_handler::Blocker took"),
+            "{}",
+            logs[0]
+        );
+        assert!(logs[0].ends_with("slow_handler::Block"), "{}", logs[0]);
+    }
+}
+
+mod missed_ticks {
+    use std::{cell::RefCell, rc::Rc};
+
+    use actix::clock::MissedTickBehavior;
+
+    use super::*;
+
+    struct Ticker {
+        behavior: MissedTickBehavior,
+        // milliseconds since the actor started, for each tick
+        ticks: Rc<RefCell<Vec<u128>>>,
+    }
+
+    impl Actor for Ticker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let start = Instant::now();
+            ctx.run_interval_with(Duration::from_millis(20), self.behavior, move |act, _| {
+                act.ticks.borrow_mut().push(start.elapsed().as_millis());
+            });
+        }
+    }
+
+    /// Runs a 20ms interval that cannot run from 25ms to 95ms. Returns the number of ticks that
+    /// ran by the time the actor resumed, and by 125ms.
+    async fn tick_counts(behavior: MissedTickBehavior) -> (usize, usize) {
+        tokio::time::pause();
+        let ticks = Rc::new(RefCell::new(Vec::new()));
+        let _addr = Ticker {
+            behavior,
+            ticks: Rc::clone(&ticks),
+        }
+        .start();
+
+        sleep(Duration::from_millis(25)).await;
+        // the clock moves on without the actor being polled, as if it were blocked
+        tokio::time::advance(Duration::from_millis(70)).await;
+        sleep(Duration::from_millis(30)).await;
+
+        // timers fire on whole milliseconds, so ticks may run up to 1ms late
+        let ticks = ticks.borrow();
+        let on_resume = ticks.iter().filter(|&&ms| ms <= 97).count();
+        (on_resume, ticks.len())
+    }
+
+    #[actix::test]
+    async fn burst_catches_up() {
+        // the ticks due at 40, 60 and 80 run on resume, then 100 and 120
+        assert_eq!(tick_counts(MissedTickBehavior::Burst).await, (4, 6));
+    }
+
+    #[actix::test]
+    async fn skip_drops_missed_ticks() {
+        // one tick on resume, then back on schedule at 100 and 120
+        assert_eq!(tick_counts(MissedTickBehavior::Skip).await, (2, 4));
+    }
+
+    #[actix::test]
+    async fn delay_restarts_schedule() {
+        // one tick on resume, then 20ms after it
+        assert_eq!(tick_counts(MissedTickBehavior::Delay).await, (2, 3));
+    }
+}
+
+mod self_notifier {
+    use actix::SelfNotifier;
+    use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Finished(usize);
+
+    struct Worker(UnboundedSender<usize>);
+
+    impl Actor for Worker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let guard = ctx.self_notifier().on_drop(Finished(1));
+            actix_rt::spawn(async move {
+                let _guard = guard;
+                sleep(Duration::from_millis(10)).await;
+            });
+
+            let notifier = ctx.self_notifier();
+            notifier.notify(Finished(0));
+        }
+    }
+
+    impl Handler<Finished> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, msg: Finished, _: &mut Self::Context) {
+            self.0.send(msg.0).unwrap();
+        }
+    }
+
+    #[actix::test]
+    async fn dropped_guard_notifies_actor() {
+        let (tx, mut rx) = unbounded_channel();
+        let _addr = Worker(tx).start();
+
+        assert_eq!(rx.recv().await, Some(0));
+        assert_eq!(rx.recv().await, Some(1));
+    }
+
+    struct Handout(
+        Option<oneshot::Sender<SelfNotifier<Finished>>>,
+        UnboundedSender<usize>,
+    );
+
+    impl Actor for Handout {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let notifier = ctx.self_notifier().on_drop(Finished(1));
+            self.0.take().unwrap().send(notifier).unwrap();
+        }
+    }
+
+    impl Handler<Finished> for Handout {
+        type Result = ();
+
+        fn handle(&mut self, msg: Finished, _: &mut Self::Context) {
+            self.1.send(msg.0).unwrap();
+        }
+    }
+
+    #[actix::test]
+    async fn notifier_does_not_keep_actor_alive() {
+        let (tx, mut rx) = unbounded_channel();
+        let (ntx, nrx) = oneshot::channel();
+
+        let addr = Handout(Some(ntx), tx).start();
+        let notifier = nrx.await.unwrap();
+
+        notifier.notify(Finished(0));
+        assert_eq!(rx.recv().await, Some(0));
+
+        // the actor stops once its last address is gone, dropping its sender
+        drop(addr);
+        assert_eq!(rx.recv().await, None);
+
+        notifier.notify(Finished(2));
+        drop(notifier);
+    }
+
+    #[actix::test]
+    async fn clones_are_not_armed() {
+        let (tx, mut rx) = unbounded_channel();
+        let (ntx, nrx) = oneshot::channel();
+
+        let addr = Handout(Some(ntx), tx).start();
+        let notifier = nrx.await.unwrap();
+
+        let clone = notifier.clone();
+        drop(notifier);
+        assert_eq!(rx.recv().await, Some(1));
+
+        // dropping the clone sends nothing, so the next message is this one
+        drop(clone);
+        addr.do_send(Finished(2));
+        assert_eq!(rx.recv().await, Some(2));
+    }
+}
+
+mod backpressure {
+    use futures_util::stream;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Work;
+
+    struct Producer {
+        watermark: Option<usize>,
+        max_queued: usize,
+        done: Option<oneshot::Sender<usize>>,
+    }
+
+    impl Actor for Producer {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let items = stream::iter(0..200u32);
+            match self.watermark {
+                Some(watermark) => ctx.add_stream_with_backpressure(items, watermark),
+                None => ctx.add_stream(items),
+            };
+        }
+    }
+
+    impl StreamHandler<u32> for Producer {
+        fn handle(&mut self, _: u32, ctx: &mut Self::Context) {
+            // each item queues follow-up work in the mailbox
+            ctx.address().do_send(Work);
+            self.max_queued = self.max_queued.max(ctx.mailbox_len());
+        }
+
+        fn finished(&mut self, _: &mut Self::Context) {
+            self.done.take().unwrap().send(self.max_queued).unwrap();
+        }
+    }
+
+    impl Handler<Work> for Producer {
+        type Result = ();
+
+        fn handle(&mut self, _: Work, _: &mut Self::Context) {}
+    }
+
+    async fn max_queued(watermark: Option<usize>) -> usize {
+        let (tx, rx) = oneshot::channel();
+        let _addr = Producer {
+            watermark,
+            max_queued: 0,
+            done: Some(tx),
+        }
+        .start();
+        rx.await.unwrap()
+    }
+
+    #[actix::test]
+    async fn mailbox_stays_below_watermark() {
+        assert!(max_queued(Some(4)).await <= 4);
+    }
+
+    #[actix::test]
+    async fn without_backpressure_mailbox_grows() {
+        assert!(max_queued(None).await > 4);
+    }
+}
+
+mod replace_stream {
+    use futures_core::Stream;
+    use futures_util::stream;
+    use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+    use super::*;
+
+    fn receiver_stream(rx: UnboundedReceiver<u32>) -> impl Stream<Item = u32> {
+        stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        })
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Reconnect(UnboundedReceiver<u32>);
+
+    struct Session {
+        stream: SpawnHandle,
+        handled: usize,
+        seen: UnboundedSender<(u32, usize)>,
+    }
+
+    impl Actor for Session {
+        type Context = Context<Self>;
+    }
+
+    impl StreamHandler<u32> for Session {
+        fn handle(&mut self, item: u32, _: &mut Self::Context) {
+            self.handled += 1;
+            self.seen.send((item, self.handled)).unwrap();
+        }
+    }
+
+    impl Handler<Reconnect> for Session {
+        type Result = ();
+
+        fn handle(&mut self, msg: Reconnect, ctx: &mut Self::Context) {
+            self.stream = ctx.replace_stream(self.stream, receiver_stream(msg.0));
+        }
+    }
+
+    #[actix::test]
+    async fn keeps_state_across_streams() {
+        let (seen_tx, mut seen_rx) = unbounded_channel();
+        let (old_tx, old_rx) = unbounded_channel();
+
+        let addr = Session::create(|ctx| Session {
+            stream: ctx.add_stream(receiver_stream(old_rx)),
+            handled: 0,
+            seen: seen_tx,
+        });
+
+        old_tx.send(1).unwrap();
+        old_tx.send(2).unwrap();
+        assert_eq!(seen_rx.recv().await, Some((1, 1)));
+        assert_eq!(seen_rx.recv().await, Some((2, 2)));
+
+        let (new_tx, new_rx) = unbounded_channel();
+        addr.send(Reconnect(new_rx)).await.unwrap();
+
+        // the replaced stream is no longer read
+        let _ = old_tx.send(100);
+        new_tx.send(3).unwrap();
+        assert_eq!(seen_rx.recv().await, Some((3, 3)));
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(seen_rx.try_recv().is_err());
+        assert!(addr.connected());
+    }
+}
+
+mod notify_at {
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Due(&'static str);
+
+    struct Scheduler {
+        deadlines: Vec<(&'static str, Instant)>,
+        fired: UnboundedSender<(&'static str, Instant)>,
+    }
+
+    impl Actor for Scheduler {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            for (name, deadline) in self.deadlines.drain(..) {
+                ctx.notify_at(Due(name), deadline);
+            }
+        }
+    }
+
+    impl Handler<Due> for Scheduler {
+        type Result = ();
+
+        fn handle(&mut self, msg: Due, _: &mut Self::Context) {
+            self.fired.send((msg.0, Instant::now())).unwrap();
+        }
+    }
+
+    #[actix::test]
+    async fn past_and_future_deadlines() {
+        let (tx, mut rx) = unbounded_channel();
+        let start = Instant::now();
+        let future = start + Duration::from_millis(50);
+
+        let _addr = Scheduler {
+            deadlines: vec![("future", future), ("past", start - Duration::from_secs(1))],
+            fired: tx,
+        }
+        .start();
+
+        let (name, at) = rx.recv().await.unwrap();
+        assert_eq!(name, "past");
+        assert!(at < future);
+
+        let (name, at) = rx.recv().await.unwrap();
+        assert_eq!(name, "future");
+        assert!(at >= future);
+    }
+}
+
+mod notify_later_debounced {
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Changed(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Save(u32);
+
+    struct Store {
+        saved: UnboundedSender<(u32, Instant)>,
+    }
+
+    impl Actor for Store {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Changed> for Store {
+        type Result = ();
+
+        fn handle(&mut self, msg: Changed, ctx: &mut Self::Context) {
+            ctx.notify_later_debounced(Save(msg.0), Duration::from_millis(50), "save");
+        }
+    }
+
+    impl Handler<Save> for Store {
+        type Result = ();
+
+        fn handle(&mut self, msg: Save, _: &mut Self::Context) {
+            self.saved.send((msg.0, Instant::now())).unwrap();
+        }
+    }
+
+    #[actix::test]
+    async fn fires_once_after_quiet_period() {
+        let (tx, mut rx) = unbounded_channel();
+        let addr = Store { saved: tx }.start();
+
+        let mut last = Instant::now();
+        for i in 0..5 {
+            last = Instant::now();
+            addr.send(Changed(i)).await.unwrap();
+            sleep(Duration::from_millis(20)).await;
+        }
+
+        let (version, at) = rx.recv().await.unwrap();
+        assert_eq!(version, 4);
+        assert!(at >= last + Duration::from_millis(50));
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(rx.try_recv().is_err());
+    }
+}
+
+mod uptime {
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "Duration")]
+    struct GetUptime;
+
+    struct Timed;
+
+    impl Actor for Timed {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            assert!(ctx.uptime() < Duration::from_millis(50));
+        }
+    }
+
+    impl Handler<GetUptime> for Timed {
+        type Result = MessageResult<GetUptime>;
+
+        fn handle(&mut self, _: GetUptime, ctx: &mut Self::Context) -> Self::Result {
+            MessageResult(ctx.uptime())
+        }
+    }
+
+    #[actix::test]
+    async fn increases_over_time() {
+        let addr = Timed.start();
+
+        let before = addr.send(GetUptime).await.unwrap();
+        sleep(Duration::from_millis(50)).await;
+        let after = addr.send(GetUptime).await.unwrap();
+
+        assert!(after >= before + Duration::from_millis(50));
+    }
+}
+
+mod extensions {
+    use super::*;
+
+    struct RequestCount(usize);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Record;
+
+    #[derive(Message)]
+    #[rtype(result = "Option<usize>")]
+    struct Report;
+
+    struct Service;
+
+    impl Actor for Service {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Record> for Service {
+        type Result = ();
+
+        fn handle(&mut self, _: Record, ctx: &mut Self::Context) {
+            match ctx.extensions_mut().get_mut::<RequestCount>() {
+                Some(count) => count.0 += 1,
+                None => {
+                    ctx.extensions_mut().insert(RequestCount(1));
+                }
+            }
+        }
+    }
+
+    impl Handler<Report> for Service {
+        type Result = Option<usize>;
+
+        fn handle(&mut self, _: Report, ctx: &mut Self::Context) -> Option<usize> {
+            ctx.extensions().get::<RequestCount>().map(|count| count.0)
+        }
+    }
+
+    #[actix::test]
+    async fn shared_between_handlers() {
+        let addr = Service.start();
+        assert_eq!(addr.send(Report).await.unwrap(), None);
+
+        addr.send(Record).await.unwrap();
+        addr.send(Record).await.unwrap();
+        assert_eq!(addr.send(Report).await.unwrap(), Some(2));
+    }
+}
+
+mod notify_order {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        External(u32),
+        Notified(u32),
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct External(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Notified(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "Vec<Event>")]
+    struct Log;
+
+    #[derive(Default)]
+    struct Recorder {
+        log: Vec<Event>,
+    }
+
+    impl Actor for Recorder {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<External> for Recorder {
+        type Result = ();
+
+        fn handle(&mut self, msg: External, ctx: &mut Self::Context) {
+            self.log.push(Event::External(msg.0));
+            if msg.0 == 0 {
+                for i in 0..4 {
+                    ctx.notify(Notified(i));
+                }
+            }
+        }
+    }
+
+    impl Handler<Notified> for Recorder {
+        type Result = ();
+
+        fn handle(&mut self, msg: Notified, _: &mut Self::Context) {
+            self.log.push(Event::Notified(msg.0));
+        }
+    }
+
+    impl Handler<Log> for Recorder {
+        type Result = MessageResult<Log>;
+
+        fn handle(&mut self, _: Log, _: &mut Self::Context) -> Self::Result {
+            MessageResult(std::mem::take(&mut self.log))
+        }
+    }
+
+    #[actix::test]
+    async fn notifications_follow_queued_messages_in_order() {
+        let addr = Recorder::default().start();
+
+        // queued before the actor runs
+        for i in 0..3 {
+            addr.do_send(External(i));
+        }
+        sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(
+            addr.send(Log).await.unwrap(),
+            [
+                Event::External(0),
+                Event::External(1),
+                Event::External(2),
+                Event::Notified(0),
+                Event::Notified(1),
+                Event::Notified(2),
+                Event::Notified(3),
+            ]
+        );
+    }
+}
+
+mod interceptors {
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "(usize, usize)")]
+    struct Ping;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Forbidden;
+
+    #[derive(Default)]
+    struct Guarded {
+        seen: usize,
+        handled: usize,
+    }
+
+    impl Actor for Guarded {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.add_interceptor(|act, _, _| {
+                act.seen += 1;
+                true
+            });
+            ctx.add_interceptor(|_, msg, _| !msg.is::<Forbidden>());
+        }
+    }
+
+    impl Handler<Ping> for Guarded {
+        type Result = MessageResult<Ping>;
+
+        fn handle(&mut self, _: Ping, _: &mut Self::Context) -> Self::Result {
+            self.handled += 1;
+            MessageResult((self.seen, self.handled))
+        }
+    }
+
+    impl Handler<Forbidden> for Guarded {
+        type Result = ();
+
+        fn handle(&mut self, _: Forbidden, _: &mut Self::Context) {
+            self.handled += 1;
+        }
+    }
+
+    #[actix::test]
+    async fn interceptors_count_and_drop_messages() {
+        let addr = Guarded::default().start();
+
+        assert_eq!(addr.send(Ping).await.unwrap(), (1, 1));
+        assert_eq!(addr.send(Forbidden).await, Err(MailboxError::Closed));
+        addr.do_send(Forbidden);
+
+        // dropped messages are still counted, as the counting interceptor runs first
+        assert_eq!(addr.send(Ping).await.unwrap(), (4, 2));
+    }
+}
+
+mod item_timeout {
+    use futures_util::stream;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        Item(u32),
+        SlowDone,
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "Vec<Event>")]
+    struct Log;
+
+    struct Consumer {
+        timeout: Option<Duration>,
+        log: Vec<Event>,
+    }
+
+    impl Actor for Consumer {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let items = stream::iter(0..3u32);
+            match self.timeout {
+                Some(timeout) => ctx.add_stream_with_item_timeout(items, timeout),
+                None => ctx.add_stream(items),
+            };
+        }
+    }
+
+    impl StreamHandler<u32> for Consumer {
+        fn handle(&mut self, item: u32, ctx: &mut Self::Context) {
+            self.log.push(Event::Item(item));
+            if item == 0 {
+                ctx.wait(
+                    sleep(Duration::from_millis(200))
+                        .into_actor(self)
+                        .map(|_, act, _| {
+                            act.log.push(Event::SlowDone);
+                        }),
+                );
+            }
+        }
+
+        fn finished(&mut self, _: &mut Self::Context) {}
+    }
+
+    impl Handler<Log> for Consumer {
+        type Result = MessageResult<Log>;
+
+        fn handle(&mut self, _: Log, _: &mut Self::Context) -> Self::Result {
+            MessageResult(std::mem::take(&mut self.log))
+        }
+    }
+
+    async fn log_after(addr: &Addr<Consumer>, dur: Duration) -> Vec<Event> {
+        sleep(dur).await;
+        addr.send(Log).await.unwrap()
+    }
+
+    #[actix::test]
+    async fn slow_item_stops_blocking_after_timeout() {
+        let addr = Consumer {
+            timeout: Some(Duration::from_millis(20)),
+            log: Vec::new(),
+        }
+        .start();
+
+        assert_eq!(
+            log_after(&addr, Duration::from_millis(100)).await,
+            [Event::Item(0), Event::Item(1), Event::Item(2)]
+        );
+        // the slow future still completes in the background
+        assert_eq!(
+            log_after(&addr, Duration::from_millis(200)).await,
+            [Event::SlowDone]
+        );
+    }
+
+    #[actix::test]
+    async fn without_timeout_slow_item_blocks_stream() {
+        let addr = Consumer {
+            timeout: None,
+            log: Vec::new(),
+        }
+        .start();
+
+        // the request is handled once the actor stops waiting, before the next item
+        assert_eq!(
+            log_after(&addr, Duration::from_millis(100)).await,
+            [Event::Item(0), Event::SlowDone]
+        );
+    }
+}
+
+mod log_dropped_on_stop {
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Stop;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Job;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Report;
+
+    struct Worker(bool);
+
+    impl Actor for Worker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.log_dropped_on_stop(self.0);
+        }
+    }
+
+    impl Handler<Stop> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+            ctx.stop();
+        }
+    }
+
+    impl Handler<Job> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, _: Job, _: &mut Self::Context) {}
+    }
+
+    impl Handler<Report> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, _: Report, _: &mut Self::Context) {}
+    }
+
+    async fn stop_with_queued(log: bool) -> Vec<String> {
+        captured_logs::install();
+
+        let addr = Worker(log).start();
+        addr.do_send(Stop);
+        for _ in 0..3 {
+            addr.do_send(Job);
+        }
+        addr.do_send(Report);
+        sleep(Duration::from_millis(20)).await;
+
+        assert!(!addr.connected());
+        captured_logs::current_thread()
+    }
+
+    #[actix::test]
+    async fn dropped_messages_are_logged() {
+        let logs = stop_with_queued(true).await;
+        assert_eq!(logs.len(), 1, "{:?}", logs);
+        assert!(
+            logs[0].contains("Worker stopped with 4 queued messages dropped"),
+            "{}",
+            logs[0]
+        );
+        assert!(
+            logs[0].contains("log_dropped_on_stop::Job x3"),
+            "{}",
+            logs[0]
+        );
+        assert!(
+            logs[0].ends_with("log_dropped_on_stop::Report x1"),
+            "{}",
+            logs[0]
+        );
+    }
+
+    #[actix::test]
+    async fn nothing_is_logged_by_default() {
+        assert!(stop_with_queued(false).await.is_empty());
+    }
+}
+
+mod wait_with_cleanup {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Event {
+        Done,
+        Cleanup,
+        Stopped,
+    }
+
+    struct Upload {
+        wait: Duration,
+        stop_after: Option<Duration>,
+        keep_running: bool,
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    impl Upload {
+        fn new(wait: Duration, events: &Arc<Mutex<Vec<Event>>>) -> Self {
+            Upload {
+                wait,
+                stop_after: None,
+                keep_running: false,
+                events: Arc::clone(events),
+            }
+        }
+    }
+
+    impl Actor for Upload {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.wait_with_cleanup(
+                sleep(self.wait)
+                    .into_actor(self)
+                    .map(|_, act, _| act.events.lock().unwrap().push(Event::Done)),
+                |act, _| act.events.lock().unwrap().push(Event::Cleanup),
+            );
+            // waits nest, so this one completes before the upload is polled again
+            if let Some(delay) = self.stop_after {
+                ctx.wait(sleep(delay).into_actor(self).map(|_, _, ctx| ctx.stop()));
+            }
+        }
+
+        fn stopping(&mut self, _: &mut Self::Context) -> Running {
+            if self.keep_running {
+                Running::Continue
+            } else {
+                Running::Stop
+            }
+        }
+
+        fn stopped(&mut self, _: &mut Self::Context) {
+            self.events.lock().unwrap().push(Event::Stopped);
+        }
+    }
+
+    #[actix::test]
+    async fn cleanup_runs_when_dropped_while_waiting() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let (_addr, fut) = Upload::into_future(|_| Upload::new(Duration::from_secs(10), &events));
+
+        let handle = actix_rt::spawn(fut);
+        sleep(Duration::from_millis(10)).await;
+        assert!(events.lock().unwrap().is_empty());
+
+        // aborting the task drops the actor while it waits, as an arbiter shutting down does
+        handle.abort();
+        let _ = handle.await;
+
+        assert_eq!(*events.lock().unwrap(), [Event::Cleanup, Event::Stopped]);
+    }
+
+    #[actix::test]
+    async fn cleanup_runs_when_stopped_while_waiting() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut upload = Upload::new(Duration::from_secs(10), &events);
+        upload.stop_after = Some(Duration::from_millis(10));
+        let addr = upload.start();
+
+        sleep(Duration::from_millis(30)).await;
+        assert!(!addr.connected());
+        assert_eq!(*events.lock().unwrap(), [Event::Cleanup, Event::Stopped]);
+    }
+
+    #[actix::test]
+    async fn upload_resumes_when_stopping_keeps_actor_running() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut upload = Upload::new(Duration::from_millis(20), &events);
+        upload.stop_after = Some(Duration::from_millis(10));
+        upload.keep_running = true;
+        let addr = upload.start();
+
+        sleep(Duration::from_millis(40)).await;
+        assert!(addr.connected());
+        assert_eq!(*events.lock().unwrap(), [Event::Done]);
+    }
+
+    /// Stops the actor from within the future it waits on.
+    struct SelfStopping {
+        keep_running: bool,
+        events: Arc<Mutex<Vec<Event>>>,
+    }
+
+    impl Actor for SelfStopping {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.wait_with_cleanup(
+                fut::ready(())
+                    .then(|(), act: &mut Self, ctx: &mut Self::Context| {
+                        ctx.stop();
+                        sleep(Duration::from_millis(20)).into_actor(act)
+                    })
+                    .map(|_, act, _| act.events.lock().unwrap().push(Event::Done)),
+                |act, _| act.events.lock().unwrap().push(Event::Cleanup),
+            );
+        }
+
+        fn stopping(&mut self, _: &mut Self::Context) -> Running {
+            if self.keep_running {
+                Running::Continue
+            } else {
+                Running::Stop
+            }
+        }
+
+        fn stopped(&mut self, _: &mut Self::Context) {
+            self.events.lock().unwrap().push(Event::Stopped);
+        }
+    }
+
+    #[actix::test]
+    async fn future_stopping_actor_is_resumed_if_stopping_continues() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let addr = SelfStopping {
+            keep_running: true,
+            events: Arc::clone(&events),
+        }
+        .start();
+
+        sleep(Duration::from_millis(40)).await;
+        assert!(addr.connected());
+        assert_eq!(*events.lock().unwrap(), [Event::Done]);
+    }
+
+    #[actix::test]
+    async fn future_stopping_actor_is_cleaned_up_if_stopping_agrees() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let addr = SelfStopping {
+            keep_running: false,
+            events: Arc::clone(&events),
+        }
+        .start();
+
+        sleep(Duration::from_millis(40)).await;
+        assert!(!addr.connected());
+        assert_eq!(*events.lock().unwrap(), [Event::Cleanup, Event::Stopped]);
+    }
+
+    #[actix::test]
+    async fn cleanup_is_skipped_when_future_completes() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let addr = Upload::new(Duration::from_millis(10), &events).start();
+
+        sleep(Duration::from_millis(30)).await;
+        assert_eq!(*events.lock().unwrap(), [Event::Done]);
+
+        drop(addr);
+        sleep(Duration::from_millis(10)).await;
+        assert_eq!(*events.lock().unwrap(), [Event::Done, Event::Stopped]);
+    }
+}
+
+mod builder {
+    use std::thread;
+
+    use actix::ActorBuilder;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "String")]
+    struct Name;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Block(Duration);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Stop;
+
+    struct Configured;
+
+    impl Actor for Configured {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Name> for Configured {
+        type Result = String;
+
+        fn handle(&mut self, _: Name, ctx: &mut Self::Context) -> String {
+            ctx.name().to_owned()
+        }
+    }
+
+    impl Handler<Block> for Configured {
+        type Result = ();
+
+        fn handle(&mut self, msg: Block, _: &mut Self::Context) {
+            thread::sleep(msg.0);
+        }
+    }
+
+    impl Handler<Stop> for Configured {
+        type Result = ();
+
+        fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+            ctx.stop();
+        }
+    }
+
+    #[actix::test]
+    async fn settings_take_effect() {
+        captured_logs::install();
+
+        let addr = ActorBuilder::new(|_| Configured)
+            .name("configured")
+            .mailbox(2)
+            .slow_handler_threshold(Duration::from_millis(30))
+            .start();
+
+        // the actor has not run yet, so only the mailbox capacity limits queueing
+        let accepted = (0..10)
+            .take_while(|_| addr.try_send(Block(Duration::ZERO)).is_ok())
+            .count();
+        assert_eq!(accepted, 2);
+
+        assert_eq!(addr.send(Name).await.unwrap(), "configured");
+
+        addr.send(Block(Duration::from_millis(60))).await.unwrap();
+        let logs = captured_logs::current_thread();
+        assert_eq!(logs.len(), 1, "{:?}", logs);
+        assert!(logs[0].contains("builder::Configured took"), "{}", logs[0]);
+    }
+
+    #[actix::test]
+    async fn factory_sees_configured_context() {
+        let addr = ActorBuilder::new(|ctx: &mut Context<Configured>| {
+            assert_eq!(ctx.name(), "early");
+            Configured
+        })
+        .name("early")
+        .start_ready()
+        .await;
+
+        assert_eq!(addr.send(Name).await.unwrap(), "early");
+    }
+
+    #[actix::test]
+    async fn dropped_messages_are_logged() {
+        captured_logs::install();
+
+        let addr = ActorBuilder::new(|_| Configured)
+            .log_dropped_on_stop(true)
+            .start();
+        addr.do_send(Stop);
+        addr.do_send(Name);
+        sleep(Duration::from_millis(20)).await;
+
+        let logs = captured_logs::current_thread();
+        assert_eq!(logs.len(), 1, "{:?}", logs);
+        assert!(logs[0].ends_with("builder::Name x1"), "{}", logs[0]);
+    }
+}
+
+mod lifetime {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct Ephemeral {
+        lifetimes: Vec<Duration>,
+        stopped_after: Arc<Mutex<Option<Duration>>>,
+    }
+
+    impl Actor for Ephemeral {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            for dur in &self.lifetimes {
+                ctx.set_lifetime(*dur);
+            }
+        }
+
+        fn stopped(&mut self, ctx: &mut Self::Context) {
+            *self.stopped_after.lock().unwrap() = Some(ctx.uptime());
+        }
+    }
+
+    fn start(lifetimes: Vec<Duration>) -> (Addr<Ephemeral>, Arc<Mutex<Option<Duration>>>) {
+        let stopped_after = Arc::new(Mutex::new(None));
+        let addr = Ephemeral {
+            lifetimes,
+            stopped_after: Arc::clone(&stopped_after),
+        }
+        .start();
+        (addr, stopped_after)
+    }
+
+    #[actix::test]
+    async fn idle_actor_stops_after_lifetime() {
+        let (addr, stopped_after) = start(vec![Duration::from_millis(50)]);
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(addr.connected());
+
+        sleep(Duration::from_millis(100)).await;
+        assert!(!addr.connected());
+        let stopped_after = stopped_after.lock().unwrap().unwrap();
+        assert!(
+            stopped_after >= Duration::from_millis(50),
+            "{:?}",
+            stopped_after
+        );
+    }
+
+    #[actix::test]
+    async fn setting_lifetime_again_replaces_it() {
+        let (addr, stopped_after) = start(vec![Duration::from_millis(20), Duration::from_secs(10)]);
+
+        sleep(Duration::from_millis(60)).await;
+        assert!(addr.connected());
+        assert!(stopped_after.lock().unwrap().is_none());
+    }
+}
+
+mod tokio_receiver {
+    use tokio::sync::{mpsc, oneshot};
+
+    use super::*;
+
+    struct Collector {
+        rx: Option<mpsc::Receiver<u32>>,
+        items: Vec<u32>,
+        done: Option<oneshot::Sender<Vec<u32>>>,
+    }
+
+    impl Actor for Collector {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.add_tokio_receiver(self.rx.take().unwrap());
+        }
+    }
+
+    impl StreamHandler<u32> for Collector {
+        fn handle(&mut self, item: u32, _: &mut Self::Context) {
+            self.items.push(item);
+        }
+
+        fn finished(&mut self, ctx: &mut Self::Context) {
+            let items = std::mem::take(&mut self.items);
+            let _ = self.done.take().unwrap().send(items);
+            ctx.stop();
+        }
+    }
+
+    #[actix::test]
+    async fn items_are_handled_until_senders_drop() {
+        let (tx, rx) = mpsc::channel(2);
+        let (done_tx, done_rx) = oneshot::channel();
+        let addr = Collector {
+            rx: Some(rx),
+            items: Vec::new(),
+            done: Some(done_tx),
+        }
+        .start();
+
+        // more items than the channel buffers, so the actor has to keep taking them
+        for item in 0..5 {
+            tx.send(item).await.unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(done_rx.await.unwrap(), [0, 1, 2, 3, 4]);
+        sleep(Duration::from_millis(10)).await;
+        assert!(!addr.connected());
+    }
+}
+
+mod stop_token {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Stop;
+
+    struct Worker {
+        // iterations of the background loop
+        ticks: Arc<AtomicUsize>,
+        done: Option<oneshot::Sender<()>>,
+    }
+
+    impl Actor for Worker {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            let token = ctx.stop_token();
+            let ticks = Arc::clone(&self.ticks);
+            let done = self.done.take().unwrap();
+            actix_rt::spawn(async move {
+                while !token.is_stopped() {
+                    ticks.fetch_add(1, Ordering::SeqCst);
+                    sleep(Duration::from_millis(5)).await;
+                }
+                let _ = done.send(());
+            });
+        }
+    }
+
+    impl Handler<Stop> for Worker {
+        type Result = ();
+
+        fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+            ctx.stop();
+        }
+    }
+
+    #[actix::test]
+    async fn spawned_loop_ends_on_stop() {
+        let ticks = Arc::new(AtomicUsize::new(0));
+        let (done_tx, mut done_rx) = oneshot::channel();
+        let addr = Worker {
+            ticks: Arc::clone(&ticks),
+            done: Some(done_tx),
+        }
+        .start();
+
+        sleep(Duration::from_millis(20)).await;
+        assert!(ticks.load(Ordering::SeqCst) > 0);
+        assert!(done_rx.try_recv().is_err());
+
+        addr.send(Stop).await.unwrap();
+        done_rx.await.unwrap();
+    }
+
+    struct Idle;
+
+    impl Actor for Idle {
+        type Context = Context<Self>;
+    }
+
+    #[actix::test]
+    async fn awaiting_clones_wake_when_addresses_drop() {
+        let (token_tx, token_rx) = oneshot::channel();
+        let addr = Idle::create(|ctx| {
+            let _ = token_tx.send(ctx.stop_token());
+            Idle
+        });
+        let token = token_rx.await.unwrap();
+        let waiters: Vec<_> = (0..2).map(|_| actix_rt::spawn(token.clone())).collect();
+
+        sleep(Duration::from_millis(10)).await;
+        assert!(!token.is_stopped());
+
+        drop(addr);
+        for waiter in waiters {
+            waiter.await.unwrap();
+        }
+        assert!(token.is_stopped());
+    }
+}
+
+mod idle_lifetime {
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Touch;
+
+    struct Session;
+
+    impl Actor for Session {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            ctx.set_idle_lifetime(Duration::from_millis(50));
+        }
+    }
+
+    impl Handler<Touch> for Session {
+        type Result = ();
+
+        fn handle(&mut self, _: Touch, _: &mut Self::Context) {}
+    }
+
+    #[actix::test]
+    async fn activity_keeps_actor_alive() {
+        let addr = Session.start();
+
+        // three times the idle lifetime, but never idle for long
+        for _ in 0..15 {
+            sleep(Duration::from_millis(10)).await;
+            addr.send(Touch).await.unwrap();
+        }
+        assert!(addr.connected());
+
+        sleep(Duration::from_millis(150)).await;
+        assert!(!addr.connected());
+    }
+}
@@ -0,0 +1,117 @@
+use actix::{
+    dead_letter::{DeadLetter, DeadLetters},
+    prelude::*,
+};
+use tokio::sync::mpsc;
+
+struct Disconnect(u32);
+
+impl Message for Disconnect {
+    type Result = ();
+}
+
+struct Data(u32);
+
+impl Message for Data {
+    type Result = ();
+}
+
+struct Session;
+
+impl Actor for Session {
+    type Context = Context<Self>;
+}
+
+impl Handler<Disconnect> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _: Disconnect, _: &mut Self::Context) {}
+}
+
+impl Handler<Data> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _: Data, _: &mut Self::Context) {}
+}
+
+struct Audit(mpsc::UnboundedSender<String>);
+
+impl Actor for Audit {
+    type Context = Context<Self>;
+}
+
+impl Handler<DeadLetter<Disconnect>> for Audit {
+    type Result = ();
+
+    fn handle(&mut self, msg: DeadLetter<Disconnect>, _: &mut Self::Context) {
+        let _ = self.0.send(format!("disconnect {}", (msg.0).0));
+    }
+}
+
+impl Handler<DeadLetter<Data>> for Audit {
+    type Result = ();
+
+    fn handle(&mut self, msg: DeadLetter<Data>, _: &mut Self::Context) {
+        let _ = self.0.send(format!("data {}", (msg.0).0));
+    }
+}
+
+fn stopped_session() -> Addr<Session> {
+    // the context is never run, so its mailbox closes once it is dropped
+    Context::<Session>::new().address()
+}
+
+#[test]
+fn dead_letters_reach_handler_for_their_type() {
+    System::new().block_on(async {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let audit = Audit(tx).start();
+        DeadLetters::set_handler_for::<Disconnect>(audit.clone().recipient());
+
+        let session = stopped_session();
+        session.do_send(Data(1));
+        session.do_send(Disconnect(2));
+        session.clone().recipient().do_send(Disconnect(3));
+
+        // messages to live actors are not dead letters
+        Session.start().do_send(Disconnect(4));
+
+        audit.send(DeadLetter(Data(5))).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "disconnect 2");
+        assert_eq!(rx.recv().await.unwrap(), "disconnect 3");
+        assert_eq!(rx.recv().await.unwrap(), "data 5");
+
+        assert!(DeadLetters::remove_handler_for::<Disconnect>());
+        assert!(!DeadLetters::remove_handler_for::<Disconnect>());
+        session.do_send(Disconnect(6));
+        audit.send(DeadLetter(Data(7))).await.unwrap();
+        assert_eq!(rx.recv().await.unwrap(), "data 7");
+    });
+}
+
+#[test]
+fn bounded_send_to_stopped_actor_is_dead_letter() {
+    System::new().block_on(async {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let audit = Audit(tx).start();
+        DeadLetters::set_handler_for::<Data>(audit.clone().recipient());
+
+        stopped_session().do_send_bounded(Data(1), |_| panic!("mailbox is not full"));
+        assert_eq!(rx.recv().await.unwrap(), "data 1");
+        assert!(DeadLetters::remove_handler_for::<Data>());
+    });
+}
+
+#[test]
+fn handler_does_not_keep_its_actor_alive() {
+    System::new().block_on(async {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        DeadLetters::set_handler_for::<Disconnect>(Audit(tx).start().recipient());
+
+        // the audit actor stops without addresses, dropping its end of the channel
+        assert_eq!(rx.recv().await, None);
+
+        stopped_session().do_send(Disconnect(1));
+        assert!(!DeadLetters::remove_handler_for::<Disconnect>());
+    });
+}
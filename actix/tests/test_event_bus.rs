@@ -0,0 +1,183 @@
+#![cfg(feature = "macros")]
+
+use std::time::Duration;
+
+use actix::{
+    actors::event_bus::{EventBus, Publish, PublishLater, Subscribe, SubscriberCount, Unsubscribe},
+    prelude::*,
+};
+use actix_rt::time::sleep;
+use tokio::sync::mpsc;
+
+#[derive(Clone)]
+struct Event(u32);
+
+impl Message for Event {
+    type Result = ();
+}
+
+struct Stop;
+
+impl Message for Stop {
+    type Result = ();
+}
+
+struct Listener(mpsc::UnboundedSender<u32>);
+
+impl Actor for Listener {
+    type Context = Context<Self>;
+}
+
+impl Handler<Event> for Listener {
+    type Result = ();
+
+    fn handle(&mut self, msg: Event, _: &mut Self::Context) {
+        let _ = self.0.send(msg.0);
+    }
+}
+
+impl Handler<Stop> for Listener {
+    type Result = ();
+
+    fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+#[actix::test]
+async fn subscribe_and_publish() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::default().start();
+    let listener = Listener(tx).start().recipient();
+
+    bus.send(Subscribe(listener.clone())).await.unwrap();
+    bus.send(Subscribe(listener)).await.unwrap();
+
+    assert_eq!(bus.send(Publish(Event(1))).await, Ok(1));
+    assert_eq!(rx.recv().await, Some(1));
+}
+
+#[actix::test]
+async fn unsubscribe() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::default().start();
+    let first = Listener(tx.clone()).start().recipient();
+    let second = Listener(tx).start().recipient();
+
+    bus.do_send(Subscribe(first.clone()));
+    bus.do_send(Subscribe(second));
+    bus.do_send(Unsubscribe(first));
+
+    assert_eq!(bus.send(Publish(Event(2))).await, Ok(1));
+    assert_eq!(rx.recv().await, Some(2));
+}
+
+#[actix::test]
+async fn stopped_subscriber_is_pruned() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::default().start();
+    let alive = Listener(tx.clone()).start();
+    let dying = Listener(tx).start();
+
+    bus.do_send(Subscribe(alive.recipient()));
+    bus.do_send(Subscribe(dying.clone().recipient()));
+    assert_eq!(bus.send(Publish(Event(3))).await, Ok(2));
+    assert_eq!(rx.recv().await, Some(3));
+    assert_eq!(rx.recv().await, Some(3));
+
+    dying.send(Stop).await.unwrap();
+    while dying.connected() {
+        actix_rt::task::yield_now().await;
+    }
+
+    assert_eq!(bus.send(Publish(Event(4))).await, Ok(1));
+    assert_eq!(rx.recv().await, Some(4));
+}
+
+#[actix::test]
+async fn publish_later_goes_to_subscribers_at_delivery() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let (late_tx, mut late_rx) = mpsc::unbounded_channel();
+    let (left_tx, mut left_rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::default().start();
+    let staying = Listener(tx).start().recipient();
+    let leaving = Listener(left_tx).start().recipient();
+    let late = Listener(late_tx).start().recipient();
+
+    bus.do_send(Subscribe(staying));
+    bus.do_send(Subscribe(leaving.clone()));
+    bus.send(PublishLater(Event(5), Duration::from_millis(20)))
+        .await
+        .unwrap();
+
+    bus.do_send(Unsubscribe(leaving));
+    bus.send(Subscribe(late)).await.unwrap();
+    assert!(rx.try_recv().is_err());
+
+    assert_eq!(rx.recv().await, Some(5));
+    assert_eq!(late_rx.recv().await, Some(5));
+
+    sleep(Duration::from_millis(20)).await;
+    assert!(left_rx.try_recv().is_err());
+}
+
+#[actix::test]
+async fn sweep_removes_stopped_subscribers() {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::new()
+        .sweep_interval(Duration::from_millis(20))
+        .start();
+    let listeners: Vec<_> = (0..4).map(|_| Listener(tx.clone()).start()).collect();
+
+    for listener in &listeners {
+        bus.do_send(Subscribe(listener.clone().recipient()));
+    }
+    assert_eq!(bus.send(SubscriberCount).await, Ok(4));
+
+    for listener in &listeners[..3] {
+        listener.send(Stop).await.unwrap();
+    }
+    while listeners[..3].iter().any(Addr::connected) {
+        actix_rt::task::yield_now().await;
+    }
+
+    // nothing is published, so only the sweep removes them
+    sleep(Duration::from_millis(40)).await;
+    assert_eq!(bus.send(SubscriberCount).await, Ok(1));
+}
+
+#[actix::test]
+async fn late_subscriber_gets_replay() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::with_replay(2).start();
+
+    for n in 1..=3 {
+        bus.send(Publish(Event(n))).await.unwrap();
+    }
+
+    let late = Listener(tx).start().recipient();
+    bus.do_send(Subscribe(late.clone()));
+    bus.do_send(Publish(Event(4)));
+    // subscribing again does not replay again
+    bus.send(Subscribe(late)).await.unwrap();
+
+    for expected in [2, 3, 4] {
+        assert_eq!(rx.recv().await, Some(expected));
+    }
+    sleep(Duration::from_millis(10)).await;
+    assert!(rx.try_recv().is_err());
+}
+
+#[actix::test]
+async fn no_replay_by_default() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let bus = EventBus::<Event>::default().start();
+
+    bus.send(Publish(Event(1))).await.unwrap();
+    bus.send(Subscribe(Listener(tx).start().recipient()))
+        .await
+        .unwrap();
+    bus.send(Publish(Event(2))).await.unwrap();
+
+    assert_eq!(rx.recv().await, Some(2));
+}
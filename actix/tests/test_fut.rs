@@ -209,6 +209,28 @@ impl Handler<TryFutureMsg> for MyStreamActor2 {
     }
 }
 
+struct FilterForEachMsg;
+
+impl Message for FilterForEachMsg {
+    type Result = usize;
+}
+
+impl Handler<FilterForEachMsg> for MyStreamActor2 {
+    type Result = ResponseActFuture<Self, usize>;
+
+    fn handle(&mut self, _: FilterForEachMsg, _: &mut Context<Self>) -> Self::Result {
+        futures_util::stream::iter(1..=10)
+            .into_actor(self)
+            .filter(|n, _, _| futures_util::future::ready(n % 2 == 0))
+            .for_each(|n, act, _| {
+                act.counter += n;
+                fut::ready(())
+            })
+            .map(|_, act, _| act.counter)
+            .boxed_local()
+    }
+}
+
 #[test]
 fn test_stream_timeout() {
     let timeout = Arc::new(AtomicBool::new(false));
@@ -249,6 +271,16 @@ fn test_stream_collect() {
     })
 }
 
+#[test]
+fn test_stream_filter_for_each() {
+    System::new().block_on(async {
+        let addr = MyStreamActor2 { counter: 0 }.start();
+        let res = addr.send(FilterForEachMsg).await.unwrap();
+
+        assert_eq!(res, 2 + 4 + 6 + 8 + 10);
+    })
+}
+
 #[test]
 fn test_try_future() {
     System::new().block_on(async {
@@ -257,3 +289,169 @@ fn test_try_future() {
         assert_eq!(res.err().unwrap(), 996u32);
     })
 }
+
+struct FlakyActor {
+    attempts: usize,
+}
+
+impl Actor for FlakyActor {
+    type Context = Context<Self>;
+}
+
+struct RetryMsg(usize);
+
+impl Message for RetryMsg {
+    type Result = Result<usize, usize>;
+}
+
+impl Handler<RetryMsg> for FlakyActor {
+    type Result = ResponseActFuture<Self, Result<usize, usize>>;
+
+    fn handle(&mut self, msg: RetryMsg, _: &mut Context<Self>) -> Self::Result {
+        let policy = fut::RetryPolicy::new(msg.0, Duration::from_millis(1)).multiplier(2);
+
+        // fails twice, then succeeds
+        fut::retry(policy, |act: &mut Self, _: &mut Context<Self>| {
+            act.attempts += 1;
+            let attempts = act.attempts;
+            async move {
+                sleep(Duration::from_millis(1)).await;
+                if attempts < 3 {
+                    Err(attempts)
+                } else {
+                    Ok(attempts)
+                }
+            }
+            .into_actor(act)
+        })
+        .boxed_local()
+    }
+}
+
+#[test]
+fn test_fut_retry() {
+    System::new().block_on(async {
+        let addr = FlakyActor { attempts: 0 }.start();
+        assert_eq!(addr.send(RetryMsg(3)).await.unwrap(), Ok(3));
+
+        let addr = FlakyActor { attempts: 0 }.start();
+        assert_eq!(addr.send(RetryMsg(2)).await.unwrap(), Err(2));
+    })
+}
+
+struct RaceActor {
+    finished: Vec<&'static str>,
+}
+
+impl Actor for RaceActor {
+    type Context = Context<Self>;
+}
+
+struct RaceMsg;
+
+impl Message for RaceMsg {
+    type Result = (&'static str, Vec<&'static str>);
+}
+
+impl Handler<RaceMsg> for RaceActor {
+    type Result = ResponseActFuture<Self, (&'static str, Vec<&'static str>)>;
+
+    fn handle(&mut self, _: RaceMsg, _: &mut Context<Self>) -> Self::Result {
+        let fast = sleep(Duration::from_millis(5))
+            .into_actor(self)
+            .map(|_, act: &mut Self, _| {
+                act.finished.push("fast");
+                "fast"
+            })
+            .boxed_local();
+        let slow = sleep(Duration::from_millis(50))
+            .into_actor(self)
+            .map(|_, act: &mut Self, _| {
+                act.finished.push("slow");
+                "slow"
+            })
+            .boxed_local();
+
+        fut::select(slow, fast)
+            .map(|res, act, _| match res {
+                // the slow future is dropped unfinished
+                futures_util::future::Either::Right((winner, _slow)) => {
+                    (winner, act.finished.clone())
+                }
+                futures_util::future::Either::Left(_) => panic!("slow future won"),
+            })
+            .boxed_local()
+    }
+}
+
+#[test]
+fn test_fut_select() {
+    System::new().block_on(async {
+        let addr = RaceActor {
+            finished: Vec::new(),
+        }
+        .start();
+
+        assert_eq!(addr.send(RaceMsg).await.unwrap(), ("fast", vec!["fast"]));
+    })
+}
+
+struct Crawler {
+    running: usize,
+    max_running: usize,
+    done: usize,
+}
+
+struct Crawl;
+
+impl Message for Crawl {
+    type Result = (usize, usize);
+}
+
+impl Actor for Crawler {
+    type Context = Context<Self>;
+}
+
+impl Handler<Crawl> for Crawler {
+    type Result = ResponseActFuture<Self, (usize, usize)>;
+
+    fn handle(&mut self, _: Crawl, ctx: &mut Self::Context) -> Self::Result {
+        let throttle = ctx.throttle(2);
+
+        let fetches = (0..10).map(|_| {
+            fut::ready(())
+                .then(|_, act: &mut Crawler, _| {
+                    act.running += 1;
+                    act.max_running = act.max_running.max(act.running);
+                    sleep(Duration::from_millis(5)).into_actor(act)
+                })
+                .map(|_, act, _| {
+                    act.running -= 1;
+                    act.done += 1;
+                })
+                .throttle(&throttle)
+        });
+        for fetch in fetches {
+            ctx.spawn(fetch);
+        }
+
+        sleep(Duration::from_millis(100))
+            .into_actor(self)
+            .map(|_, act, _| (act.done, act.max_running))
+            .boxed_local()
+    }
+}
+
+#[test]
+fn test_fut_throttle() {
+    System::new().block_on(async {
+        let addr = Crawler {
+            running: 0,
+            max_running: 0,
+            done: 0,
+        }
+        .start();
+
+        assert_eq!(addr.send(Crawl).await.unwrap(), (10, 2));
+    })
+}
@@ -0,0 +1,807 @@
+#![cfg(feature = "macros")]
+
+use std::{
+    cell::RefCell,
+    future::Future,
+    io,
+    pin::Pin,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context as StdContext, Poll, Wake, Waker},
+};
+
+use actix::{
+    io::{FramedWrite, Writer},
+    prelude::*,
+};
+use bytes::Bytes;
+use tokio::io::AsyncWrite;
+use tokio_util::codec::BytesCodec;
+
+/// Waker that counts how many times it was woken.
+#[derive(Default)]
+struct CountingWaker(AtomicUsize);
+
+impl CountingWaker {
+    fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Wake for CountingWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+/// `AsyncWrite` that appends everything to a shared buffer and counts flushes.
+#[derive(Clone, Default)]
+struct SharedBuf(Rc<RefCell<Vec<u8>>>, Rc<AtomicUsize>);
+
+impl SharedBuf {
+    fn flushes(&self) -> usize {
+        self.1.load(Ordering::SeqCst)
+    }
+}
+
+impl AsyncWrite for SharedBuf {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut StdContext<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        _: &mut StdContext<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        let mut written = 0;
+        for buf in bufs {
+            self.0.borrow_mut().extend_from_slice(buf);
+            written += buf.len();
+        }
+        Poll::Ready(Ok(written))
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        true
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+        self.1.fetch_add(1, Ordering::SeqCst);
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Line(Bytes);
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Flush;
+
+struct FramedActor {
+    framed: FramedWrite<Bytes, SharedBuf, BytesCodec>,
+}
+
+impl Actor for FramedActor {
+    type Context = Context<Self>;
+}
+
+impl actix::io::WriteHandler<io::Error> for FramedActor {}
+
+impl Handler<Line> for FramedActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Line, _: &mut Self::Context) {
+        self.framed.write(msg.0);
+    }
+}
+
+impl Handler<Flush> for FramedActor {
+    type Result = ();
+
+    fn handle(&mut self, _: Flush, _: &mut Self::Context) {
+        self.framed.flush();
+    }
+}
+
+#[test]
+fn framed_write_coalesces_wakes() {
+    let buf = SharedBuf::default();
+
+    let mut ctx = Context::new();
+    let framed = FramedWrite::new(buf.clone(), BytesCodec::new(), &mut ctx);
+    let addr = ctx.address();
+    let mut fut = ctx.into_future(FramedActor { framed });
+
+    let waker = Arc::new(CountingWaker::default());
+    let task_waker = Waker::from(Arc::clone(&waker));
+    let mut cx = StdContext::from_waker(&task_waker);
+
+    // first poll parks the writer
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+
+    for _ in 0..32 {
+        addr.do_send(Line(Bytes::from_static(b"ping")));
+    }
+    let before = waker.count();
+
+    // all 32 writes happen in one poll but the writer task is woken once
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    assert_eq!(waker.count() - before, 1);
+    assert_eq!(buf.0.borrow().len(), 32 * 4);
+}
+
+#[test]
+fn framed_write_flush() {
+    let buf = SharedBuf::default();
+
+    let mut ctx = Context::new();
+    let framed = FramedWrite::new(buf.clone(), BytesCodec::new(), &mut ctx);
+    let addr = ctx.address();
+    let mut fut = ctx.into_future(FramedActor { framed });
+
+    let waker = Arc::new(CountingWaker::default());
+    let task_waker = Waker::from(Arc::clone(&waker));
+    let mut cx = StdContext::from_waker(&task_waker);
+
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    let flushes = buf.flushes();
+
+    addr.do_send(Line(Bytes::from_static(b"ping")));
+    addr.do_send(Flush);
+    let before = waker.count();
+
+    // the flush wakes up the writer, which writes and flushes the data in the same poll
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+    assert_eq!(waker.count() - before, 1);
+    assert_eq!(&buf.0.borrow()[..], b"ping");
+    assert!(buf.flushes() > flushes);
+}
+
+enum Chunk {
+    Slice(&'static [u8]),
+    Bytes(Bytes),
+}
+
+impl Message for Chunk {
+    type Result = ();
+}
+
+struct WriterActor {
+    writer: Writer<SharedBuf, io::Error>,
+}
+
+impl Actor for WriterActor {
+    type Context = Context<Self>;
+}
+
+impl actix::io::WriteHandler<io::Error> for WriterActor {}
+
+impl Handler<Chunk> for WriterActor {
+    type Result = ();
+
+    fn handle(&mut self, msg: Chunk, _: &mut Self::Context) {
+        match msg {
+            Chunk::Slice(buf) => self.writer.write(buf),
+            Chunk::Bytes(buf) => self.writer.write_bytes(buf),
+        }
+    }
+}
+
+#[test]
+fn writer_write_bytes_keeps_order() {
+    let buf = SharedBuf::default();
+
+    let mut ctx = Context::new();
+    let writer = Writer::new(buf.clone(), &mut ctx);
+    let addr = ctx.address();
+    let mut fut = ctx.into_future(WriterActor { writer });
+
+    let waker = Arc::new(CountingWaker::default());
+    let task_waker = Waker::from(Arc::clone(&waker));
+    let mut cx = StdContext::from_waker(&task_waker);
+
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+
+    let payload = Bytes::from(vec![b'x'; 64 * 1024]);
+    addr.do_send(Chunk::Slice(b"head "));
+    addr.do_send(Chunk::Bytes(payload.clone()));
+    addr.do_send(Chunk::Slice(b" middle "));
+    addr.do_send(Chunk::Bytes(Bytes::from_static(b"tail")));
+
+    assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+
+    let mut expected = b"head ".to_vec();
+    expected.extend_from_slice(&payload);
+    expected.extend_from_slice(b" middle tail");
+    assert_eq!(&buf.0.borrow()[..], &expected[..]);
+}
+
+mod write_timeout {
+    use std::time::Duration;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    /// `AsyncWrite` of a peer that stopped reading.
+    struct StalledIo;
+
+    impl AsyncWrite for StalledIo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut StdContext<'_>,
+            _: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Pending
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Poll::Pending
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Poll::Pending
+        }
+    }
+
+    struct StalledActor {
+        writer: Writer<StalledIo, io::Error>,
+        error: Option<oneshot::Sender<io::ErrorKind>>,
+    }
+
+    impl Actor for StalledActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, _: &mut Self::Context) {
+            self.writer.set_write_timeout(Duration::from_millis(10));
+            self.writer.write(b"ping");
+        }
+    }
+
+    impl actix::io::WriteHandler<io::Error> for StalledActor {
+        fn error(&mut self, err: io::Error, _: &mut Self::Context) -> Running {
+            let _ = self.error.take().unwrap().send(err.kind());
+            Running::Stop
+        }
+    }
+
+    #[test]
+    fn stalled_writer_reports_timeout() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            StalledActor::create(|ctx| StalledActor {
+                writer: Writer::new(StalledIo, ctx),
+                error: Some(tx),
+            });
+
+            assert_eq!(rx.await.unwrap(), io::ErrorKind::TimedOut);
+        });
+    }
+
+    struct ClosingActor {
+        framed: FramedWrite<Bytes, StalledIo, BytesCodec>,
+        closed: Option<oneshot::Sender<(io::ErrorKind, bool)>>,
+        error: Option<io::ErrorKind>,
+    }
+
+    impl Actor for ClosingActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, _: &mut Self::Context) {
+            self.framed.write(Bytes::from_static(b"bye"));
+            self.framed.close_with_timeout(Duration::from_millis(20));
+        }
+    }
+
+    impl actix::io::WriteHandler<io::Error> for ClosingActor {
+        fn error(&mut self, err: io::Error, _: &mut Self::Context) -> Running {
+            self.error = Some(err.kind());
+            Running::Continue
+        }
+
+        fn errored(&mut self, ctx: &mut Self::Context) {
+            let closed = self.framed.closed();
+            let _ = self
+                .closed
+                .take()
+                .unwrap()
+                .send((self.error.unwrap(), closed));
+            ctx.stop();
+        }
+    }
+
+    #[test]
+    fn close_gives_up_after_timeout() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            ClosingActor::create(|ctx| ClosingActor {
+                framed: FramedWrite::new(StalledIo, BytesCodec::new(), ctx),
+                closed: Some(tx),
+                error: None,
+            });
+
+            assert_eq!(rx.await.unwrap(), (io::ErrorKind::TimedOut, true));
+        });
+    }
+}
+
+mod write_budget {
+    use super::*;
+
+    /// `AsyncWrite` that accepts at most 16 KiB per call.
+    #[derive(Clone, Default)]
+    struct SmallWrites(SharedBuf);
+
+    impl AsyncWrite for SmallWrites {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut StdContext<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            let len = buf.len().min(16 * 1024);
+            Pin::new(&mut self.get_mut().0).poll_write(cx, &buf[..len])
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().0).poll_flush(cx)
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, cx: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.get_mut().0).poll_shutdown(cx)
+        }
+    }
+
+    struct BigWriter {
+        writer: Writer<SmallWrites, io::Error>,
+    }
+
+    impl Actor for BigWriter {
+        type Context = Context<Self>;
+
+        fn started(&mut self, _: &mut Self::Context) {
+            self.writer.write(&vec![b'x'; 4 * 1024 * 1024]);
+        }
+    }
+
+    impl actix::io::WriteHandler<io::Error> for BigWriter {}
+
+    #[test]
+    fn large_buffer_is_written_over_several_polls() {
+        let io = SmallWrites::default();
+
+        let mut ctx = Context::new();
+        let writer = Writer::new(io.clone(), &mut ctx);
+        let _addr = ctx.address();
+        let mut fut = ctx.into_future(BigWriter { writer });
+
+        let waker = Arc::new(CountingWaker::default());
+        let task_waker = Waker::from(Arc::clone(&waker));
+        let mut cx = StdContext::from_waker(&task_waker);
+
+        // the writer yields after its budget and reschedules itself
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+        let first = io.0 .0.borrow().len();
+        assert!(first > 0 && first < 4 * 1024 * 1024);
+        assert_eq!(waker.count(), 1);
+
+        let mut polls = 1;
+        while io.0 .0.borrow().len() < 4 * 1024 * 1024 {
+            assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+            polls += 1;
+        }
+        assert!(polls > 1);
+        assert_eq!(io.0 .0.borrow().len(), 4 * 1024 * 1024);
+    }
+}
+
+mod encode_errors {
+    use bytes::{BufMut, BytesMut};
+    use tokio_util::codec::Encoder;
+
+    use super::*;
+
+    /// Writes lines, but fails on "bad" after having written part of it.
+    struct PickyCodec;
+
+    impl Encoder<&'static str> for PickyCodec {
+        type Error = io::Error;
+
+        fn encode(&mut self, item: &'static str, dst: &mut BytesMut) -> io::Result<()> {
+            dst.put_slice(item.as_bytes());
+            if item == "bad" {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "bad item"));
+            }
+            dst.put_u8(b'\n');
+            Ok(())
+        }
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Put(&'static str);
+
+    struct Lines {
+        framed: FramedWrite<&'static str, SharedBuf, PickyCodec>,
+        errors: Rc<RefCell<Vec<io::ErrorKind>>>,
+    }
+
+    impl Actor for Lines {
+        type Context = Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<io::Error> for Lines {
+        fn error(&mut self, err: io::Error, _: &mut Self::Context) -> Running {
+            self.errors.borrow_mut().push(err.kind());
+            Running::Continue
+        }
+    }
+
+    impl Handler<Put> for Lines {
+        type Result = ();
+
+        fn handle(&mut self, msg: Put, _: &mut Self::Context) {
+            self.framed.write(msg.0);
+        }
+    }
+
+    #[test]
+    fn failed_item_is_skipped() {
+        let buf = SharedBuf::default();
+        let errors = Rc::new(RefCell::new(Vec::new()));
+
+        let mut ctx = Context::new();
+        let framed = FramedWrite::new(buf.clone(), PickyCodec, &mut ctx);
+        let addr = ctx.address();
+        let mut fut = ctx.into_future(Lines {
+            framed,
+            errors: Rc::clone(&errors),
+        });
+
+        let waker = Arc::new(CountingWaker::default());
+        let task_waker = Waker::from(Arc::clone(&waker));
+        let mut cx = StdContext::from_waker(&task_waker);
+
+        for item in ["one", "bad", "two", "bad", "three"] {
+            addr.do_send(Put(item));
+        }
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+
+        assert_eq!(&buf.0.borrow()[..], b"one\ntwo\nthree\n");
+        assert_eq!(
+            *errors.borrow(),
+            [io::ErrorKind::InvalidInput, io::ErrorKind::InvalidInput]
+        );
+
+        // the writer keeps going
+        addr.do_send(Put("four"));
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_pending());
+        assert_eq!(&buf.0.borrow()[..], b"one\ntwo\nthree\nfour\n");
+    }
+}
+
+mod reply_after_flush {
+    use std::{cell::Cell, time::Duration};
+
+    use actix::io::ResponseAfterFlush;
+    use actix_rt::time::sleep;
+
+    use super::*;
+
+    /// `AsyncWrite` whose flush stays pending until the gate is opened.
+    #[derive(Clone, Default)]
+    struct GatedFlush {
+        buf: SharedBuf,
+        open: Rc<Cell<bool>>,
+        waker: Rc<RefCell<Option<Waker>>>,
+    }
+
+    impl GatedFlush {
+        fn open(&self) {
+            self.open.set(true);
+            if let Some(waker) = self.waker.borrow_mut().take() {
+                waker.wake();
+            }
+        }
+    }
+
+    impl AsyncWrite for GatedFlush {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            cx: &mut StdContext<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Pin::new(&mut self.get_mut().buf).poll_write(cx, buf)
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, cx: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            if self.open.get() {
+                Pin::new(&mut self.get_mut().buf).poll_flush(cx)
+            } else {
+                *self.waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[derive(Message)]
+    #[rtype(result = "u32")]
+    struct Ask(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Fail;
+
+    struct Server {
+        framed: FramedWrite<Bytes, GatedFlush, BytesCodec>,
+    }
+
+    impl Actor for Server {
+        type Context = Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<io::Error> for Server {
+        fn finished(&mut self, _: &mut Self::Context) {}
+    }
+
+    impl Handler<Ask> for Server {
+        type Result = ResponseAfterFlush<u32>;
+
+        fn handle(&mut self, msg: Ask, _: &mut Self::Context) -> Self::Result {
+            self.framed.write(Bytes::from(msg.0.to_string()));
+            self.framed.reply_after_flush(msg.0)
+        }
+    }
+
+    impl Handler<Fail> for Server {
+        type Result = ();
+
+        fn handle(&mut self, _: Fail, ctx: &mut Self::Context) {
+            // stops the writer task without stopping the actor
+            ctx.cancel_future(self.framed.handle());
+        }
+    }
+
+    #[test]
+    fn reply_resolves_after_flush() {
+        System::new().block_on(async {
+            let io = GatedFlush::default();
+            let server = Server::create({
+                let io = io.clone();
+                |ctx| Server {
+                    framed: FramedWrite::new(io, BytesCodec::new(), ctx),
+                }
+            });
+
+            let reply = actix_rt::spawn(server.send(Ask(42)));
+            sleep(Duration::from_millis(20)).await;
+            // written out, but not flushed yet
+            assert_eq!(&io.buf.0.borrow()[..], b"42");
+            assert!(!reply.is_finished());
+
+            io.open();
+            assert_eq!(reply.await.unwrap(), Ok(42));
+        });
+    }
+
+    #[test]
+    fn reply_fails_if_writer_stops_first() {
+        System::new().block_on(async {
+            let io = GatedFlush::default();
+            let server = Server::create({
+                let io = io.clone();
+                |ctx| Server {
+                    framed: FramedWrite::new(io, BytesCodec::new(), ctx),
+                }
+            });
+
+            let reply = server.send(Ask(7));
+            server.send(Fail).await.unwrap();
+            assert_eq!(reply.await, Err(MailboxError::Closed));
+        });
+    }
+}
+
+mod zero_writes {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    /// `AsyncWrite` that never accepts any bytes, counting the attempts.
+    #[derive(Clone, Default)]
+    struct ZeroIo(Rc<AtomicUsize>);
+
+    impl AsyncWrite for ZeroIo {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _: &mut StdContext<'_>,
+            _: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Poll::Ready(Ok(0))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _: &mut StdContext<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    struct ZeroActor {
+        writer: Writer<ZeroIo, io::Error>,
+        error: Option<oneshot::Sender<io::ErrorKind>>,
+    }
+
+    impl Actor for ZeroActor {
+        type Context = Context<Self>;
+
+        fn started(&mut self, _: &mut Self::Context) {
+            self.writer.set_max_zero_writes(3);
+            self.writer.write(b"ping");
+        }
+    }
+
+    impl actix::io::WriteHandler<io::Error> for ZeroActor {
+        fn error(&mut self, err: io::Error, _: &mut Self::Context) -> Running {
+            let _ = self.error.take().unwrap().send(err.kind());
+            Running::Stop
+        }
+    }
+
+    #[test]
+    fn repeated_zero_writes_are_reported() {
+        System::new().block_on(async {
+            let io = ZeroIo::default();
+            let (tx, rx) = oneshot::channel();
+            ZeroActor::create({
+                let io = io.clone();
+                |ctx| ZeroActor {
+                    writer: Writer::new(io, ctx),
+                    error: Some(tx),
+                }
+            });
+
+            assert_eq!(rx.await.unwrap(), io::ErrorKind::WriteZero);
+            assert_eq!(io.0.load(Ordering::SeqCst), 3);
+        });
+    }
+}
+
+mod framed_reader {
+    use std::collections::VecDeque;
+
+    use actix::io::FramedReadActor;
+    use tokio::{io::ReadBuf, sync::oneshot};
+    use tokio_util::codec::{LinesCodec, LinesCodecError};
+
+    use super::*;
+
+    /// `AsyncRead` returning one chunk per read, or `Pending` for a `None` chunk.
+    struct Chunks(VecDeque<Option<&'static [u8]>>);
+
+    impl Chunks {
+        fn new(chunks: &[Option<&'static [u8]>]) -> Self {
+            Chunks(chunks.iter().copied().collect())
+        }
+    }
+
+    impl tokio::io::AsyncRead for Chunks {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            cx: &mut StdContext<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            let chunks = &mut self.get_mut().0;
+            match chunks.pop_front() {
+                Some(Some(chunk)) => {
+                    let len = chunk.len().min(buf.remaining());
+                    buf.put_slice(&chunk[..len]);
+                    if len < chunk.len() {
+                        chunks.push_front(Some(&chunk[len..]));
+                    }
+                    Poll::Ready(Ok(()))
+                }
+                Some(None) => {
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+                // end of input
+                None => Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    struct Lines {
+        io: Option<Chunks>,
+        lines: Vec<String>,
+        done: Option<oneshot::Sender<Vec<String>>>,
+    }
+
+    impl Actor for Lines {
+        type Context = Context<Self>;
+
+        fn started(&mut self, ctx: &mut Self::Context) {
+            Self::add_framed_read(ctx, self.io.take().unwrap(), LinesCodec::new());
+        }
+    }
+
+    impl StreamHandler<Result<String, LinesCodecError>> for Lines {
+        fn handle(&mut self, line: Result<String, LinesCodecError>, _: &mut Self::Context) {
+            self.lines.push(line.unwrap());
+        }
+
+        fn finished(&mut self, ctx: &mut Self::Context) {
+            let lines = std::mem::take(&mut self.lines);
+            let _ = self.done.take().unwrap().send(lines);
+            ctx.stop();
+        }
+    }
+
+    #[test]
+    fn frames_split_across_reads() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            Lines {
+                io: Some(Chunks::new(&[
+                    Some(b"hel"),
+                    None,
+                    Some(b"lo\nwo"),
+                    Some(b"rld\nla"),
+                    None,
+                    Some(b"st"),
+                ])),
+                lines: Vec::new(),
+                done: Some(tx),
+            }
+            .start();
+
+            assert_eq!(rx.await.unwrap(), ["hello", "world", "last"]);
+        });
+    }
+
+    #[test]
+    fn partial_frame_is_retained() {
+        System::new().block_on(async {
+            let (tx, rx) = oneshot::channel();
+            Lines {
+                // the reader is pending while only part of the second line arrived
+                io: Some(Chunks::new(&[
+                    Some(b"first\nsec"),
+                    None,
+                    Some(b"ond line\n"),
+                ])),
+                lines: Vec::new(),
+                done: Some(tx),
+            }
+            .start();
+
+            assert_eq!(rx.await.unwrap(), ["first", "second line"]);
+        });
+    }
+}
@@ -8,8 +8,12 @@ use std::{
     time::Duration,
 };
 
-use actix::prelude::*;
+use actix::{
+    lifecycle::{lifecycle_events, LifecycleEvent, LifecycleStage},
+    prelude::*,
+};
 use actix_rt::time::sleep;
+use futures_util::stream::StreamExt as _;
 use tokio::sync::oneshot::{channel, Sender};
 
 struct MyActor {
@@ -277,3 +281,49 @@ fn test_stop_restore_after_stopping() {
     assert!(stopping.load(Ordering::Relaxed), "Not stopping");
     assert!(!stopped.load(Ordering::Relaxed), "Stopped");
 }
+
+struct ShortLived;
+
+impl Actor for ShortLived {
+    type Context = actix::Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+#[test]
+fn test_lifecycle_events() {
+    let sys = System::new();
+
+    sys.block_on(async {
+        let mut events = lifecycle_events();
+
+        ShortLived.start_named("short-lived");
+
+        let mut stages = Vec::new();
+        while let Some(event) = events.next().await {
+            let LifecycleEvent {
+                stage,
+                actor_type,
+                name,
+            } = event;
+            assert!(actor_type.ends_with("ShortLived"));
+            assert_eq!(name.as_deref(), Some("short-lived"));
+
+            stages.push(stage);
+            if stage == LifecycleStage::Stopped {
+                break;
+            }
+        }
+
+        assert_eq!(
+            stages,
+            [
+                LifecycleStage::Started,
+                LifecycleStage::Stopping,
+                LifecycleStage::Stopped
+            ]
+        );
+    });
+}
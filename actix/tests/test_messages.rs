@@ -131,3 +131,225 @@ async fn test_different_message_result_types() {
         "Session with id `1` should already have been inserted"
     );
 }
+
+mod behavior {
+    use actix::Behavior;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "Result<u32, &'static str>")]
+    struct Deposit(u32);
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Freeze;
+
+    #[derive(Message)]
+    #[rtype(result = "()")]
+    struct Thaw;
+
+    struct Account {
+        balance: u32,
+        on_deposit: Behavior<Account, Deposit>,
+    }
+
+    impl Account {
+        fn open(&mut self, msg: Deposit, _: &mut Context<Self>) -> Result<u32, &'static str> {
+            self.balance += msg.0;
+            Ok(self.balance)
+        }
+
+        fn frozen(&mut self, _: Deposit, _: &mut Context<Self>) -> Result<u32, &'static str> {
+            Err("account is frozen")
+        }
+    }
+
+    impl Actor for Account {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Deposit> for Account {
+        type Result = Result<u32, &'static str>;
+
+        fn handle(&mut self, msg: Deposit, ctx: &mut Self::Context) -> Self::Result {
+            (self.on_deposit.handler())(self, msg, ctx)
+        }
+    }
+
+    impl Handler<Freeze> for Account {
+        type Result = ();
+
+        fn handle(&mut self, _: Freeze, _: &mut Self::Context) {
+            self.on_deposit.set(Account::frozen);
+        }
+    }
+
+    impl Handler<Thaw> for Account {
+        type Result = ();
+
+        fn handle(&mut self, _: Thaw, _: &mut Self::Context) {
+            self.on_deposit.set(Account::open);
+        }
+    }
+
+    #[actix::test]
+    async fn transition_between_behaviors() {
+        let account = Account {
+            balance: 0,
+            on_deposit: Behavior::new(Account::open),
+        }
+        .start();
+
+        assert_eq!(account.send(Deposit(5)).await.unwrap(), Ok(5));
+
+        account.do_send(Freeze);
+        assert_eq!(
+            account.send(Deposit(5)).await.unwrap(),
+            Err("account is frozen")
+        );
+
+        // state is kept across transitions
+        account.do_send(Thaw);
+        assert_eq!(account.send(Deposit(5)).await.unwrap(), Ok(10));
+    }
+}
+
+mod unboxed_response {
+    use std::{
+        future::{ready, Ready},
+        time::Duration,
+    };
+
+    use actix::UnboxedResponse;
+    use actix_rt::time::sleep;
+    use tokio::sync::oneshot::{self, error::RecvError, Receiver};
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "usize")]
+    struct Double(usize);
+
+    #[derive(Message)]
+    #[rtype(result = "Result<usize, RecvError>")]
+    struct DoubleLater(usize);
+
+    struct Doubler;
+
+    impl Actor for Doubler {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<Double> for Doubler {
+        type Result = UnboxedResponse<Ready<usize>>;
+
+        fn handle(&mut self, msg: Double, _: &mut Self::Context) -> Self::Result {
+            UnboxedResponse(ready(msg.0 * 2))
+        }
+    }
+
+    impl Handler<DoubleLater> for Doubler {
+        type Result = UnboxedResponse<Receiver<usize>>;
+
+        fn handle(&mut self, msg: DoubleLater, _: &mut Self::Context) -> Self::Result {
+            let (tx, rx) = oneshot::channel();
+            actix_rt::spawn(async move {
+                sleep(Duration::from_millis(10)).await;
+                tx.send(msg.0 * 2).unwrap();
+            });
+            UnboxedResponse(rx)
+        }
+    }
+
+    #[actix::test]
+    async fn ready_future() {
+        let addr = Doubler.start();
+        assert_eq!(addr.send(Double(21)).await.unwrap(), 42);
+    }
+
+    #[actix::test]
+    async fn pending_future() {
+        let addr = Doubler.start();
+        assert_eq!(addr.send(DoubleLater(21)).await.unwrap().unwrap(), 42);
+    }
+}
+
+mod blocking_response {
+    use std::{thread, time::Duration};
+
+    use actix::{respond_blocking, BlockingResponse};
+    use futures_util::FutureExt as _;
+
+    use super::*;
+
+    #[derive(Message)]
+    #[rtype(result = "u64")]
+    struct SlowSum(Vec<u64>);
+
+    #[derive(Message)]
+    #[rtype(result = "u64")]
+    struct Fail;
+
+    #[derive(Message)]
+    #[rtype(result = "usize")]
+    struct Ping;
+
+    struct Offloader {
+        pings: usize,
+    }
+
+    impl Actor for Offloader {
+        type Context = Context<Self>;
+    }
+
+    impl Handler<SlowSum> for Offloader {
+        type Result = BlockingResponse<u64>;
+
+        fn handle(&mut self, msg: SlowSum, _: &mut Self::Context) -> Self::Result {
+            respond_blocking(move || {
+                thread::sleep(Duration::from_millis(50));
+                msg.0.iter().sum()
+            })
+        }
+    }
+
+    impl Handler<Fail> for Offloader {
+        type Result = BlockingResponse<u64>;
+
+        fn handle(&mut self, _: Fail, _: &mut Self::Context) -> Self::Result {
+            respond_blocking(|| panic!("computation failed"))
+        }
+    }
+
+    impl Handler<Ping> for Offloader {
+        type Result = usize;
+
+        fn handle(&mut self, _: Ping, _: &mut Self::Context) -> usize {
+            self.pings += 1;
+            self.pings
+        }
+    }
+
+    #[actix::test]
+    async fn result_is_delivered() {
+        let addr = Offloader { pings: 0 }.start();
+
+        let mut sum = std::pin::pin!(addr.send(SlowSum(vec![1, 2, 3, 4])));
+        let ping = addr.send(Ping);
+
+        // the actor keeps handling messages while the sum is computed
+        assert_eq!(ping.await.unwrap(), 1);
+        assert!(sum.as_mut().now_or_never().is_none());
+
+        assert_eq!(sum.await.unwrap(), 10);
+    }
+
+    #[actix::test]
+    async fn panic_closes_request() {
+        let addr = Offloader { pings: 0 }.start();
+
+        assert_eq!(addr.send(Fail).await, Err(MailboxError::Closed));
+        assert_eq!(addr.send(Ping).await.unwrap(), 1);
+    }
+}
@@ -0,0 +1,94 @@
+use std::sync::{Arc, Mutex};
+
+use actix::{prelude::*, ActorPool};
+
+struct Job;
+
+impl Message for Job {
+    type Result = usize;
+}
+
+struct Worker {
+    id: usize,
+    handled: Arc<Mutex<Vec<usize>>>,
+}
+
+impl Actor for Worker {
+    type Context = Context<Self>;
+}
+
+impl Handler<Job> for Worker {
+    type Result = usize;
+
+    fn handle(&mut self, _: Job, _: &mut Self::Context) -> usize {
+        self.handled.lock().unwrap().push(self.id);
+        self.id
+    }
+}
+
+fn pool(size: usize, handled: &Arc<Mutex<Vec<usize>>>) -> ActorPool<Worker> {
+    let mut id = 0;
+    ActorPool::new(size, || {
+        id += 1;
+        Worker {
+            id: id - 1,
+            handled: Arc::clone(handled),
+        }
+    })
+}
+
+fn count(handled: &Mutex<Vec<usize>>, id: usize) -> usize {
+    handled.lock().unwrap().iter().filter(|&&h| h == id).count()
+}
+
+#[actix::test]
+async fn test_pool_skips_loaded_worker() {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let pool = pool(3, &handled);
+
+    // nothing runs before the test yields, so these stay queued
+    for _ in 0..5 {
+        pool.workers()[0].do_send(Job);
+    }
+    assert_eq!(pool.workers()[0].mailbox_len(), 5);
+
+    for _ in 0..4 {
+        pool.do_send(Job);
+    }
+    assert_eq!(pool.workers()[1].mailbox_len(), 2);
+    assert_eq!(pool.workers()[2].mailbox_len(), 2);
+
+    for worker in pool.workers() {
+        worker.send(Job).await.unwrap();
+    }
+
+    assert_eq!(count(&handled, 0), 6);
+    assert_eq!(count(&handled, 1), 3);
+    assert_eq!(count(&handled, 2), 3);
+}
+
+#[actix::test]
+async fn test_pool_spreads_ties() {
+    let handled = Arc::new(Mutex::new(Vec::new()));
+    let pool = pool(3, &handled);
+
+    for _ in 0..6 {
+        pool.do_send(Job);
+    }
+    for worker in pool.workers() {
+        assert_eq!(worker.mailbox_len(), 2);
+    }
+
+    for worker in pool.workers() {
+        worker.send(Job).await.unwrap();
+    }
+    for id in 0..3 {
+        assert_eq!(count(&handled, id), 3);
+    }
+}
+
+#[test]
+#[should_panic(expected = "at least one actor")]
+fn test_empty_pool() {
+    ActorPool::<Worker>::from_addrs(Vec::new());
+}
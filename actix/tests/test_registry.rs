@@ -0,0 +1,80 @@
+#![cfg(feature = "macros")]
+
+use actix::{prelude::*, SystemRegistry, Tracked};
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Stop;
+
+#[derive(Message)]
+#[rtype(result = "()")]
+struct Track;
+
+struct Session;
+
+impl Actor for Session {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        Self::track(ctx);
+    }
+}
+
+impl Tracked for Session {}
+
+impl Handler<Stop> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _: Stop, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+impl Handler<Track> for Session {
+    type Result = ();
+
+    fn handle(&mut self, _: Track, ctx: &mut Self::Context) {
+        Self::track(ctx);
+    }
+}
+
+async fn started() -> Addr<Session> {
+    Session.start_ready().await
+}
+
+#[actix::test]
+async fn stopped_instances_are_left_out() {
+    let sessions = [started().await, started().await, started().await];
+    assert_eq!(SystemRegistry::instances::<Session>().len(), 3);
+
+    sessions[1].send(Stop).await.unwrap();
+    while sessions[1].connected() {
+        actix_rt::task::yield_now().await;
+    }
+
+    let instances = SystemRegistry::instances::<Session>();
+    assert_eq!(instances.len(), 2);
+    for session in [&sessions[0], &sessions[2]] {
+        assert!(instances.contains(&session.downgrade()));
+    }
+}
+
+#[actix::test]
+async fn tracking_twice_has_no_effect() {
+    let session = started().await;
+    session.send(Track).await.unwrap();
+
+    assert_eq!(SystemRegistry::instances::<Session>().len(), 1);
+}
+
+#[test]
+fn instances_are_per_system() {
+    System::new().block_on(async {
+        let _session = started().await;
+        assert_eq!(SystemRegistry::instances::<Session>().len(), 1);
+    });
+
+    System::new().block_on(async {
+        assert!(SystemRegistry::instances::<Session>().is_empty());
+    });
+}
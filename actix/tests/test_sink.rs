@@ -304,3 +304,429 @@ async fn test_send_single_bytes() {
 
     assert_eq!(b"hi!", &res[..]);
 }
+
+mod reconnect {
+    use std::{cell::Cell, rc::Rc};
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    /// Sink that reports the items it receives and fails its first flush if `fail` is set.
+    struct FlakySink {
+        tx: mpsc::UnboundedSender<Bytes>,
+        fail: bool,
+    }
+
+    impl Sink<Bytes> for FlakySink {
+        type Error = ();
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, item: Bytes) -> Result<(), ()> {
+            self.tx.send(item).map_err(|_| ())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            if self.fail {
+                Poll::Ready(Err(()))
+            } else {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            self.poll_flush(cx)
+        }
+    }
+
+    struct ReconnectActor {
+        sink: SinkWrite<Bytes, FlakySink>,
+        errors: Rc<Cell<usize>>,
+    }
+
+    impl Actor for ReconnectActor {
+        type Context = actix::Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<()> for ReconnectActor {
+        fn error(&mut self, _: (), _: &mut Self::Context) -> Running {
+            self.errors.set(self.errors.get() + 1);
+            Running::Continue
+        }
+
+        fn finished(&mut self, ctx: &mut Self::Context) {
+            ctx.stop();
+        }
+    }
+
+    impl Handler<Data> for ReconnectActor {
+        type Result = ();
+
+        fn handle(&mut self, data: Data, _: &mut Self::Context) {
+            let _ = self.sink.write(data.bytes);
+            if data.last {
+                self.sink.close();
+            }
+        }
+    }
+
+    /// Starts an actor writing "a" then "b" to a sink that fails on first flush, returning the
+    /// items seen by the first and the replacement sink, and the number of reported errors.
+    async fn write_through_flaky_sink(replay: bool) -> (Vec<Bytes>, Vec<Bytes>, usize) {
+        let (first_tx, mut first_rx) = mpsc::unbounded_channel();
+        let (second_tx, mut second_rx) = mpsc::unbounded_channel();
+        let errors = Rc::new(Cell::new(0));
+
+        let actor_errors = Rc::clone(&errors);
+        let addr = ReconnectActor::create(move |ctx| {
+            let mut sink = SinkWrite::new(
+                FlakySink {
+                    tx: first_tx,
+                    fail: true,
+                },
+                ctx,
+            );
+
+            let factory = move || FlakySink {
+                tx: second_tx.clone(),
+                fail: false,
+            };
+            if replay {
+                sink.set_reconnect_replay(factory);
+            } else {
+                sink.set_reconnect(factory);
+            }
+
+            ReconnectActor {
+                sink,
+                errors: actor_errors,
+            }
+        });
+
+        addr.do_send(Data {
+            bytes: Bytes::from_static(b"a"),
+            last: false,
+        });
+
+        // "b" is written only once "a" went through the failing sink
+        let mut first = vec![first_rx.recv().await.unwrap()];
+        addr.do_send(Data {
+            bytes: Bytes::from_static(b"b"),
+            last: true,
+        });
+        drop(addr);
+
+        while let Some(item) = first_rx.recv().await {
+            first.push(item);
+        }
+        let mut second = Vec::new();
+        while let Some(item) = second_rx.recv().await {
+            second.push(item);
+        }
+
+        (first, second, errors.get())
+    }
+
+    #[actix::test]
+    async fn at_most_once() {
+        let (first, second, errors) = write_through_flaky_sink(false).await;
+
+        assert_eq!(first, vec![Bytes::from_static(b"a")]);
+        assert_eq!(second, vec![Bytes::from_static(b"b")]);
+        assert_eq!(errors, 1);
+    }
+
+    #[actix::test]
+    async fn at_least_once() {
+        let (first, second, errors) = write_through_flaky_sink(true).await;
+
+        assert_eq!(first, vec![Bytes::from_static(b"a")]);
+        assert_eq!(
+            second,
+            vec![Bytes::from_static(b"a"), Bytes::from_static(b"b")]
+        );
+        assert_eq!(errors, 1);
+    }
+
+    /// Sink that is never ready.
+    struct BrokenSink;
+
+    impl Sink<Bytes> for BrokenSink {
+        type Error = ();
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Err(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, _: Bytes) -> Result<(), ()> {
+            Err(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Err(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Err(()))
+        }
+    }
+
+    const MAX_ERRORS: usize = 1000;
+
+    struct Retrier {
+        _sink: SinkWrite<Bytes, BrokenSink>,
+        errors: usize,
+        halted: bool,
+        first_error: Option<oneshot::Sender<()>>,
+    }
+
+    impl Actor for Retrier {
+        type Context = actix::Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<()> for Retrier {
+        fn error(&mut self, _: (), _: &mut Self::Context) -> Running {
+            self.errors += 1;
+            if let Some(tx) = self.first_error.take() {
+                let _ = tx.send(());
+            }
+            if self.halted || self.errors >= MAX_ERRORS {
+                Running::Stop
+            } else {
+                Running::Continue
+            }
+        }
+    }
+
+    struct Halt;
+
+    impl Message for Halt {
+        type Result = usize;
+    }
+
+    impl Handler<Halt> for Retrier {
+        type Result = usize;
+
+        fn handle(&mut self, _: Halt, _: &mut Self::Context) -> usize {
+            self.halted = true;
+            self.errors
+        }
+    }
+
+    #[actix::test]
+    async fn failing_factory_does_not_block_arbiter() {
+        let (tx, rx) = oneshot::channel();
+        let addr = Retrier::create(|ctx| {
+            let mut sink = SinkWrite::new(BrokenSink, ctx);
+            sink.set_reconnect(|| BrokenSink);
+            Retrier {
+                _sink: sink,
+                errors: 0,
+                halted: false,
+                first_error: Some(tx),
+            }
+        });
+
+        // messages are still handled between reconnects
+        rx.await.unwrap();
+        let errors = addr.send(Halt).await.unwrap();
+        assert!(errors < MAX_ERRORS, "{} errors before halting", errors);
+    }
+}
+
+mod flush_and_wait {
+    use futures_util::FutureExt as _;
+
+    use super::*;
+
+    struct Replier {
+        sink: SinkWrite<Bytes, MySink>,
+    }
+
+    impl Actor for Replier {
+        type Context = actix::Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<()> for Replier {}
+
+    struct Reply(Bytes);
+
+    impl Message for Reply {
+        type Result = Result<(), MailboxError>;
+    }
+
+    impl Handler<Reply> for Replier {
+        type Result = ResponseFuture<Result<(), MailboxError>>;
+
+        fn handle(&mut self, msg: Reply, _: &mut Self::Context) -> Self::Result {
+            let _ = self.sink.write(msg.0);
+            Box::pin(self.sink.flush_and_wait())
+        }
+    }
+
+    /// Writes the bytes and gives up on the flush while it is still pending.
+    struct Abandon(Bytes);
+
+    impl Message for Abandon {
+        type Result = bool;
+    }
+
+    impl Handler<Abandon> for Replier {
+        type Result = bool;
+
+        fn handle(&mut self, msg: Abandon, _: &mut Self::Context) -> bool {
+            let _ = self.sink.write(msg.0);
+            self.sink.flush_and_wait().now_or_never().is_none()
+        }
+    }
+
+    fn start(sender: ByteSender) -> Addr<Replier> {
+        Replier::create(move |ctx| Replier {
+            sink: SinkWrite::new(
+                MySink {
+                    sender,
+                    queue: Vec::new(),
+                },
+                ctx,
+            ),
+        })
+    }
+
+    fn received(receiver: &mut mpsc::UnboundedReceiver<u8>) -> Vec<u8> {
+        let mut sent = Vec::new();
+        while let Ok(byte) = receiver.try_recv() {
+            sent.push(byte);
+        }
+        sent
+    }
+
+    #[actix::test]
+    async fn resolves_after_items_are_flushed() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let addr = start(sender);
+
+        let res = addr
+            .send(Reply(Bytes::from_static(b"Hello")))
+            .await
+            .unwrap();
+        assert_eq!(res, Ok(()));
+        assert_eq!(b"Hello", &received(&mut receiver)[..]);
+    }
+
+    #[actix::test]
+    async fn dropped_future_does_not_stall_writer() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let addr = start(sender);
+
+        let pending = addr.send(Abandon(Bytes::from_static(b"Hello"))).await;
+        assert_eq!(pending, Ok(true));
+
+        let res = addr
+            .send(Reply(Bytes::from_static(b" world")))
+            .await
+            .unwrap();
+        assert_eq!(res, Ok(()));
+        assert_eq!(b"Hello world", &received(&mut receiver)[..]);
+    }
+
+    #[actix::test]
+    async fn fails_if_writer_stops() {
+        let (sender, _receiver) = mpsc::unbounded_channel();
+        let addr = start(sender);
+
+        // the sink error is passed to the write handler, which stops the writer
+        let res = addr.send(Reply(Bytes::from_static(b"He#"))).await;
+        assert_eq!(res, Ok(Err(MailboxError::Closed)));
+    }
+}
+
+mod errored {
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    /// Sink that accepts everything and fails every flush if `fail` is set.
+    struct FailingSink {
+        fail: bool,
+    }
+
+    impl Sink<Bytes> for FailingSink {
+        type Error = ();
+
+        fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn start_send(self: Pin<&mut Self>, _: Bytes) -> Result<(), ()> {
+            Ok(())
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            Poll::Ready(if self.fail { Err(()) } else { Ok(()) })
+        }
+
+        fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), ()>> {
+            self.poll_flush(cx)
+        }
+    }
+
+    struct OutcomeActor {
+        sink: SinkWrite<Bytes, FailingSink>,
+        outcome: Option<oneshot::Sender<&'static str>>,
+    }
+
+    impl Actor for OutcomeActor {
+        type Context = actix::Context<Self>;
+    }
+
+    impl actix::io::WriteHandler<()> for OutcomeActor {
+        fn finished(&mut self, ctx: &mut Self::Context) {
+            let _ = self.outcome.take().unwrap().send("finished");
+            ctx.stop();
+        }
+
+        fn errored(&mut self, ctx: &mut Self::Context) {
+            let _ = self.outcome.take().unwrap().send("errored");
+            ctx.stop();
+        }
+    }
+
+    impl Handler<Data> for OutcomeActor {
+        type Result = ();
+
+        fn handle(&mut self, data: Data, _: &mut Self::Context) {
+            let _ = self.sink.write(data.bytes);
+            if data.last {
+                self.sink.close();
+            }
+        }
+    }
+
+    async fn outcome(fail: bool) -> &'static str {
+        let (tx, rx) = oneshot::channel();
+        let addr = OutcomeActor::create(move |ctx| OutcomeActor {
+            sink: SinkWrite::new(FailingSink { fail }, ctx),
+            outcome: Some(tx),
+        });
+
+        addr.do_send(Data {
+            bytes: Bytes::from_static(b"x"),
+            last: true,
+        });
+
+        rx.await.unwrap()
+    }
+
+    #[actix::test]
+    async fn clean_close_calls_finished() {
+        assert_eq!(outcome(false).await, "finished");
+    }
+
+    #[actix::test]
+    async fn error_calls_errored() {
+        assert_eq!(outcome(true).await, "errored");
+    }
+}
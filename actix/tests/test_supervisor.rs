@@ -70,3 +70,137 @@ fn test_supervisor_restart() {
     assert_eq!(restarts.load(Ordering::Relaxed), 2);
     assert_eq!(messages.load(Ordering::Relaxed), 2);
 }
+
+struct SlowStart {
+    starts: Arc<AtomicUsize>,
+    restarts: Arc<AtomicUsize>,
+}
+
+impl Actor for SlowStart {
+    type Context = Context<Self>;
+
+    fn started(&mut self, ctx: &mut Self::Context) {
+        // the first start never finishes its initialization
+        if self.starts.fetch_add(1, Ordering::SeqCst) == 0 {
+            ctx.wait(futures_util::future::pending::<()>().into_actor(self));
+        }
+    }
+}
+
+impl Supervised for SlowStart {
+    fn restarting(&mut self, _: &mut Self::Context) {
+        self.restarts.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+struct Starts;
+
+impl Message for Starts {
+    type Result = usize;
+}
+
+impl Handler<Starts> for SlowStart {
+    type Result = usize;
+
+    fn handle(&mut self, _: Starts, _: &mut Self::Context) -> usize {
+        self.starts.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn test_supervisor_start_timeout() {
+    System::new().block_on(async {
+        let restarts = Arc::new(AtomicUsize::new(0));
+        let restarts2 = Arc::clone(&restarts);
+        let addr = Supervisor::start_with_timeout(Duration::from_millis(20), move |_| SlowStart {
+            starts: Arc::new(AtomicUsize::new(0)),
+            restarts: restarts2,
+        });
+
+        assert_eq!(addr.send(Starts).await.unwrap(), 2);
+        assert_eq!(restarts.load(Ordering::SeqCst), 1);
+
+        // a ready actor is not restarted by the timeout
+        sleep(Duration::from_millis(40)).await;
+        assert_eq!(addr.send(Starts).await.unwrap(), 2);
+        assert_eq!(restarts.load(Ordering::SeqCst), 1);
+    });
+}
+
+mod supervisor_tree {
+    use actix::{SupervisionStrategy, SupervisorTree};
+
+    use super::*;
+
+    struct Child(Arc<AtomicUsize>);
+
+    impl Actor for Child {
+        type Context = Context<Self>;
+    }
+
+    impl actix::Supervised for Child {
+        fn restarting(&mut self, _: &mut Context<Self>) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    impl Handler<Die> for Child {
+        type Result = ();
+
+        fn handle(&mut self, _: Die, ctx: &mut Context<Self>) {
+            ctx.stop();
+        }
+    }
+
+    /// Starts three children, fails the middle one and returns the restart count of each.
+    fn restarts_after_failure(strategy: SupervisionStrategy) -> Vec<usize> {
+        let restarts = vec![
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+            Arc::new(AtomicUsize::new(0)),
+        ];
+
+        System::new().block_on({
+            let restarts = restarts.clone();
+            async move {
+                let tree = SupervisorTree::new(strategy);
+                let children = restarts
+                    .into_iter()
+                    .map(|count| tree.start_child(move |_| Child(count)))
+                    .collect::<Vec<_>>();
+
+                children[1].do_send(Die);
+                sleep(Duration::from_millis(20)).await;
+            }
+        });
+
+        restarts
+            .iter()
+            .map(|count| count.load(Ordering::SeqCst))
+            .collect()
+    }
+
+    #[test]
+    fn one_for_one() {
+        assert_eq!(
+            restarts_after_failure(SupervisionStrategy::OneForOne),
+            [0, 1, 0]
+        );
+    }
+
+    #[test]
+    fn one_for_all() {
+        assert_eq!(
+            restarts_after_failure(SupervisionStrategy::OneForAll),
+            [1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn rest_for_one() {
+        assert_eq!(
+            restarts_after_failure(SupervisionStrategy::RestForOne),
+            [0, 1, 1]
+        );
+    }
+}
@@ -1,9 +1,15 @@
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc, Condvar, Mutex,
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    thread,
+    time::Duration,
 };
 
 use actix::prelude::*;
+use futures_util::future::join_all;
 
 struct Fibonacci(pub u32);
 
@@ -100,3 +106,253 @@ fn test_sync() {
         "Wrong number of messages"
     );
 }
+
+struct Work;
+
+impl Message for Work {
+    type Result = thread::ThreadId;
+}
+
+struct Crash;
+
+impl Message for Crash {
+    type Result = ();
+}
+
+struct Worker;
+
+impl Actor for Worker {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Work> for Worker {
+    type Result = MessageResult<Work>;
+
+    fn handle(&mut self, _: Work, _: &mut Self::Context) -> Self::Result {
+        thread::sleep(Duration::from_millis(20));
+        MessageResult(thread::current().id())
+    }
+}
+
+impl Handler<Crash> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, _: Crash, _: &mut Self::Context) {
+        panic!("worker crashed");
+    }
+}
+
+#[test]
+fn test_sync_dispatches_across_workers() {
+    System::new().block_on(async {
+        let addr = SyncArbiter::start(4, || Worker);
+
+        let threads: HashSet<_> = join_all((0..8).map(|_| addr.send(Work)))
+            .await
+            .into_iter()
+            .map(Result::unwrap)
+            .collect();
+
+        assert!(threads.len() > 1, "all messages handled by one worker");
+    });
+}
+
+#[test]
+fn test_sync_worker_panic_does_not_stop_others() {
+    System::new().block_on(async {
+        let addr = SyncArbiter::start(2, || Worker);
+
+        assert_eq!(addr.send(Crash).await, Err(MailboxError::Closed));
+
+        for res in join_all((0..4).map(|_| addr.send(Work))).await {
+            assert!(res.is_ok());
+        }
+    });
+}
+
+struct WriteFile(std::path::PathBuf);
+
+impl Message for WriteFile {
+    type Result = std::io::Result<usize>;
+}
+
+struct Restart;
+
+impl Message for Restart {
+    type Result = ();
+}
+
+#[derive(Default)]
+struct FileWriter {
+    written: usize,
+}
+
+impl Actor for FileWriter {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<WriteFile> for FileWriter {
+    type Result = std::io::Result<usize>;
+
+    fn handle(&mut self, msg: WriteFile, _: &mut Self::Context) -> Self::Result {
+        // plain blocking IO, no runtime needed on the worker thread
+        std::fs::write(&msg.0, b"actix")?;
+        self.written += std::fs::read(&msg.0)?.len();
+        std::fs::remove_file(&msg.0)?;
+        Ok(self.written)
+    }
+}
+
+impl Handler<Restart> for FileWriter {
+    type Result = ();
+
+    fn handle(&mut self, _: Restart, ctx: &mut Self::Context) {
+        ctx.stop();
+    }
+}
+
+#[test]
+fn test_sync_context_blocking_handler() {
+    System::new().block_on(async {
+        let addr = SyncArbiter::start(1, FileWriter::default);
+        let path = std::env::temp_dir().join(format!("actix-sync-{}", std::process::id()));
+
+        assert_eq!(
+            addr.send(WriteFile(path.clone())).await.unwrap().unwrap(),
+            5
+        );
+        assert_eq!(
+            addr.send(WriteFile(path.clone())).await.unwrap().unwrap(),
+            10
+        );
+
+        // stopping a sync actor replaces it with a fresh instance
+        addr.send(Restart).await.unwrap();
+        assert_eq!(addr.send(WriteFile(path)).await.unwrap().unwrap(), 5);
+    });
+}
+
+struct Heavy;
+
+impl Message for Heavy {
+    type Result = ();
+}
+
+struct Light;
+
+impl Message for Light {
+    type Result = ();
+}
+
+#[derive(Default)]
+struct Load {
+    running: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl Load {
+    fn run(&self, dur: Duration) {
+        let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(running, Ordering::SeqCst);
+        thread::sleep(dur);
+        self.running.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+struct Pool {
+    heavy: Arc<Load>,
+    light: Arc<Load>,
+}
+
+impl Actor for Pool {
+    type Context = SyncContext<Self>;
+}
+
+impl Handler<Heavy> for Pool {
+    type Result = ();
+
+    fn handle(&mut self, _: Heavy, _: &mut Self::Context) {
+        self.heavy.run(Duration::from_millis(50));
+    }
+}
+
+impl Handler<Light> for Pool {
+    type Result = ();
+
+    fn handle(&mut self, _: Light, _: &mut Self::Context) {
+        self.light.run(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn test_sync_concurrency_limits() {
+    let heavy = Arc::new(Load::default());
+    let light = Arc::new(Load::default());
+
+    System::new().block_on({
+        let heavy = Arc::clone(&heavy);
+        let light = Arc::clone(&light);
+
+        async move {
+            let limits = actix::sync::ConcurrencyLimits::new().limit::<Heavy>(2);
+            let addr = SyncArbiter::start_with_limits(6, limits, {
+                let heavy = Arc::clone(&heavy);
+                move || Pool {
+                    heavy: Arc::clone(&heavy),
+                    light: Arc::clone(&light),
+                }
+            });
+
+            // requests are queued right away, heavy jobs first
+            let heavy_jobs: Vec<_> = (0..6).map(|_| addr.send(Heavy)).collect();
+            let light_jobs: Vec<_> = (0..4).map(|_| addr.send(Light)).collect();
+
+            for res in join_all(light_jobs).await {
+                assert!(res.is_ok());
+            }
+            assert!(heavy.running.load(Ordering::SeqCst) > 0);
+
+            for res in join_all(heavy_jobs).await {
+                assert!(res.is_ok());
+            }
+        }
+    });
+
+    assert_eq!(heavy.peak.load(Ordering::SeqCst), 2);
+    assert!(light.peak.load(Ordering::SeqCst) > 2);
+}
+
+struct Risky {
+    crash: bool,
+}
+
+impl Message for Risky {
+    type Result = ();
+}
+
+impl Handler<Risky> for Worker {
+    type Result = ();
+
+    fn handle(&mut self, msg: Risky, _: &mut Self::Context) {
+        thread::sleep(Duration::from_millis(20));
+        if msg.crash {
+            panic!("worker crashed");
+        }
+    }
+}
+
+#[test]
+fn test_sync_concurrency_limit_survives_panic() {
+    System::new().block_on(async {
+        let limits = actix::sync::ConcurrencyLimits::new().limit::<Risky>(1);
+        let addr = SyncArbiter::start_with_limits(3, limits, || Worker);
+
+        // the second message waits for the slot of the panicking one
+        let crash = addr.send(Risky { crash: true });
+        let queued = addr.send(Risky { crash: false });
+
+        assert_eq!(crash.await, Err(MailboxError::Closed));
+        let res = actix_rt::time::timeout(Duration::from_secs(5), queued).await;
+        assert_eq!(res.expect("queued message was not handled"), Ok(()));
+    });
+}
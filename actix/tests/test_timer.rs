@@ -0,0 +1,104 @@
+#![cfg(feature = "macros")]
+
+use std::time::Duration;
+
+use actix::{
+    actors::timer::{Cancel, ScheduleOnce, ScheduleRepeating, TimerActor},
+    prelude::*,
+};
+use actix_rt::time::sleep;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone, PartialEq, Message)]
+#[rtype(result = "()")]
+struct Tick(u32);
+
+struct Listener(mpsc::UnboundedSender<Tick>);
+
+impl Actor for Listener {
+    type Context = Context<Self>;
+}
+
+impl Handler<Tick> for Listener {
+    type Result = ();
+
+    fn handle(&mut self, msg: Tick, _: &mut Self::Context) {
+        let _ = self.0.send(msg);
+    }
+}
+
+#[actix::test]
+async fn one_shot() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let timer = TimerActor::new().start();
+
+    let handle = timer
+        .send(ScheduleOnce {
+            recipient: Listener(tx).start().recipient(),
+            msg: Tick(1),
+            delay: Duration::from_millis(30),
+        })
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(10)).await;
+    assert!(rx.try_recv().is_err());
+
+    assert_eq!(rx.recv().await, Some(Tick(1)));
+
+    // fired timers can no longer be cancelled
+    assert!(!timer.send(Cancel(handle)).await.unwrap());
+    sleep(Duration::from_millis(50)).await;
+    assert!(rx.try_recv().is_err());
+}
+
+#[actix::test]
+async fn repeating() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let timer = TimerActor::new().start();
+
+    let handle = timer
+        .send(ScheduleRepeating {
+            recipient: Listener(tx).start().recipient(),
+            msg: Tick(2),
+            interval: Duration::from_millis(10),
+        })
+        .await
+        .unwrap();
+
+    for _ in 0..3 {
+        assert_eq!(rx.recv().await, Some(Tick(2)));
+    }
+    assert!(timer.send(Cancel(handle)).await.unwrap());
+}
+
+#[actix::test]
+async fn cancellation() {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let timer = TimerActor::from_registry();
+    let listener = Listener(tx).start().recipient();
+
+    let once = timer
+        .send(ScheduleOnce {
+            recipient: listener.clone(),
+            msg: Tick(3),
+            delay: Duration::from_millis(20),
+        })
+        .await
+        .unwrap();
+    let repeating = timer
+        .send(ScheduleRepeating {
+            recipient: listener,
+            msg: Tick(4),
+            interval: Duration::from_millis(20),
+        })
+        .await
+        .unwrap();
+
+    assert!(timer.send(Cancel(once)).await.unwrap());
+    assert!(timer.send(Cancel(repeating)).await.unwrap());
+    assert!(!timer.send(Cancel(once)).await.unwrap());
+
+    sleep(Duration::from_millis(60)).await;
+    assert!(rx.try_recv().is_err());
+}